@@ -4,6 +4,7 @@
 // Apache License text: https://www.apache.org/licenses/LICENSE-2.0
 // MIT License text: https://opensource.org/licenses/MIT
 
+use crate::CONFIG;
 use crate::rpc::{self, launcher};
 use falcon_cli::*;
 
@@ -20,18 +21,23 @@ impl CliCommand for CliDbClose {
         // Send RPC
         let _ = rpc::send::<String, bool>("db.close", &vec![]);
 
-        cli_info!("Closed Nyx database.");
+        match &CONFIG.db_alias {
+            Some(alias) => cli_info!("Closed Nyx database {}.", alias),
+            None => cli_info!("Closed Nyx database."),
+        }
         Ok(())
     }
 
     fn help(&self) -> CliHelpScreen {
         let mut help = CliHelpScreen::new(
             "Close Nyx Database",
-            "nyx db close",
-            "Closes any currently open Nyx database",
+            "nyx db close [-D <ALIAS>]",
+            "Closes the database open under the given alias, or all open databases if no alias is given.",
         );
 
+        help.add_param("ALIAS", "Optional alias of database to close.");
         help.add_example("nyx db close");
+        help.add_example("nyx -D team db close");
         help
     }
 }
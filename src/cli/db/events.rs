@@ -0,0 +1,74 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::rpc::{self, RpcEvent, launcher};
+use chrono::{DateTime, Utc};
+use falcon_cli::*;
+
+#[derive(Default)]
+pub struct CliDbEvents {}
+
+impl CliCommand for CliDbEvents {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        if !launcher::ping() {
+            cli_info!("No Nyx database is currently open.");
+            return Ok(());
+        }
+        let as_json = req.has_flag("--json");
+
+        // Print events as they arrive, until daemon exits
+        rpc::subscribe(|event| {
+            if as_json {
+                println!("{}", serde_json::to_string(&event).unwrap_or_default());
+            } else {
+                println!("{}", format_event(&event));
+            }
+            true
+        })?;
+
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Watch Events",
+            "nyx db events [--json]",
+            "Displays lock, unlock, clipboard and entry change events from the RPC daemon as they happen, until it exits or Ctrl+C is pressed.  Events never include secret values.",
+        );
+
+        help.add_flag("--json", "Output each event as a single line of JSON.");
+        help.add_example("nyx db events");
+        help.add_example("nyx db events --json");
+        help
+    }
+}
+
+/// Format event as a single line
+fn format_event(event: &RpcEvent) -> String {
+    let time_str = DateTime::<Utc>::from_timestamp(event.timestamp as i64, 0)
+        .map(|dt| dt.format("%b %d, %Y %H:%M:%S").to_string())
+        .unwrap_or_default();
+
+    let details = match event.event.as_str() {
+        "unlock" => "Unlocked".to_string(),
+        "lock" => format!("Locked ({})", event.reason.as_deref().unwrap_or("closed")),
+        "clipboard_cleared" => "Clipboard cleared".to_string(),
+        action => {
+            let mut details = format!(
+                "{} {} {}",
+                action,
+                event.data_type.as_deref().unwrap_or_default(),
+                event.name.as_deref().unwrap_or_default()
+            );
+            if let Some(dest) = &event.dest {
+                details.push_str(&format!(" -> {}", dest));
+            }
+            details
+        }
+    };
+
+    format!("{}  [{}] {}", time_str, event.db, details)
+}
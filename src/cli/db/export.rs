@@ -0,0 +1,108 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::database::{ExportDump, ExportFormat};
+use crate::rpc;
+use crate::security::crypto;
+use falcon_cli::*;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use zeroize::Zeroize;
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+#[derive(Default)]
+pub struct CliDbExport {}
+
+impl CliCommand for CliDbExport {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check flags
+        let format_str =
+            req.get_flag("--format").ok_or(CliError::MissingFlag("--format".to_string()))?;
+        let format = ExportFormat::from_str(&format_str)?;
+        let filename = req.get_flag("--file").ok_or(CliError::MissingFlag("--file".to_string()))?;
+        let encrypt = req.has_flag("--encrypt") && format != ExportFormat::Kdbx;
+
+        // Check destination
+        if Path::new(&filename).exists()
+            && !cli_confirm(&format!("File already exists at {}, overwrite?", filename))
+        {
+            return Ok(());
+        }
+
+        // Get passphrase, or confirm plaintext output
+        cli_header(&format!("Export Database to {}", format));
+        let mut password = String::new();
+        if format == ExportFormat::Kdbx {
+            cli_info!("Enter the master password to protect the KeePass database with.\n");
+            password = cli_get_new_password(0);
+        } else if encrypt {
+            cli_info!(
+                "Enter the passphrase to encrypt the export with.  The export can be restored with:  nyx db import --format json <FILE>\n"
+            );
+            password = cli_get_new_password(0);
+        } else {
+            cli_warn!(
+                "The export will contain all passwords, OTP secrets and private SSH keys in plaintext.  Use --encrypt to protect it with a passphrase instead.\n"
+            );
+            if !cli_confirm("Write unencrypted export?") {
+                cli_info!("Export cancelled.");
+                return Ok(());
+            }
+        }
+
+        // Render export
+        let dump: ExportDump = rpc::send::<String, ExportDump>("db.export", &vec![])?;
+        let mut output = dump.render(format, &password)?;
+        if encrypt {
+            let encrypted = crypto::encrypt(&output, crypto::normalize_password(&password));
+            output.zeroize();
+            output = encrypted?;
+        }
+        password.zeroize();
+
+        // Check parent dir
+        if let Some(parent) = Path::new(&filename).parent()
+            && !parent.as_os_str().is_empty()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Save file, readable by owner only
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let result = options.open(&filename).and_then(|mut file| file.write_all(&output));
+        output.zeroize();
+        result?;
+
+        cli_info!("Exported {} entries to {}", dump.len(), filename);
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Export Database",
+            "nyx db export --format <FORMAT> --file <PATH> [--encrypt]",
+            "Exports all users, OTP entries, SSH keys, strings and notes.  The json format is a complete dump of the database, which can be restored with 'nyx db import --format json', while the bitwarden and kdbx formats can be imported into Bitwarden and KeePass.  KeePass exports are always encrypted with a new master password, other formats are written in plaintext after confirmation unless --encrypt is specified.",
+        );
+
+        help.add_flag("--format", "One of: json, bitwarden, kdbx");
+        help.add_flag("--file", "Location to save the export to.");
+        help.add_flag(
+            "--encrypt",
+            "Encrypt the export with a separate passphrase.",
+        );
+        help.add_example("nyx db export --format json --file ~/nyx-export.json --encrypt");
+        help.add_example("nyx db export --format kdbx --file ~/passwords.kdbx");
+        help
+    }
+}
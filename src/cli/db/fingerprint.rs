@@ -0,0 +1,40 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::CONFIG;
+use crate::rpc::tls;
+use falcon_cli::*;
+
+#[derive(Default)]
+pub struct CliDbFingerprint {}
+
+impl CliCommand for CliDbFingerprint {
+    fn process(&self, _req: &CliRequest) -> anyhow::Result<()> {
+        let fingerprint = tls::local_fingerprint()?;
+
+        cli_info!(
+            "SHA-256 fingerprint of TLS certificate at {}:\n",
+            CONFIG.tls_cert
+        );
+
+        // Printed directly, as cli_send! word wraps long lines
+        println!("    {}\n", fingerprint);
+        cli_info!("Pin it when connecting from another machine with --tls-fingerprint.");
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "TLS Certificate Fingerprint",
+            "nyx db fingerprint",
+            "Displays the SHA-256 fingerprint of the certificate used when serving RPC over TLS, generating a self-signed certificate if one does not yet exist.  Remote clients pin this fingerprint via --tls-fingerprint.",
+        );
+
+        help.add_example("nyx db fingerprint");
+        help.add_example("nyx --tls-cert server.pem --tls-key server.key db fingerprint");
+        help
+    }
+}
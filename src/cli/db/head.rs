@@ -0,0 +1,52 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::database::HistoryHead;
+use crate::rpc;
+use falcon_cli::*;
+use std::fs;
+
+#[derive(Default)]
+pub struct CliDbHistoryHead {}
+
+impl CliCommand for CliDbHistoryHead {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        let head: HistoryHead = rpc::send::<String, HistoryHead>("db.history_head", &vec![])?;
+
+        // Export for third party
+        if let Some(filename) = req.get_flag("--file") {
+            fs::write(&filename, serde_json::to_string_pretty(&head)?)?;
+            cli_info!(
+                "Exported head of history chain, entry {}, to {}\n",
+                head.seq,
+                filename
+            );
+            return Ok(());
+        }
+
+        cli_info!("Head of history chain is entry {}, with hash:\n", head.seq);
+
+        // Printed directly, as cli_send! word wraps long lines
+        println!("    {}\n", head.hash);
+        cli_info!(
+            "Check it is still part of the history later with:  nyx db history verify --head <FILE>"
+        );
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "History Chain Head",
+            "nyx db history head [--file <FILE>]",
+            "Displays the sequence number and hash of the newest history entry, which every earlier entry is chained to.  Hand the exported file to a third party to attest to, and any later rewrite of the history up to that point will fail verification against it.",
+        );
+
+        help.add_flag("--file", "Optional location to export the head to as JSON.");
+        help.add_example("nyx db history head");
+        help.add_example("nyx db history head --file ~/nyx-head.json");
+        help
+    }
+}
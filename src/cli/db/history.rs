@@ -5,9 +5,9 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 use crate::Error;
-use crate::database::HistoryItem;
+use crate::database::{HistoryItem, HistoryRetention};
 use crate::rpc;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use falcon_cli::*;
 
 #[derive(Default)]
@@ -15,7 +15,12 @@ pub struct CliDbHistory {}
 
 impl CliCommand for CliDbHistory {
     fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
-        let n = if let Some(n_str) = req.get_flag("n") {
+        // Set retention limits
+        if req.has_flag("--keep") || req.has_flag("--keep-days") {
+            return self.retention(req);
+        }
+
+        let n = if let Some(n_str) = req.get_flag("-n") {
             n_str
                 .parse::<usize>()
                 .map_err(|e| Error::Validate(format!("Invalid start number: {}", e)))?
@@ -23,8 +28,28 @@ impl CliCommand for CliDbHistory {
             0
         };
 
+        // Get filters
+        let since = match req.get_flag("--since") {
+            Some(date) => parse_date(&date, NaiveTime::MIN)?.to_string(),
+            None => String::new(),
+        };
+        let until = match req.get_flag("--until") {
+            Some(date) => {
+                parse_date(&date, NaiveTime::from_hms_opt(23, 59, 59).unwrap())?.to_string()
+            }
+            None => String::new(),
+        };
+        let params = vec![
+            n.to_string(),
+            req.get_flag("--type").unwrap_or_default(),
+            req.get_flag("--action").unwrap_or_default(),
+            req.get_flag("--name").unwrap_or_default(),
+            since,
+            until,
+        ];
+
         // Send RPC
-        let entries: Vec<HistoryItem> = rpc::send("db.history", &vec![n])?;
+        let entries: Vec<HistoryItem> = rpc::send("db.history", &params)?;
 
         // Get table rows
         let rows = entries
@@ -42,25 +67,83 @@ impl CliCommand for CliDbHistory {
                     item.data_type.to_string(),
                     item.source.to_string(),
                     item.dest.to_string(),
+                    item.client.to_string(),
                 ]
             })
             .collect::<Vec<Vec<String>>>();
 
         // Display table
         cli_header("History");
-        cli_display_table(&["#", "Date", "Action", "Type", "Source", "Target"], &rows);
+        cli_display_table(
+            &["#", "Date", "Action", "Type", "Source", "Target", "Client"],
+            &rows,
+        );
         Ok(())
     }
 
     fn help(&self) -> CliHelpScreen {
         let mut help = CliHelpScreen::new(
-            "List Notes",
-            "nyx note ls [<DIRNAME>]",
-            "Lists all notes within directory in alphabetical order.",
+            "History Log",
+            "nyx db history [-n <START>] [--type <TYPE>] [--action <ACTION>] [--name <PREFIX>] [--since <DATE>] [--until <DATE>]",
+            "Lists the audit log of the database newest first, 25 entries at a time.  Records changes, reads, copies to clipboard, OTP generations and unlock attempts along with the client that made them.  Use the --keep and --keep-days flags to limit how many entries are kept.",
         );
 
-        help.add_param("DIRNAME", "Optional directory name to list entries from.");
-        help.add_example("nyx note ls mysite");
+        help.add_flag("-n", "Number of entries to skip, defaults to 0.");
+        help.add_flag(
+            "--type",
+            "Only list entries of data type (user, otp, ssh, str, note, db).",
+        );
+        help.add_flag("--action", "Only list entries of action (create, update, delete, copy, rename, read, clipboard, generate, unlock, unlock-failed).");
+        help.add_flag("--name", "Only list entries whose name starts with prefix.");
+        help.add_flag(
+            "--since",
+            "Only list entries on or after date, formatted as YYYY-MM-DD.",
+        );
+        help.add_flag(
+            "--until",
+            "Only list entries on or before date, formatted as YYYY-MM-DD.",
+        );
+        help.add_flag(
+            "--keep",
+            "Maximum number of entries kept, 0 for unlimited.  Defaults to 10000.",
+        );
+        help.add_flag(
+            "--keep-days",
+            "Maximum age in days of entries kept, 0 for unlimited.  Defaults to 0.",
+        );
+        help.add_example("nyx db history --type user --action clipboard");
+        help.add_example("nyx db history --name mysite/ --since 2025-06-01");
+        help.add_example("nyx db history --keep 5000 --keep-days 365");
         help
     }
 }
+
+impl CliDbHistory {
+    /// Set maximum count and age of entries kept
+    fn retention(&self, req: &CliRequest) -> anyhow::Result<()> {
+        let params = vec![
+            req.get_flag("--keep").unwrap_or_default(),
+            req.get_flag("--keep-days").unwrap_or_default(),
+        ];
+        let retention: HistoryRetention = rpc::send("db.history_retention", &params)?;
+
+        let count = match retention.max_items {
+            0 => "unlimited entries".to_string(),
+            max => format!("up to {} entries", max),
+        };
+        let age = match retention.max_days {
+            0 => "of any age".to_string(),
+            max => format!("from the last {} days", max),
+        };
+        cli_info!("History log now keeps {}, {}.\n", count, age);
+        Ok(())
+    }
+}
+
+/// Parse YYYY-MM-DD date into timestamp at time of day
+fn parse_date(date: &str, time: NaiveTime) -> Result<i64, Error> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| {
+        Error::Validate(format!("Invalid date {}, expected YYYY-MM-DD: {}", date, e))
+    })?;
+    Ok(date.and_time(time).and_utc().timestamp())
+}
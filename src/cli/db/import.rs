@@ -0,0 +1,127 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::database::{ImportBundle, ImportFormat, ImportSummary};
+use crate::rpc;
+use falcon_cli::*;
+use serde_json::Value;
+use std::fs;
+use std::str::FromStr;
+use zeroize::Zeroize;
+
+#[derive(Default)]
+pub struct CliDbImport {}
+
+impl CliCommand for CliDbImport {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check params
+        if req.args.is_empty() {
+            cli_error!("You did not specify a file to import.\n");
+            cli_info!(
+                "    Usage: nyx db import --format <json|kdbx|bitwarden|1password|csv> <FILE> [--dry-run]\n"
+            );
+            return Err(CliError::MissingParams.into());
+        }
+        req.validate_params(vec![CliFormat::File])?;
+        let format_str =
+            req.get_flag("--format").ok_or(CliError::MissingFlag("--format".to_string()))?;
+        let format = ImportFormat::from_str(&format_str)?;
+
+        // Get password, if needed
+        let data = fs::read(&req.args[0])?;
+        let mut password = String::new();
+        if format == ImportFormat::Kdbx {
+            password = cli_get_password("KeePass Password: ", false);
+        } else if ImportBundle::needs_password(format, &data) {
+            password = cli_get_password("Export Passphrase: ", false);
+        }
+
+        // Parse file
+        let bundle = ImportBundle::parse(format, &data, &password);
+        password.zeroize();
+        let bundle = bundle?;
+
+        for warning in bundle.warnings.iter() {
+            cli_warn!("Skipping, {}\n", warning);
+        }
+        if bundle.is_empty() {
+            return Err(
+                CliError::Generic(format!("No entries found within {} export.", format)).into(),
+            );
+        }
+        let bundle_value = serde_json::to_value(&bundle)
+            .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
+
+        // Dry run
+        let summary: ImportSummary = rpc::send(
+            "db.import",
+            &vec![bundle_value.clone(), Value::from("dry-run")],
+        )?;
+        cli_header(&format!("Import from {}", format));
+        self.display_summary(&summary);
+
+        if summary.total() == 0 {
+            cli_info!("All entries already exist, nothing to import.");
+            return Ok(());
+        } else if req.has_flag("--dry-run") {
+            cli_info!("Dry run, no changes were made to the database.");
+            return Ok(());
+        } else if !cli_confirm(&format!("Import {} entries?", summary.total())) {
+            cli_info!("Import cancelled.");
+            return Ok(());
+        }
+
+        // Import
+        let summary: ImportSummary = rpc::send("db.import", &vec![bundle_value, Value::from("")])?;
+        cli_info!("Imported {} entries from {}", summary.total(), format);
+
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Import Database",
+            "nyx db import --format <FORMAT> <FILE> [--dry-run]",
+            "Imports entries from another password manager.  Logins are imported as users, TOTP seeds as OTP entries, secure notes as notes, and attached SSH keys as SSH keys.  Folders are preserved as slash separated categories, and entries that already exist are skipped.  A summary is displayed and confirmation requested before anything is saved.",
+        );
+
+        help.add_param("FILE", "The exported file to import.");
+        help.add_flag(
+            "--format",
+            "One of: json (Nyx export), kdbx (KeePass 4), bitwarden (unencrypted JSON), 1password (CSV), csv",
+        );
+        help.add_flag(
+            "--dry-run",
+            "Only display the summary, do not import anything.",
+        );
+        help.add_example("nyx db import --format kdbx ~/passwords.kdbx");
+        help.add_example("nyx db import --format bitwarden bitwarden_export.json --dry-run");
+        help
+    }
+}
+
+impl CliDbImport {
+    /// Display summary of entries to import
+    fn display_summary(&self, summary: &ImportSummary) {
+        let data = indexmap! {
+            "Users: " => summary.users.to_string(),
+            "OTP: " => summary.oauth.to_string(),
+            "SSH Keys: " => summary.ssh_keys.to_string(),
+            "Strings: " => summary.strings.to_string(),
+            "Notes: " => summary.notes.to_string(),
+            "Skipped: " => format!("{} already exist", summary.skipped.len())
+        };
+        cli_display_array(&data);
+
+        if !summary.skipped.is_empty() {
+            cli_info!("\nThe following entries already exist and will be skipped:\n");
+            for name in summary.skipped.iter() {
+                cli_info!("    {}", name);
+            }
+        }
+        cli_info!("");
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::rpc::{self, OpenDbInfo, launcher};
+use chrono::{DateTime, Utc};
+use falcon_cli::*;
+
+#[derive(Default)]
+pub struct CliDbList {}
+
+impl CliCommand for CliDbList {
+    fn process(&self, _req: &CliRequest) -> anyhow::Result<()> {
+        if !launcher::ping() {
+            cli_info!("No Nyx database is currently open.");
+            return Ok(());
+        }
+
+        // Send RPC
+        let databases: Vec<OpenDbInfo> = rpc::send::<String, Vec<OpenDbInfo>>("db.list", &vec![])?;
+
+        // Get table rows
+        let rows = databases
+            .iter()
+            .map(|info| {
+                let locks_at = match info.expires_at {
+                    Some(secs) => DateTime::<Utc>::from_timestamp(secs as i64, 0)
+                        .map(|dt| dt.format("%b %d, %Y %H:%M:%S").to_string())
+                        .unwrap_or_default(),
+                    None => "Never".to_string(),
+                };
+                vec![info.alias.to_string(), info.dbfile.to_string(), locks_at]
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        // Display table
+        cli_header("Open Databases");
+        cli_display_table(&["Alias", "File", "Locks At"], &rows);
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "List Open Databases",
+            "nyx db ls",
+            "Lists all databases currently open within the RPC daemon, along with their alias and when they lock.",
+        );
+
+        help.add_example("nyx db ls");
+        help
+    }
+}
@@ -8,16 +8,32 @@ pub use self::backup::CliDbBackup;
 pub use self::changepass::CliDbChangePass;
 pub use self::close::CliDbClose;
 pub use self::create::CliDbCreate;
+pub use self::events::CliDbEvents;
+pub use self::export::CliDbExport;
+pub use self::fingerprint::CliDbFingerprint;
+pub use self::head::CliDbHistoryHead;
 pub use self::history::CliDbHistory;
+pub use self::import::CliDbImport;
+pub use self::list::CliDbList;
 pub use self::open::CliDbOpen;
 pub use self::restore::CliDbRestore;
+pub use self::retention::CliDbRetention;
 pub use self::stats::CliDbStats;
+pub use self::verify::CliDbHistoryVerify;
 
 mod backup;
 mod changepass;
 mod close;
 mod create;
+mod events;
+mod export;
+mod fingerprint;
+mod head;
 mod history;
+mod import;
+mod list;
 mod open;
 mod restore;
+mod retention;
 mod stats;
+mod verify;
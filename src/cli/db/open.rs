@@ -4,6 +4,7 @@
 // Apache License text: https://www.apache.org/licenses/LICENSE-2.0
 // MIT License text: https://opensource.org/licenses/MIT
 
+use crate::CONFIG;
 use crate::database::loader;
 use crate::rpc::{self, launcher};
 use falcon_cli::*;
@@ -13,7 +14,7 @@ pub struct CliDbOpen {}
 
 impl CliCommand for CliDbOpen {
     fn process(&self, _req: &CliRequest) -> anyhow::Result<()> {
-        if launcher::ping() {
+        if CONFIG.db_alias.is_none() && launcher::ping() {
             cli_info!("A Nyx database is currently open, closing...");
             let _ = rpc::send::<String, bool>("db.close", &vec![]);
         }
@@ -26,8 +27,8 @@ impl CliCommand for CliDbOpen {
             }
         };
 
-        if let Err(e) = launcher::launch(&dbfile, n_password) {
-            cli_error!("Unable to launch RPC daemon: {}", e);
+        if let Err(e) = launcher::open(&dbfile, n_password) {
+            cli_error!("Unable to open database within RPC daemon: {}", e);
             return Ok(());
         }
 
@@ -38,12 +39,14 @@ impl CliCommand for CliDbOpen {
     fn help(&self) -> CliHelpScreen {
         let mut help = CliHelpScreen::new(
             "Open Nyx Database",
-            "nyx db open [-f <DBFILE>]",
-            "Opens a Nyx database",
+            "nyx db open [-f <DBFILE>] [-D <ALIAS>]",
+            "Opens a Nyx database.  Without -D any open databases are closed first, otherwise the database is opened alongside them under the given alias.",
         );
 
         help.add_param("DBFILE", "Optional location of database file to open.");
+        help.add_param("ALIAS", "Optional alias to open database under, selected in later commands via -D or an ALIAS: prefix.");
         help.add_example("nyx db open");
+        help.add_example("nyx -D team -f ~/team.db db open");
         help
     }
 }
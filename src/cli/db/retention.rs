@@ -0,0 +1,49 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::Error;
+use crate::rpc;
+use falcon_cli::*;
+
+#[derive(Default)]
+pub struct CliDbRetention {}
+
+impl CliCommand for CliDbRetention {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Display current count
+        if req.args.is_empty() {
+            let count: u32 = rpc::send::<String, u32>("db.retention", &vec![])?;
+            cli_info!("Keeping {} prior versions of each entry.\n", count);
+            return Ok(());
+        }
+
+        // Set count
+        let count = req.args[0]
+            .parse::<u32>()
+            .map_err(|e| Error::Validate(format!("Invalid retention count: {}", e)))?;
+        rpc::send::<u32, u32>("db.retention", &vec![count])?;
+
+        if count == 0 {
+            cli_info!("Prior versions of entries will no longer be kept.\n");
+        } else {
+            cli_info!("Now keeping {} prior versions of each entry.\n", count);
+        }
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Version Retention",
+            "nyx db retention [<COUNT>]",
+            "Displays or sets how many prior versions of each entry are kept when it is edited, defaults to 10.  Lowering the count immediately discards older versions, and 0 disables keeping them.",
+        );
+
+        help.add_param("COUNT", "Optional number of prior versions to keep.");
+        help.add_example("nyx db retention");
+        help.add_example("nyx db retention 25");
+        help
+    }
+}
@@ -17,15 +17,27 @@ impl CliCommand for CliDbStats {
         let stats: DbStats = rpc::send::<String, DbStats>("db.stats", &vec![])?;
 
         // Set data
-        let data = indexmap! {
+        let mut data = indexmap! {
             "Db File: " => stats.dbfile.to_string(),
             "Users: " => format!("{} entries, {} dirs", stats.users.0, stats.users.1),
             "OTP: " => format!("{} entries, {} dirs", stats.oauth.0, stats.oauth.1),
             "SSH Keys: " => format!("{} entries, {} dirs", stats.ssh_keys.0, stats.ssh_keys.1),
             "Strings: " => format!("{} entries, {} dirs", stats.strings.0, stats.strings.1),
-            "Notes: " => format!("{} entries, {} dirs", stats.notes.0, stats.notes.1)
+            "Notes: " => format!("{} entries, {} dirs", stats.notes.0, stats.notes.1),
+            "Favorites: " => format!("{} entries", stats.favorites)
         };
 
+        // Tags, by number of entries
+        if !stats.tags.is_empty() {
+            let mut tags: Vec<(&String, &u32)> = stats.tags.iter().collect();
+            tags.sort_by(|a, b| b.1.cmp(a.1));
+            let tag_list = tags
+                .iter()
+                .map(|(tag, count)| format!("#{} ({})", tag, count))
+                .collect::<Vec<String>>();
+            data.insert("Tags: ", tag_list.join(", "));
+        }
+
         // Display
         cli_header("Nyx Database Stats");
         cli_display_array(&data);
@@ -37,7 +49,7 @@ impl CliCommand for CliDbStats {
         let mut help = CliHelpScreen::new(
             "Database Stats",
             "nyx db stats",
-            "Displays overall database statistics",
+            "Displays overall database statistics, including the number of entries with each tag.",
         );
 
         help.add_example("nyx db stats");
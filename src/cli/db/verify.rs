@@ -0,0 +1,63 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::Error;
+use crate::database::{HistoryHead, HistoryVerify};
+use crate::rpc;
+use falcon_cli::*;
+use std::fs;
+
+#[derive(Default)]
+pub struct CliDbHistoryVerify {}
+
+impl CliCommand for CliDbHistoryVerify {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Get attested head
+        let mut params = vec![];
+        if let Some(filename) = req.get_flag("--head") {
+            let head: HistoryHead = serde_json::from_str(&fs::read_to_string(&filename)?)
+                .map_err(|e| Error::Validate(format!("Invalid head file {}: {}", filename, e)))?;
+            params = vec![head.hash, head.seq.to_string()];
+        }
+
+        // Send RPC
+        let res: HistoryVerify = rpc::send("db.history_verify", &params)?;
+
+        cli_header("Verify History");
+        if res.problems.is_empty() {
+            cli_info!(
+                "History chain is intact, {} entries verified from entry {} to {}.\n",
+                res.entries,
+                res.anchor.seq + 1,
+                res.head.seq
+            );
+            return Ok(());
+        }
+
+        cli_error!("History chain failed verification:\n");
+        for problem in res.problems.iter() {
+            cli_send!("    {}\n", problem);
+        }
+        cli_send!("\n");
+        Err(Error::Validate(format!("{} problems found in history.", res.problems.len())).into())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Verify History",
+            "nyx db history verify [--head <FILE>]",
+            "Verifies the hash chain linking each history entry to the one before it, reporting any entries missing or altered.  Entries removed by the history limits are expected, with the oldest kept entry chained to the last one removed.",
+        );
+
+        help.add_flag(
+            "--head",
+            "Optional head previously exported with 'nyx db history head --file', checking it is still part of the history.",
+        );
+        help.add_example("nyx db history verify");
+        help.add_example("nyx db history verify --head ~/nyx-head.json");
+        help
+    }
+}
@@ -8,31 +8,33 @@ use crate::rpc;
 use falcon_cli::*;
 
 use self::db::{
-    CliDbBackup, CliDbChangePass, CliDbClose, CliDbCreate, CliDbHistory, CliDbOpen, CliDbRestore,
-    CliDbStats,
+    CliDbBackup, CliDbChangePass, CliDbClose, CliDbCreate, CliDbEvents, CliDbExport,
+    CliDbFingerprint, CliDbHistory, CliDbHistoryHead, CliDbHistoryVerify, CliDbImport, CliDbList,
+    CliDbOpen, CliDbRestore, CliDbRetention, CliDbStats,
 };
 use self::note::{
     CliNoteCopy, CliNoteDelete, CliNoteEdit, CliNoteFind, CliNoteList, CliNoteNew, CliNoteRename,
     CliNoteShow, CliNoteXn,
 };
 use self::otp::{
-    CliOtpCopy, CliOtpDelete, CliOtpEdit, CliOtpFind, CliOtpGenerate, CliOtpList, CliOtpNew,
-    CliOtpRename, CliOtpShow, CliOtpXp, CliOtpXr, CliOtpXw,
+    CliOtpCopy, CliOtpDelete, CliOtpEdit, CliOtpExport, CliOtpFind, CliOtpGenerate, CliOtpList,
+    CliOtpNew, CliOtpRename, CliOtpShow, CliOtpXp, CliOtpXr, CliOtpXw,
 };
 use self::ssh::{
-    CliSshKeyCopy, CliSshKeyDelete, CliSshKeyEdit, CliSshKeyFind, CliSshKeyGenerate,
-    CliSshKeyImport, CliSshKeyList, CliSshKeyRename, CliSshKeyShow, CliSshKeyXb, CliSshKeyXh,
-    CliSshKeyXp, CliSshKeyXu, CliSshKeyXv,
+    CliSshKeyCopy, CliSshKeyDelete, CliSshKeyEdit, CliSshKeyExport, CliSshKeyFind,
+    CliSshKeyGenerate, CliSshKeyImport, CliSshKeyList, CliSshKeyRename, CliSshKeyShow, CliSshKeyXb,
+    CliSshKeyXh, CliSshKeyXp, CliSshKeyXu, CliSshKeyXv,
 };
 use self::str::{
     CliStrCopy, CliStrDelete, CliStrFind, CliStrGet, CliStrList, CliStrRename, CliStrSet,
 };
+use self::tag::{CliTagAdd, CliTagFavorite, CliTagRemove, CliTagUnfavorite};
 use self::user::{
-    CliUserCopy, CliUserDelete, CliUserEdit, CliUserFind, CliUserList, CliUserNew, CliUserRename,
-    CliUserShow, CliUserXp, CliUserXu, CliUserXw,
+    CliUserCopy, CliUserDelete, CliUserEdit, CliUserFind, CliUserHistory, CliUserList, CliUserNew,
+    CliUserRename, CliUserRevert, CliUserShow, CliUserX, CliUserXp, CliUserXu, CliUserXw,
 };
 
-#[cfg(feature="testutil")]
+#[cfg(feature = "testutil")]
 use self::test::CliTest;
 
 pub mod clipboard;
@@ -41,9 +43,10 @@ mod note;
 mod otp;
 mod ssh;
 mod str;
+mod tag;
 mod user;
 
-#[cfg(feature="testutil")]
+#[cfg(feature = "testutil")]
 mod test;
 
 /// Boot CLI router and define available commands
@@ -53,7 +56,19 @@ pub fn boot() -> CliRouter {
     router.version_message(&format!("Nyx v{} - Secure CLI password & key manager\nDeveloped by the Cicero Project - https://cicero.sh/latest", env!("CARGO_PKG_VERSION")));
 
     router.global("-f", "--dbfile", true, "Location of Nyx database file.");
+    router.global(
+        "-D",
+        "--db",
+        true,
+        "Alias of open database to use, when multiple databases are open.",
+    );
     router.global("-t", "--timeout", true, "Time of inactivity to lock database (eg. 3h = 3 hours, 15m = 15 minutes, 60s = 60 seconds)");
+    router.global(
+        "-L",
+        "--lock-on",
+        true,
+        "Desktop session events that lock database, any of: sleep, lock, logout, or all / none.  Defaults to all (Linux only).",
+    );
     router.global(
         "-c",
         "--cb-timeout",
@@ -66,6 +81,54 @@ pub fn boot() -> CliRouter {
         true,
         "Directory to mount fuse point, defaults to /tmp/nyx",
     );
+    router.global(
+        "-a",
+        "--agent-sock",
+        true,
+        "Location of SSH agent socket, defaults to $XDG_RUNTIME_DIR/nyx-agent.sock",
+    );
+    router.global(
+        "-k",
+        "--token-file",
+        true,
+        "Location of RPC session token, defaults to $XDG_RUNTIME_DIR/nyx-<PORT>.token",
+    );
+    router.global(
+        "-s",
+        "--rpc-sock",
+        true,
+        "Location of RPC socket, defaults to $XDG_RUNTIME_DIR/nyx/rpc.sock",
+    );
+    router.global(
+        "-r",
+        "--rpc-transport",
+        true,
+        "RPC transport, one of: unix, tcp, both.  Defaults to unix on Linux / Mac, tcp otherwise.",
+    );
+    router.global(
+        "-S",
+        "--tls",
+        false,
+        "Serve and connect to RPC over TLS, using a self-signed certificate unless --tls-cert and --tls-key are given.",
+    );
+    router.global(
+        "",
+        "--tls-cert",
+        true,
+        "TLS certificate in PEM format, defaults to <DATA_DIR>/nyx/tls/cert.pem",
+    );
+    router.global(
+        "",
+        "--tls-key",
+        true,
+        "TLS private key in PEM format, defaults to <DATA_DIR>/nyx/tls/key.pem",
+    );
+    router.global(
+        "",
+        "--tls-fingerprint",
+        true,
+        "SHA-256 fingerprint of the daemon certificate to pin when connecting remotely.",
+    );
     router.global("-h", "--host", true, "RPC host, defaults to 127.0.0.1");
     router.global("-p", "--port", true, "RPC port, defaults to 7924");
     router.ignore("-d", false);
@@ -76,34 +139,67 @@ pub fn boot() -> CliRouter {
     router.add::<CliDbChangePass>("db changepass", vec!["changepass"], vec![]);
     router.add::<CliDbClose>("db close", vec!["close"], vec![]);
     router.add::<CliDbCreate>("db create", vec![], vec![]);
-    router.add::<CliDbHistory>("db history", vec!["history"], vec![]);
+    router.add::<CliDbEvents>("db events", vec!["events"], vec![]);
+    router.add::<CliDbExport>("db export", vec!["export"], vec!["--format", "--file"]);
+    router.add::<CliDbFingerprint>("db fingerprint", vec![], vec![]);
+    router.add::<CliDbHistory>(
+        "db history",
+        vec!["history"],
+        vec![
+            "-n",
+            "--type",
+            "--action",
+            "--name",
+            "--since",
+            "--until",
+            "--keep",
+            "--keep-days",
+        ],
+    );
+    router.add::<CliDbHistoryHead>("db history head", vec![], vec!["--file"]);
+    router.add::<CliDbHistoryVerify>("db history verify", vec![], vec!["--head"]);
+    router.add::<CliDbImport>("db import", vec!["import"], vec!["--format"]);
+    router.add::<CliDbList>("db ls", vec!["db list"], vec![]);
     router.add::<CliDbOpen>("db open", vec!["open"], vec![]);
     router.add::<CliDbRestore>("db restore", vec!["restore"], vec![]);
+    router.add::<CliDbRetention>("db retention", vec![], vec![]);
     router.add::<CliDbStats>("db stats", vec!["stats"], vec![]);
 
     // Users
     router.add_category("user", "Users", "Manage user / password combinations.");
     router.add::<CliUserCopy>("user cp", vec!["user copy", "copy", "cp"], vec![]);
     router.add::<CliUserDelete>("user rm", vec!["user del", "rm", "del"], vec![]);
-    router.add::<CliUserEdit>("user edit", vec!["edit"], vec![]);
-    router.add::<CliUserFind>("user find", vec!["find"], vec![]);
-    router.add::<CliUserList>("user ls", vec!["user list", "list", "ls"], vec!["-n"]);
+    router.add::<CliUserEdit>(
+        "user edit",
+        vec!["edit"],
+        vec!["--field", "--secret", "--attach", "--remove"],
+    );
+    router.add::<CliUserFind>("user find", vec!["find"], vec!["--tag"]);
+    router.add::<CliUserHistory>("user history", vec![], vec![]);
+    router.add::<CliUserList>(
+        "user ls",
+        vec!["user list", "list", "ls"],
+        vec!["-n", "--tag"],
+    );
     router.add::<CliUserNew>("user new", vec!["new"], vec![]);
     router.add::<CliUserRename>("user mv", vec!["user rename", "rename", "mv"], vec![]);
+    router.add::<CliUserRevert>("user revert", vec!["revert"], vec![]);
     router.add::<CliUserShow>("user show", vec!["show"], vec![]);
+    router.add::<CliUserX>("user x", vec!["x"], vec!["--file"]);
     router.add::<CliUserXp>("user xp", vec!["xp"], vec![]);
     router.add::<CliUserXu>("user xu", vec!["xu"], vec![]);
     router.add::<CliUserXw>("user xw", vec!["xw"], vec![]);
 
     // Oauth OTP codes
     router.add_category("otp", "Oauth", "Manage Oauth OTP Codes");
-    router.add::<CliOtpGenerate>("otp", vec![], vec![]);
+    router.add::<CliOtpGenerate>("otp", vec![], vec!["--min-remaining"]);
     router.add::<CliOtpCopy>("otp cp", vec!["otp copy"], vec![]);
     router.add::<CliOtpDelete>("otp rm", vec!["otp delete", "otp del"], vec![]);
     router.add::<CliOtpEdit>("otp edit", vec![], vec![]);
-    router.add::<CliOtpFind>("otp find", vec![], vec![]);
-    router.add::<CliOtpList>("otp ls", vec!["opt list"], vec!["-n"]);
-    router.add::<CliOtpNew>("otp new", vec![], vec![]);
+    router.add::<CliOtpExport>("otp export", vec![], vec![]);
+    router.add::<CliOtpFind>("otp find", vec![], vec!["--tag"]);
+    router.add::<CliOtpList>("otp ls", vec!["opt list"], vec!["-n", "--tag"]);
+    router.add::<CliOtpNew>("otp new", vec![], vec!["--qr"]);
     router.add::<CliOtpRename>("otp mv", vec!["otp rename"], vec![]);
     router.add::<CliOtpShow>("otp show", vec![], vec![]);
     router.add::<CliOtpXp>("otp xp", vec![], vec![]);
@@ -115,10 +211,15 @@ pub fn boot() -> CliRouter {
     router.add::<CliSshKeyCopy>("ssh cp", vec!["ssh copy"], vec![]);
     router.add::<CliSshKeyDelete>("ssh rm", vec!["ssh delete", "ssh del"], vec![]);
     router.add::<CliSshKeyEdit>("ssh edit", vec![], vec![]);
-    router.add::<CliSshKeyFind>("ssh find", vec![], vec![]);
-    router.add::<CliSshKeyGenerate>("ssh gen", vec!["ssh generate"], vec![]);
+    router.add::<CliSshKeyExport>("ssh export", vec![], vec!["--file"]);
+    router.add::<CliSshKeyFind>("ssh find", vec![], vec!["--tag"]);
+    router.add::<CliSshKeyGenerate>(
+        "ssh gen",
+        vec!["ssh generate"],
+        vec!["--type", "--bits", "--comment"],
+    );
     router.add::<CliSshKeyImport>("ssh import", vec![], vec!["--file"]);
-    router.add::<CliSshKeyList>("ssh ls", vec!["ssh list"], vec!["-n"]);
+    router.add::<CliSshKeyList>("ssh ls", vec!["ssh list"], vec!["-n", "--tag"]);
     router.add::<CliSshKeyRename>("ssh mv", vec!["ssh rename"], vec![]);
     router.add::<CliSshKeyShow>("ssh show", vec![], vec![]);
     router.add::<CliSshKeyXb>("ssh xb", vec![], vec![]);
@@ -131,9 +232,9 @@ pub fn boot() -> CliRouter {
     router.add_category("str", "Strings", "Manage strings");
     router.add::<CliStrCopy>("str cp", vec!["str copy"], vec![]);
     router.add::<CliStrDelete>("str rm", vec!["str delete", "str del"], vec![]);
-    router.add::<CliStrFind>("str find", vec![], vec![]);
+    router.add::<CliStrFind>("str find", vec![], vec!["--tag"]);
     router.add::<CliStrGet>("str get", vec!["get"], vec![]);
-    router.add::<CliStrList>("str ls", vec!["str list"], vec!["-n"]);
+    router.add::<CliStrList>("str ls", vec!["str list"], vec!["-n", "--tag"]);
     router.add::<CliStrRename>("str mv", vec!["str rename"], vec![]);
     router.add::<CliStrSet>("str set", vec!["set"], vec![]);
 
@@ -142,15 +243,26 @@ pub fn boot() -> CliRouter {
     router.add::<CliNoteCopy>("note cp", vec!["note copy"], vec![]);
     router.add::<CliNoteDelete>("note rm", vec!["note delete", "note del"], vec![]);
     router.add::<CliNoteEdit>("note edit", vec![], vec![]);
-    router.add::<CliNoteFind>("note find", vec![], vec![]);
-    router.add::<CliNoteList>("note ls", vec!["note list"], vec!["-n"]);
+    router.add::<CliNoteFind>("note find", vec![], vec!["--tag"]);
+    router.add::<CliNoteList>("note ls", vec!["note list"], vec!["-n", "--tag"]);
     router.add::<CliNoteNew>("note new", vec![], vec![]);
     router.add::<CliNoteRename>("note mv", vec!["note rename"], vec![]);
     router.add::<CliNoteShow>("note show", vec![], vec![]);
     router.add::<CliNoteXn>("note xn", vec![], vec![]);
 
+    // Tags
+    router.add_category(
+        "tag",
+        "Tags",
+        "Tag entries of any type, and mark favorites.",
+    );
+    router.add::<CliTagAdd>("tag", vec![], vec!["--type"]);
+    router.add::<CliTagRemove>("tag rm", vec!["untag"], vec!["--type"]);
+    router.add::<CliTagFavorite>("tag fav", vec!["fav"], vec!["--type"]);
+    router.add::<CliTagUnfavorite>("tag unfav", vec!["unfav"], vec!["--type"]);
+
     // Test utils
-    #[cfg(feature="testutil")]
+    #[cfg(feature = "testutil")]
     {
         router.add::<CliTest>("test", vec![], vec![]);
     }
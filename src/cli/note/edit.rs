@@ -7,6 +7,7 @@
 use crate::database::Note;
 use crate::rpc;
 use falcon_cli::*;
+use serde_json::Value;
 
 #[derive(Default)]
 pub struct CliNoteEdit {}
@@ -27,11 +28,14 @@ impl CliCommand for CliNoteEdit {
         let new_note = cli_text_editor(&note.note)?;
         note.note = new_note;
 
-        let note_str = serde_json::to_string(&note)
+        let note_value = serde_json::to_value(&note)
             .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
 
         // Edit item
-        rpc::send::<&String, bool>("note.edit", &vec![&req.args[0].to_lowercase(), &note_str])?;
+        rpc::send::<Value, bool>(
+            "note.edit",
+            &vec![Value::from(req.args[0].to_lowercase()), note_value],
+        )?;
 
         cli_info!("Updated entry info for {}", req.args[0]);
 
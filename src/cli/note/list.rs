@@ -19,9 +19,11 @@ impl CliCommand for CliNoteList {
             String::new()
         };
         let start = req.get_flag("-n").unwrap_or("0".to_string());
+        let tag = req.get_flag("--tag").unwrap_or_default();
 
         // Send RPC
-        let entries: Vec<String> = rpc::send("note.list", &vec![&dirname.to_string(), &start])?;
+        let entries: Vec<String> =
+            rpc::send("note.list", &vec![&dirname.to_string(), &start, &tag])?;
 
         // Get table rows
         let rows = entries
@@ -31,7 +33,11 @@ impl CliCommand for CliNoteList {
             .collect::<Vec<Vec<String>>>();
 
         // Display table
-        cli_header(&format!("{}/", dirname));
+        if tag.is_empty() {
+            cli_header(&format!("{}/", dirname));
+        } else {
+            cli_header(&format!("{}/ #{}", dirname, tag));
+        }
         cli_display_table(&["#", "Name"], &rows);
         Ok(())
     }
@@ -39,13 +45,18 @@ impl CliCommand for CliNoteList {
     fn help(&self) -> CliHelpScreen {
         let mut help = CliHelpScreen::new(
             "List Notes",
-            "nyx note ls [<DIRNAME>] [-n XX]",
-            "Lists all notes within directory in alphabetical order.",
+            "nyx note ls [<DIRNAME>] [-n XX] [--tag <TAG>]",
+            "Lists all notes within directory in alphabetical order.  Favorites are listed first.",
         );
 
         help.add_param("DIRNAME", "Optional directory name to list entries from.");
         help.add_flag("-n", "Optional offset / start position of entries.");
+        help.add_flag(
+            "--tag",
+            "Only list entries with tag, by full name, from within directory and below.",
+        );
         help.add_example("nyx note ls mysite");
+        help.add_example("nyx note ls --tag prod");
         help
     }
 }
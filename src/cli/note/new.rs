@@ -8,6 +8,7 @@ use crate::cli;
 use crate::database::Note;
 use crate::rpc;
 use falcon_cli::*;
+use serde_json::Value;
 
 #[derive(Default)]
 pub struct CliNoteNew {}
@@ -36,11 +37,14 @@ impl CliCommand for CliNoteNew {
             note,
         };
 
-        let note_str = serde_json::to_string(&note)
+        let note_value = serde_json::to_value(&note)
             .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
 
         // Create item
-        rpc::send::<&String, bool>("note.new", &vec![&req.args[0].to_lowercase(), &note_str])?;
+        rpc::send::<Value, bool>(
+            "note.new",
+            &vec![Value::from(req.args[0].to_lowercase()), note_value],
+        )?;
 
         cli_info!("Created new entry, {}", req.args[0]);
 
@@ -8,6 +8,7 @@ use crate::cli;
 use crate::database::Oauth;
 use crate::rpc;
 use falcon_cli::*;
+use serde_json::Value;
 
 #[derive(Default)]
 pub struct CliOtpEdit {}
@@ -46,11 +47,16 @@ impl CliCommand for CliOtpEdit {
             otp.recovery_keys = recovery_keys;
         }
 
-        let otp_str = serde_json::to_string(&otp)
+        cli::otp::get_token_params(&mut otp)?;
+
+        let otp_value = serde_json::to_value(&otp)
             .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
 
         // Edit item
-        rpc::send::<&String, bool>("otp.edit", &vec![&req.args[0].to_lowercase(), &otp_str])?;
+        rpc::send::<Value, bool>(
+            "otp.edit",
+            &vec![Value::from(req.args[0].to_lowercase()), otp_value],
+        )?;
 
         cli_info!("Updated entry info for {}", req.args[0]);
 
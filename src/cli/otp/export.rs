@@ -0,0 +1,69 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::cli;
+use crate::database::Oauth;
+use crate::rpc;
+use falcon_cli::*;
+use qrcode::QrCode;
+use qrcode::render::unicode::Dense1x2;
+
+#[derive(Default)]
+pub struct CliOtpExport {}
+
+impl CliCommand for CliOtpExport {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check params
+        if req.args.is_empty() {
+            cli_error!("You did not specify a name of an entry");
+            cli_info!("    Usage: nyx otp export <NAME> [--qr]\n");
+            return Err(CliError::MissingParams.into());
+        }
+
+        // Check if entry exists
+        cli::check_exists("otp", &req.args[0], true)?;
+
+        // Get entry
+        let otp: Oauth = rpc::send("otp.get", &vec![&req.args[0]])?;
+        let uri = otp.to_uri()?;
+
+        // Render QR code, light modules drawn as blocks so it scans on dark terminals
+        if req.has_flag("--qr") {
+            let code = QrCode::new(uri.as_bytes())
+                .map_err(|e| CliError::Generic(format!("Unable to create QR code: {}", e)))?;
+            let image = code
+                .render::<Dense1x2>()
+                .dark_color(Dense1x2::Light)
+                .light_color(Dense1x2::Dark)
+                .quiet_zone(true)
+                .build();
+
+            cli_header(&format!("OTP: {}", req.args[0]));
+            println!("{}\n", image);
+        }
+
+        // Printed directly, as cli_send! word wraps long lines
+        println!("{}", uri);
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Export OTP Entry",
+            "nyx otp export <NAME> [--qr]",
+            "Outputs the otpauth:// key URI of the entry, which can be imported into any other authenticator app.",
+        );
+
+        help.add_param("NAME", "Name of entry to export.");
+        help.add_flag(
+            "--qr",
+            "Also display the URI as a QR code within the terminal for scanning with a phone.",
+        );
+        help.add_example("nyx otp export mysite/cloudflare");
+        help.add_example("nyx otp export mysite/cloudflare --qr");
+        help
+    }
+}
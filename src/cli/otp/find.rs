@@ -13,14 +13,17 @@ pub struct CliOtpFind {}
 impl CliCommand for CliOtpFind {
     fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
         // Check
-        if req.args.is_empty() {
+        if req.args.is_empty() && !req.has_flag("--tag") {
             cli_info!("You did not specify a search string.");
-            cli_info!("    Usage:  nyx otp find <SEARCH>\n");
+            cli_info!("    Usage:  nyx otp find <SEARCH> [--tag <TAG>]\n");
             return Err(CliError::MissingParams.into());
         }
 
         // Send RPC
-        let entries: Vec<String> = rpc::send("otp.find", &vec![req.args[0].to_string()])?;
+        let search = req.args.first().cloned().unwrap_or_default();
+        let tag = req.get_flag("--tag").unwrap_or_default();
+        let entries: Vec<String> =
+            rpc::send("otp.find", &vec![search.to_string(), tag.to_string()])?;
 
         // Get table rows
         let rows = entries
@@ -30,7 +33,12 @@ impl CliCommand for CliOtpFind {
             .collect::<Vec<Vec<String>>>();
 
         // Display table
-        cli_header(&format!("Results for {}", req.args[0]));
+        let title = if tag.is_empty() {
+            search.to_string()
+        } else {
+            format!("{} #{}", search, tag)
+        };
+        cli_header(&format!("Results for {}", title.trim()));
         cli_display_table(&["#", "Name"], &rows);
         Ok(())
     }
@@ -38,12 +46,14 @@ impl CliCommand for CliOtpFind {
     fn help(&self) -> CliHelpScreen {
         let mut help = CliHelpScreen::new(
             "Search OTP Entries",
-            "nyx otp find <TEXT>",
+            "nyx otp find [<TEXT>] [--tag <TAG>]",
             "Search all OTP entries",
         );
 
         help.add_param("TEXT", "The text to search all entries for.");
+        help.add_flag("--tag", "Only list entries with tag.");
         help.add_example("nyx otp find my-username");
+        help.add_example("nyx otp find --tag prod");
         help
     }
 }
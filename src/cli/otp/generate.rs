@@ -6,8 +6,12 @@
 
 use crate::cli;
 use crate::cli::clipboard;
+use crate::database::{Oauth, OtpCode, OtpKind};
 use crate::rpc;
 use falcon_cli::*;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
 
 #[derive(Default)]
 pub struct CliOtpGenerate {}
@@ -17,19 +21,61 @@ impl CliCommand for CliOtpGenerate {
         // Check params
         if req.args.is_empty() {
             cli_error!("You did not specify a name of an entry");
-            cli_info!("    Usage: nyx otp show <NAME>\n");
+            cli_info!("    Usage: nyx otp <NAME> [--min-remaining <SECS>] [--watch]\n");
             return Err(CliError::MissingParams.into());
         }
 
         // Check if entry already exists
         cli::check_exists("otp", &req.args[0], true)?;
 
+        // Live countdown
+        if req.has_flag("--watch") {
+            return self.watch(&req.args[0]);
+        }
+
+        // Parse minimum remaining validity
+        let min_remaining = match req.get_flag("--min-remaining") {
+            Some(value) => Some(value.parse::<u64>().map_err(|_| {
+                CliError::Generic(format!("Invalid --min-remaining value, {}", value))
+            })?),
+            None => None,
+        };
+
         // Generate otp
-        let otp: String = rpc::send("otp.generate", &vec![&req.args[0]])?;
+        let mut otp: OtpCode = rpc::send("otp.generate", &vec![&req.args[0]])?;
+
+        // Wait for fresh window if current code expires too soon
+        if let (Some(min_remaining), Some(remaining), Some(period)) =
+            (min_remaining, otp.remaining, otp.period)
+        {
+            if min_remaining >= period {
+                return Err(CliError::Generic(format!(
+                    "The --min-remaining value must be less than the {} second period of the entry.",
+                    period
+                ))
+                .into());
+            }
+
+            if remaining < min_remaining {
+                cli_info!(
+                    "Code expires in {} seconds, waiting for next code...",
+                    remaining
+                );
+                thread::sleep(Duration::from_secs(remaining));
+                otp = rpc::send("otp.generate", &vec![&req.args[0]])?;
+            }
+        }
 
         // copy to clipboard
-        clipboard::copy(&otp)?;
-        cli_info!("{}", otp);
+        clipboard::copy(&otp.code)?;
+        cli_info!("{}", otp.code);
+        if let (Some(remaining), Some(next_code)) = (otp.remaining, otp.next_code) {
+            cli_info!(
+                "Valid for {} more seconds, next code {}",
+                remaining,
+                next_code
+            );
+        }
 
         Ok(())
     }
@@ -38,11 +84,54 @@ impl CliCommand for CliOtpGenerate {
         let mut help = CliHelpScreen::new(
             "Generate OTP",
             "nyx otp <NAME>",
-            "Generates 6 digit OTP code for authentication.",
+            "Generates OTP code for authentication, and copies it to the clipboard.",
         );
 
         help.add_param("NAME", "Name of OTP entry to generate code for.");
+        help.add_flag(
+            "--min-remaining",
+            "If the current code expires in fewer than this many seconds, wait for the next code instead.",
+        );
+        help.add_flag(
+            "--watch",
+            "Continuously display the current code with a countdown until Ctrl+C is pressed.",
+        );
         help.add_example("nyx otp mysite/cloudflare");
+        help.add_example("nyx otp mysite/cloudflare --min-remaining 5");
+        help.add_example("nyx otp mysite/cloudflare --watch");
         help
     }
 }
+
+impl CliOtpGenerate {
+    /// Display current code with countdown, refreshing every time window
+    fn watch(&self, name: &str) -> anyhow::Result<()> {
+        // Check kind first, as generating a code advances the counter of HOTP entries
+        let entry: Oauth = rpc::send("otp.get", &vec![name])?;
+        if entry.kind != OtpKind::Totp {
+            return Err(CliError::Generic(
+                "The --watch flag is only supported for time based (TOTP) entries.".to_string(),
+            )
+            .into());
+        }
+
+        cli_header(&format!("OTP: {}", name));
+        cli_info!("Press Ctrl+C to exit.\n");
+
+        loop {
+            let otp: OtpCode = rpc::send("otp.generate", &vec![name])?;
+            let (Some(remaining), Some(next_code)) = (otp.remaining, otp.next_code) else {
+                return Ok(());
+            };
+
+            for secs in (1..=remaining).rev() {
+                print!(
+                    "\r{}    expires in {:>3}s    next {}  ",
+                    otp.code, secs, next_code
+                );
+                io::stdout().flush()?;
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+    }
+}
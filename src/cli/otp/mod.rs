@@ -4,9 +4,15 @@
 // Apache License text: https://www.apache.org/licenses/LICENSE-2.0
 // MIT License text: https://opensource.org/licenses/MIT
 
+use crate::database::{BaseDbItem, Oauth, OtpAlgorithm, OtpKind};
+use falcon_cli::*;
+use std::path::Path;
+use std::str::FromStr;
+
 pub use self::copy::CliOtpCopy;
 pub use self::delete::CliOtpDelete;
 pub use self::edit::CliOtpEdit;
+pub use self::export::CliOtpExport;
 pub use self::find::CliOtpFind;
 pub use self::generate::CliOtpGenerate;
 pub use self::list::CliOtpList;
@@ -20,6 +26,7 @@ pub use self::xw::CliOtpXw;
 mod copy;
 mod delete;
 mod edit;
+mod export;
 mod find;
 mod generate;
 mod list;
@@ -29,3 +36,87 @@ mod show;
 mod xp;
 mod xr;
 mod xw;
+
+/// Prompt for token parameters, blank input keeps the current value
+pub fn get_token_params(otp: &mut Oauth) -> Result<(), CliError> {
+    cli_info!("Token parameters, press Enter to keep the value shown in brackets.\n");
+
+    let kind = cli_get_input(
+        &format!(
+            "Type, totp or hotp [{}]: ",
+            otp.kind.to_string().to_lowercase()
+        ),
+        "",
+    );
+    if !kind.is_empty() {
+        otp.kind = OtpKind::from_str(&kind)?;
+    }
+
+    let algorithm = cli_get_input(
+        &format!("Algorithm, SHA1 / SHA256 / SHA512 [{}]: ", otp.algorithm),
+        "",
+    );
+    if !algorithm.is_empty() {
+        otp.algorithm = OtpAlgorithm::from_str(&algorithm)?;
+    }
+
+    let digits = cli_get_input(&format!("Digits [{}]: ", otp.digits), "");
+    if !digits.is_empty() {
+        otp.digits =
+            digits.parse::<u32>().map_err(|_| CliError::Generic("Invalid digits".to_string()))?;
+    }
+
+    if otp.kind == OtpKind::Totp {
+        let period = cli_get_input(&format!("Period in seconds [{}]: ", otp.period), "");
+        if !period.is_empty() {
+            otp.period = period
+                .parse::<u64>()
+                .map_err(|_| CliError::Generic("Invalid period".to_string()))?;
+        }
+    } else {
+        let counter = cli_get_input(&format!("Counter [{}]: ", otp.counter), "");
+        if !counter.is_empty() {
+            otp.counter = counter
+                .parse::<u64>()
+                .map_err(|_| CliError::Generic("Invalid counter".to_string()))?;
+        }
+    }
+
+    otp.validate()?;
+    Ok(())
+}
+
+/// Decode QR code image into its text contents
+pub fn decode_qr(filename: &str) -> Result<String, CliError> {
+    if !Path::new(filename).exists() {
+        return Err(CliError::Generic(format!(
+            "File does not exist, {}",
+            filename
+        )));
+    }
+
+    let img = image::open(filename)
+        .map_err(|e| CliError::Generic(format!("Unable to read image {}, {}", filename, e)))?
+        .to_luma8();
+
+    // Decode first QR code found within the image
+    let (width, height) = (img.width() as usize, img.height() as usize);
+    let mut img = rqrr::PreparedImage::prepare_from_greyscale(width, height, |x, y| {
+        img.get_pixel(x as u32, y as u32)[0]
+    });
+    let contents = img
+        .detect_grids()
+        .iter()
+        .find_map(|grid| grid.decode().ok())
+        .map(|(_meta, contents)| contents.trim().to_string())
+        .unwrap_or_default();
+
+    if contents.is_empty() {
+        return Err(CliError::Generic(format!(
+            "No QR code found in image, {}",
+            filename
+        )));
+    }
+
+    Ok(contents)
+}
@@ -8,6 +8,7 @@ use crate::cli;
 use crate::database::Oauth;
 use crate::rpc;
 use falcon_cli::*;
+use serde_json::Value;
 
 #[derive(Default)]
 pub struct CliOtpNew {}
@@ -17,10 +18,19 @@ impl CliCommand for CliOtpNew {
         // Check params
         if req.args.is_empty() {
             cli_error!("You did not specify a name for the new entry.\n");
-            cli_info!("    Usage: nyx otp new <NAME>\n");
+            cli_info!("    Usage: nyx otp new <NAME> [URI] [--qr <FILE>]\n");
             return Err(CliError::MissingParams.into());
         }
 
+        // Import from key URI or QR code
+        let uri = match req.get_flag("--qr") {
+            Some(filename) => Some(cli::otp::decode_qr(&filename)?),
+            None => req.args.get(1).cloned(),
+        };
+        if let Some(uri) = uri {
+            return self.import_uri(&req.args[0], &uri);
+        }
+
         // Ensure item not exists
         cli::check_exists("otp", &req.args[0], false)?;
 
@@ -31,20 +41,21 @@ impl CliCommand for CliOtpNew {
         let url = cli_get_input("URL: ", "");
         let recovery_keys = cli_get_multiline_input("Recovery Keys");
 
-        // Instantiate item
-        let otp = Oauth {
-            display_name: req.args[0].to_string(),
-            secret_code,
-            url,
-            recovery_keys,
+        // Instantiate item, a pasted key URI provides the token parameters
+        let mut otp = if secret_code.starts_with("otpauth://") {
+            Oauth::from_uri(&secret_code)?
+        } else {
+            let mut otp = Oauth::default();
+            otp.secret_code = secret_code;
+            cli::otp::get_token_params(&mut otp)?;
+            otp
         };
-
-        let otp_str = serde_json::to_string(&otp)
-            .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
+        otp.display_name = req.args[0].to_string();
+        otp.url = url;
+        otp.recovery_keys = recovery_keys;
 
         // Create item
-        rpc::send::<&String, bool>("otp.new", &vec![&req.args[0].to_lowercase(), &otp_str])?;
-
+        self.save(&otp)?;
         cli_info!("Created new entry, {}", req.args[0]);
 
         Ok(())
@@ -54,14 +65,70 @@ impl CliCommand for CliOtpNew {
         let mut help = CliHelpScreen::new(
             "Create New OTP Entry",
             "nyx otp new <NAME>",
-            "Creates a new OTP entry.",
+            "Creates a new OTP entry.  Supports time based (TOTP) and counter based (HOTP) tokens with SHA1, SHA256 or SHA512 and 6 to 10 digits.",
         );
 
         help.add_param(
             "NAME",
             "Name of entry to add.  Supports directory structure (eg. category/myuser)",
         );
-        help.add_example("nyx new mysite/cloudflare");
+        help.add_param(
+            "URI",
+            "Optional otpauth:// key URI to import.  An otpauth-migration:// export from Google Authenticator imports all of its entries into the NAME directory.",
+        );
+        help.add_flag(
+            "--qr",
+            "PNG or JPEG image of a QR code to import instead of a URI.",
+        );
+        help.add_example("nyx otp new mysite/cloudflare");
+        help.add_example(
+            "nyx otp new mysite/github 'otpauth://totp/GitHub:alice?secret=JBSWY3DPEHPK3PXP'",
+        );
+        help.add_example("nyx otp new phone --qr ~/Downloads/export.png");
         help
     }
 }
+
+impl CliOtpNew {
+    /// Import entries from otpauth:// or otpauth-migration:// URI
+    fn import_uri(&self, name: &str, uri: &str) -> anyhow::Result<()> {
+        // Single entry
+        if !uri.trim().starts_with("otpauth-migration://") {
+            cli::check_exists("otp", name, false)?;
+            let mut otp = Oauth::from_uri(uri)?;
+            otp.display_name = name.to_string();
+            self.save(&otp)?;
+            cli_info!("Created new entry, {}", name);
+            return Ok(());
+        }
+
+        // Bulk import into directory
+        let entries = Oauth::from_migration_uri(uri)?;
+        let mut imported = 0;
+        for mut otp in entries {
+            otp.display_name = format!("{}/{}", name.trim_end_matches('/'), otp.suggested_name());
+            if let Err(e) = cli::check_exists("otp", &otp.display_name, false) {
+                cli_warn!("Skipping entry, {}", e);
+                continue;
+            }
+            self.save(&otp)?;
+            cli_info!("Imported entry, {}", otp.display_name);
+            imported += 1;
+        }
+
+        cli_info!("Imported {} entries into {}", imported, name);
+        Ok(())
+    }
+
+    /// Save new entry
+    fn save(&self, otp: &Oauth) -> Result<(), CliError> {
+        let otp_value = serde_json::to_value(otp)
+            .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
+
+        rpc::send::<Value, bool>(
+            "otp.new",
+            &vec![Value::from(otp.display_name.to_lowercase()), otp_value],
+        )?;
+        Ok(())
+    }
+}
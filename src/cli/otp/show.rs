@@ -5,7 +5,7 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 use crate::cli;
-use crate::database::Oauth;
+use crate::database::{Oauth, OtpKind};
 use crate::rpc;
 use falcon_cli::*;
 
@@ -28,8 +28,26 @@ impl CliCommand for CliOtpShow {
         let otp: Oauth = rpc::send("otp.get", &vec![&req.args[0]])?;
 
         // Get vector
+        let params = match otp.kind {
+            OtpKind::Totp => {
+                format!(
+                    "{}, {} digits, {} seconds",
+                    otp.algorithm, otp.digits, otp.period
+                )
+            }
+            OtpKind::Hotp => {
+                format!(
+                    "{}, {} digits, counter {}",
+                    otp.algorithm, otp.digits, otp.counter
+                )
+            }
+        };
         let data = indexmap! {
             "Secret Code:" => otp.secret_code.to_string(),
+            "Type:" => otp.kind.to_string(),
+            "Parameters:" => params,
+            "Issuer:" => otp.issuer.to_string(),
+            "Account:" => otp.account.to_string(),
             "URL:" => otp.url.to_string(),
             "Recovery Keys:" => otp.recovery_keys.to_string()
         };
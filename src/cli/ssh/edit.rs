@@ -8,6 +8,7 @@ use crate::cli;
 use crate::database::SshKey;
 use crate::rpc;
 use falcon_cli::*;
+use serde_json::Value;
 
 #[derive(Default)]
 pub struct CliSshKeyEdit {}
@@ -58,11 +59,14 @@ impl CliCommand for CliSshKeyEdit {
             ssh_key.notes = notes.to_string();
         }
 
-        let ssh_key_str = serde_json::to_string(&ssh_key)
+        let ssh_key_value = serde_json::to_value(&ssh_key)
             .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
 
         // Edit item
-        rpc::send::<&String, bool>("ssh.edit", &vec![&req.args[0].to_lowercase(), &ssh_key_str])?;
+        rpc::send::<Value, bool>(
+            "ssh.edit",
+            &vec![Value::from(req.args[0].to_lowercase()), ssh_key_value],
+        )?;
 
         cli_info!("Updated entry info for {}", req.args[0]);
 
@@ -0,0 +1,98 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::cli;
+use crate::database::SshKey;
+use crate::error::Error;
+use crate::rpc;
+use falcon_cli::*;
+use rand::rngs::OsRng;
+use ssh_key::{LineEnding, PrivateKey};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use zeroize::Zeroize;
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+#[derive(Default)]
+pub struct CliSshKeyExport {}
+
+impl CliCommand for CliSshKeyExport {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check params
+        if req.args.is_empty() {
+            cli_error!("You did not specify a name of an entry");
+            cli_info!("    Usage: nyx ssh export <NAME> --file <PATH>\n");
+            return Err(CliError::MissingParams.into());
+        }
+        let filename = req.get_flag("--file").ok_or(CliError::MissingFlag("--file".to_string()))?;
+
+        // Check if entry exists
+        cli::check_exists("ssh", &req.args[0], true)?;
+
+        // Check destination
+        if Path::new(&filename).exists()
+            && !cli_confirm(&format!("File already exists at {}, overwrite?", filename))
+        {
+            return Ok(());
+        }
+
+        // Get entry
+        let ssh_key: SshKey = rpc::send("ssh.get", &vec![&req.args[0]])?;
+        let privkey = PrivateKey::from_openssh(&ssh_key.private_key)
+            .map_err(|e| Error::Validate(format!("Unable to parse stored private key: {}", e)))?;
+
+        // Get passphrase
+        cli_header(&format!("Export SSH Key: {}", req.args[0]));
+        cli_info!("Enter the passphrase to encrypt the exported private key with.\n");
+        let mut passphrase = cli_get_new_password(0);
+
+        // Encrypt
+        let encrypted = privkey.encrypt(&mut OsRng, &passphrase);
+        passphrase.zeroize();
+        let encrypted = encrypted
+            .map_err(|e| Error::Validate(format!("Unable to encrypt private key: {}", e)))?;
+        let pem = encrypted.to_openssh(LineEnding::LF).map_err(|e| {
+            Error::Validate(format!(
+                "Unable to convert SSH key to OpenSSH format: {}",
+                e
+            ))
+        })?;
+
+        // Check parent dir
+        if let Some(parent) = Path::new(&filename).parent()
+            && !parent.as_os_str().is_empty()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Save file, readable by owner only
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        options.open(&filename)?.write_all(pem.as_bytes())?;
+
+        cli_info!("Exported encrypted private key to: {}", filename);
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Export SSH Key",
+            "nyx ssh export <NAME> --file <PATH>",
+            "Writes the private key to a file in OpenSSH format, encrypted with a passphrase of your choice.",
+        );
+
+        help.add_param("NAME", "Name of entry to export.");
+        help.add_flag("--file", "Location to save the encrypted private key to.");
+        help.add_example("nyx ssh export mysite/app_server --file ~/.ssh/app_server");
+        help
+    }
+}
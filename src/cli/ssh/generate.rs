@@ -11,8 +11,12 @@ use crate::rpc;
 use falcon_cli::*;
 use rand::rngs::OsRng;
 use rsa::RsaPrivateKey;
+use serde_json::Value;
 use ssh_key::private::RsaKeypair;
-use ssh_key::{LineEnding, PrivateKey};
+use ssh_key::{Algorithm, EcdsaCurve, LineEnding, PrivateKey};
+
+const KEY_TYPES: [&str; 4] = ["ed25519", "ecdsa-p256", "ecdsa-p384", "rsa"];
+const RSA_BITS: [usize; 4] = [2048, 3072, 4096, 8192];
 
 #[derive(Default)]
 pub struct CliSshKeyGenerate {}
@@ -26,6 +30,31 @@ impl CliCommand for CliSshKeyGenerate {
             return Err(CliError::MissingParams.into());
         }
 
+        // Get key type
+        if req.has_flag("--type") {
+            req.validate_flag(
+                "--type",
+                CliFormat::OneOf(KEY_TYPES.iter().map(|t| t.to_string()).collect()),
+            )?;
+        }
+        let key_type = req.get_flag("--type").unwrap_or("ed25519".to_string());
+
+        // Get RSA bit size
+        if req.has_flag("--bits") {
+            if key_type != "rsa" {
+                return Err(CliError::Generic(format!(
+                    "The --bits flag only applies to RSA keys, not {}",
+                    key_type
+                ))
+                .into());
+            }
+            req.validate_flag(
+                "--bits",
+                CliFormat::OneOf(RSA_BITS.iter().map(|b| b.to_string()).collect()),
+            )?;
+        }
+        let bits = req.get_flag("--bits").unwrap_or("4096".to_string()).parse::<usize>()?;
+
         // Check if entry already exists
         cli::check_exists("ssh", &req.args[0], false)?;
 
@@ -38,14 +67,13 @@ impl CliCommand for CliSshKeyGenerate {
         let password = cli_get_password("Password (optional): ", true);
         let notes = cli_get_multiline_input("Notes");
 
-        cli_send!("Generating 4096 bit private key, please be patient... ");
         // Generate
-        let rsa_key = RsaPrivateKey::new(&mut OsRng, 4096)
-            .map_err(|e| Error::Validate(format!("Unable to generate RSA key: {}", e)))?;
-        let rsa_keypair = RsaKeypair::try_from(rsa_key)
-            .map_err(|e| Error::Validate(format!("Unable to convert to RsaKeypair: {}", e)))?;
+        cli_send!("Generating {} private key, please be patient... ", key_type);
+        let mut privkey = generate_key(&key_type, bits)?;
+        if let Some(comment) = req.get_flag("--comment") {
+            privkey.set_comment(comment);
+        }
 
-        let privkey = PrivateKey::from(rsa_keypair);
         let private_key = privkey.to_openssh(LineEnding::LF).map_err(|e| {
             Error::Validate(format!(
                 "Unable to convert SSH key to OpenSSH format: {}",
@@ -73,13 +101,14 @@ impl CliCommand for CliSshKeyGenerate {
             notes,
         };
 
-        let key_str = serde_json::to_string(&ssh_key)
+        let key_value = serde_json::to_value(&ssh_key)
             .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
 
         // Create item
-        if let Err(e) =
-            rpc::send::<&String, bool>("ssh.import", &vec![&req.args[0].to_lowercase(), &key_str])
-        {
+        if let Err(e) = rpc::send::<Value, bool>(
+            "ssh.import",
+            &vec![Value::from(req.args[0].to_lowercase()), key_value],
+        ) {
             return Err(Error::Generic(format!("Unable to create new SSH key: {}", e)).into());
         }
 
@@ -91,12 +120,56 @@ impl CliCommand for CliSshKeyGenerate {
     fn help(&self) -> CliHelpScreen {
         let mut help = CliHelpScreen::new(
             "Generate SSH Key",
-            "nyx ssh gen <NAME>",
-            "Generates new 4096 bit RSA SSH key.",
+            "nyx ssh gen <NAME> [--type <TYPE>] [--bits <BITS>] [--comment <COMMENT>]",
+            "Generates new SSH key, defaults to Ed25519.",
         );
 
         help.add_param("NAME", "Name of SSH key to generate.");
+        help.add_flag(
+            "--type",
+            "Key type, one of: ed25519, ecdsa-p256, ecdsa-p384, rsa.  Defaults to ed25519.",
+        );
+        help.add_flag(
+            "--bits",
+            "RSA key size, one of: 2048, 3072, 4096, 8192.  Defaults to 4096, only valid with --type rsa.",
+        );
+        help.add_flag(
+            "--comment",
+            "Optional comment embedded in the key (eg. user@host).",
+        );
         help.add_example("nyx ssh gen mysite/cloudflare");
+        help.add_example(
+            "nyx ssh gen mysite/legacy --type rsa --bits 3072 --comment deploy@legacy",
+        );
         help
     }
 }
+
+/// Generate private key of the given type
+fn generate_key(key_type: &str, bits: usize) -> Result<PrivateKey, Error> {
+    let algorithm = match key_type {
+        "ed25519" => Algorithm::Ed25519,
+        "ecdsa-p256" => Algorithm::Ecdsa {
+            curve: EcdsaCurve::NistP256,
+        },
+        "ecdsa-p384" => Algorithm::Ecdsa {
+            curve: EcdsaCurve::NistP384,
+        },
+        "rsa" => {
+            let rsa_key = RsaPrivateKey::new(&mut OsRng, bits)
+                .map_err(|e| Error::Validate(format!("Unable to generate RSA key: {}", e)))?;
+            let rsa_keypair = RsaKeypair::try_from(rsa_key)
+                .map_err(|e| Error::Validate(format!("Unable to convert to RsaKeypair: {}", e)))?;
+            return Ok(PrivateKey::from(rsa_keypair));
+        }
+        _ => {
+            return Err(Error::Validate(format!(
+                "Unsupported key type, {}",
+                key_type
+            )));
+        }
+    };
+
+    PrivateKey::random(&mut OsRng, algorithm)
+        .map_err(|e| Error::Validate(format!("Unable to generate {} key: {}", key_type, e)))
+}
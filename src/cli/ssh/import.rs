@@ -9,8 +9,10 @@ use crate::database::SshKey;
 use crate::error::Error;
 use crate::rpc;
 use falcon_cli::*;
-use ssh_key::PrivateKey;
+use serde_json::Value;
+use ssh_key::{LineEnding, PrivateKey};
 use std::fs;
+use zeroize::Zeroize;
 
 #[derive(Default)]
 pub struct CliSshKeyImport {}
@@ -30,15 +32,32 @@ impl CliCommand for CliSshKeyImport {
 
         // GEt SSH key
         let filename = req.get_flag("--file").ok_or(CliError::MissingFlag("--file".to_string()))?;
-        let private_key = fs::read(&filename).unwrap();
-
-        // Parse SSH key, get public key
-        let privkey =
-            PrivateKey::from_openssh(&String::from_utf8(private_key.clone())?).map_err(|_| {
-                CliError::Generic(
-                    "Invalid private key, please double check and try again.".to_string(),
-                )
+        let pem = fs::read_to_string(&filename)?;
+
+        // Parse SSH key
+        let mut privkey = PrivateKey::from_openssh(&pem).map_err(|_| {
+            CliError::Generic("Invalid private key, please double check and try again.".to_string())
+        })?;
+
+        // Decrypt, if needed
+        if privkey.is_encrypted() {
+            cli_info!("This private key is protected with a passphrase.\n");
+            let mut passphrase = cli_get_password("Key Passphrase: ", false);
+            let decrypted = privkey.decrypt(&passphrase);
+            passphrase.zeroize();
+
+            privkey = decrypted.map_err(|_| {
+                CliError::Generic("Unable to decrypt private key, invalid passphrase.".to_string())
             })?;
+        }
+
+        // Get private and public key
+        let private_key = privkey.to_openssh(LineEnding::LF).map_err(|e| {
+            CliError::Generic(format!(
+                "Unable to convert SSH key to OpenSSH format: {}",
+                e
+            ))
+        })?;
         let public_key = privkey.public_key().to_openssh().map_err(|e| {
             CliError::Generic(format!(
                 "Unable to convert private SSH key to public: {}",
@@ -64,17 +83,18 @@ impl CliCommand for CliSshKeyImport {
             username,
             password,
             public_key,
-            private_key,
+            private_key: private_key.as_bytes().to_vec(),
             notes,
         };
 
-        let key_str = serde_json::to_string(&ssh_key)
+        let key_value = serde_json::to_value(&ssh_key)
             .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
 
         // Create item
-        if let Err(e) =
-            rpc::send::<&String, bool>("ssh.import", &vec![&req.args[0].to_lowercase(), &key_str])
-        {
+        if let Err(e) = rpc::send::<Value, bool>(
+            "ssh.import",
+            &vec![Value::from(req.args[0].to_lowercase()), key_value],
+        ) {
             return Err(Error::Generic(format!("Unable to import SSH key: {}", e)).into());
         }
 
@@ -87,7 +107,7 @@ impl CliCommand for CliSshKeyImport {
         let mut help = CliHelpScreen::new(
             "Import SSH Key",
             "nyx ssh import <NAME> -- file <PEM_FILE>",
-            "Imports a new SSH key, which is then available as the IdentityFile paramter in your ~/.ssh/config file at: /tmp/nyx/ssh_keys/<NAME>, or the virtual directory you specified during database creation.",
+            "Imports a new SSH key, prompting for its passphrase if encrypted.  The key is stored decrypted within the database, and is then available as the IdentityFile paramter in your ~/.ssh/config file at: /tmp/nyx/ssh_keys/<NAME>, or the virtual directory you specified during database creation.",
         );
 
         help.add_param(
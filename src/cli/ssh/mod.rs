@@ -7,6 +7,7 @@
 pub use self::copy::CliSshKeyCopy;
 pub use self::delete::CliSshKeyDelete;
 pub use self::edit::CliSshKeyEdit;
+pub use self::export::CliSshKeyExport;
 pub use self::find::CliSshKeyFind;
 pub use self::generate::CliSshKeyGenerate;
 pub use self::import::CliSshKeyImport;
@@ -22,6 +23,7 @@ pub use self::xv::CliSshKeyXv;
 mod copy;
 mod delete;
 mod edit;
+mod export;
 mod find;
 mod generate;
 mod import;
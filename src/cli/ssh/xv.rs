@@ -25,10 +25,10 @@ impl CliCommand for CliSshKeyXv {
         cli::check_exists("ssh", &req.args[0], true)?;
 
         // Get entry
-        let ssh_key: SshKey = rpc::send("ssh.get", &vec![&req.args[0], &"1".to_string()])?;
+        let mut ssh_key: SshKey = rpc::send("ssh.get", &vec![&req.args[0], &"1".to_string()])?;
 
         // Copy to clipboard
-        let privkey = String::from_utf8(ssh_key.private_key).map_err(|e| {
+        let privkey = String::from_utf8(std::mem::take(&mut ssh_key.private_key)).map_err(|e| {
             CliError::Generic(format!("Unable to encode private key to UTF-8: {}", e))
         })?;
         clipboard::copy(&privkey)?;
@@ -8,6 +8,7 @@ use crate::cli;
 use crate::database::StrItem;
 use crate::rpc;
 use falcon_cli::*;
+use serde_json::Value;
 
 #[derive(Default)]
 pub struct CliStrSet {}
@@ -30,11 +31,14 @@ impl CliCommand for CliStrSet {
             value: req.args[1].to_string(),
         };
 
-        let item_str = serde_json::to_string(&item)
+        let item_value = serde_json::to_value(&item)
             .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
 
         // Create item
-        rpc::send::<&String, bool>("str.set", &vec![&req.args[0].to_lowercase(), &item_str])?;
+        rpc::send::<Value, bool>(
+            "str.set",
+            &vec![Value::from(req.args[0].to_lowercase()), item_value],
+        )?;
         cli_info!("Created new entry, {}", req.args[0]);
 
         Ok(())
@@ -0,0 +1,55 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::cli;
+use crate::database::EntryTags;
+use crate::rpc;
+use falcon_cli::*;
+
+#[derive(Default)]
+pub struct CliTagAdd {}
+
+impl CliCommand for CliTagAdd {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check params
+        if req.args.is_empty() {
+            cli_error!("You did not specify a name of an entry");
+            cli_info!("    Usage: nyx tag <NAME> [<TAG>...] [--type <TYPE>]\n");
+            return Err(CliError::MissingParams.into());
+        }
+        let data_type = super::entry_type(req)?;
+        cli::check_exists(&data_type, &req.args[0], true)?;
+
+        // Show tags, or add them
+        let tags: EntryTags = if req.args.len() == 1 {
+            rpc::send(&format!("{}.tags", data_type), &vec![&req.args[0]])?
+        } else {
+            let add = req.args[1..].join(",");
+            rpc::send(&format!("{}.tag", data_type), &vec![&req.args[0], &add])?
+        };
+
+        super::display(&req.args[0], &tags);
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Tag Entry",
+            "nyx tag <NAME> [<TAG>...] [--type <TYPE>]",
+            "Add tags to entry, or display its tags if none given.  Tags are case insensitive and kept when the entry is copied or renamed.  List entries with a tag via nyx ls --tag <TAG>.",
+        );
+
+        help.add_param("NAME", "Name of entry to tag.");
+        help.add_param("TAG", "One or more tags to add.");
+        help.add_flag(
+            "--type",
+            "Data type of entry (user, otp, ssh, str, note), defaults to user.",
+        );
+        help.add_example("nyx tag mysite/cloudflare prod work");
+        help.add_example("nyx tag mysite/server1 prod --type ssh");
+        help
+    }
+}
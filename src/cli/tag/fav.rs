@@ -0,0 +1,51 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::cli;
+use crate::database::EntryTags;
+use crate::rpc;
+use falcon_cli::*;
+
+#[derive(Default)]
+pub struct CliTagFavorite {}
+
+impl CliCommand for CliTagFavorite {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check params
+        if req.args.is_empty() {
+            cli_error!("You did not specify a name of an entry");
+            cli_info!("    Usage: nyx fav <NAME> [--type <TYPE>]\n");
+            return Err(CliError::MissingParams.into());
+        }
+        let data_type = super::entry_type(req)?;
+        cli::check_exists(&data_type, &req.args[0], true)?;
+
+        // Send RPC
+        let tags: EntryTags = rpc::send(
+            &format!("{}.favorite", data_type),
+            &vec![&req.args[0], &"1".to_string()],
+        )?;
+
+        super::display(&req.args[0], &tags);
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Mark Favorite",
+            "nyx fav <NAME> [--type <TYPE>]",
+            "Mark entry as favorite, listed first by nyx ls and nyx find.",
+        );
+
+        help.add_param("NAME", "Name of entry.");
+        help.add_flag(
+            "--type",
+            "Data type of entry (user, otp, ssh, str, note), defaults to user.",
+        );
+        help.add_example("nyx fav mysite/cloudflare");
+        help
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::database::EntryTags;
+use falcon_cli::*;
+
+pub use self::add::CliTagAdd;
+pub use self::fav::CliTagFavorite;
+pub use self::remove::CliTagRemove;
+pub use self::unfav::CliTagUnfavorite;
+
+mod add;
+mod fav;
+mod remove;
+mod unfav;
+
+/// Get data type of entry from --type flag, defaulting to user
+pub fn entry_type(req: &CliRequest) -> Result<String, CliError> {
+    let data_type = req.get_flag("--type").unwrap_or("user".to_string()).to_lowercase();
+    if !["user", "otp", "ssh", "str", "note"].contains(&data_type.as_str()) {
+        return Err(CliError::Generic(format!(
+            "Invalid type, {}, expected one of user, otp, ssh, str, note",
+            data_type
+        )));
+    }
+    Ok(data_type)
+}
+
+/// Display tags of entry
+pub fn display(name: &str, tags: &EntryTags) {
+    let tag_list = if tags.tags.is_empty() {
+        "(none)".to_string()
+    } else {
+        tags.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<String>>().join(" ")
+    };
+    let data = indexmap! {
+        "Tags:" => tag_list,
+        "Favorite:" => if tags.favorite { "Yes" } else { "No" }.to_string()
+    };
+
+    cli_header(name);
+    cli_display_array(&data);
+}
@@ -0,0 +1,53 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::cli;
+use crate::database::EntryTags;
+use crate::rpc;
+use falcon_cli::*;
+
+#[derive(Default)]
+pub struct CliTagRemove {}
+
+impl CliCommand for CliTagRemove {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check params
+        if req.args.len() < 2 {
+            cli_error!("You did not specify a name of an entry and tags to remove");
+            cli_info!("    Usage: nyx tag rm <NAME> <TAG>... [--type <TYPE>]\n");
+            return Err(CliError::MissingParams.into());
+        }
+        let data_type = super::entry_type(req)?;
+        cli::check_exists(&data_type, &req.args[0], true)?;
+
+        // Send RPC
+        let remove = req.args[1..].join(",");
+        let tags: EntryTags = rpc::send(
+            &format!("{}.tag", data_type),
+            &vec![&req.args[0], &String::new(), &remove],
+        )?;
+
+        super::display(&req.args[0], &tags);
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Remove Tags",
+            "nyx tag rm <NAME> <TAG>... [--type <TYPE>]",
+            "Remove tags from entry.",
+        );
+
+        help.add_param("NAME", "Name of entry to remove tags from.");
+        help.add_param("TAG", "One or more tags to remove.");
+        help.add_flag(
+            "--type",
+            "Data type of entry (user, otp, ssh, str, note), defaults to user.",
+        );
+        help.add_example("nyx tag rm mysite/cloudflare work");
+        help
+    }
+}
@@ -9,6 +9,9 @@ use crate::database::User;
 use crate::rpc;
 use crate::security::password;
 use falcon_cli::*;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
 
 #[derive(Default)]
 pub struct CliUserEdit {}
@@ -28,6 +31,12 @@ impl CliCommand for CliUserEdit {
         // Get user
         let mut user: User = rpc::send("user.get", &vec![&req.args[0]])?;
 
+        // Custom fields and attachments, without prompting for other details
+        if ["--field", "--secret", "--attach", "--remove"].iter().any(|flag| req.has_flag(flag)) {
+            self.edit_fields(req, &mut user)?;
+            return self.save(&req.args[0], &user);
+        }
+
         // Get user info
         cli_header(&format!("Edit {}", req.args[0]));
         cli_info!("Enter the new user information below.  Leave blank to skip a field.\n");
@@ -52,22 +61,93 @@ impl CliCommand for CliUserEdit {
             user.notes = notes;
         }
 
-        let user_str = serde_json::to_string(&user)
-            .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
-
-        // Edit user
-        rpc::send::<&String, bool>("user.edit", &vec![&req.args[0], &user_str])?;
-
-        cli_info!("Updated user info for {}", req.args[0]);
-
-        Ok(())
+        self.save(&req.args[0], &user)
     }
 
     fn help(&self) -> CliHelpScreen {
-        let mut help = CliHelpScreen::new("Edit User", "nyx edit <NAME>", "Edit a user's details.");
+        let mut help = CliHelpScreen::new(
+            "Edit User",
+            "nyx edit <NAME> [--field <FIELD>[=<VALUE>]] [--secret <FIELD>[=<VALUE>]] [--attach <FILE>] [--remove <FIELD>]",
+            "Edit a user's details.  With any of the flags below, only changes the custom fields and attachments of the user, without prompting for other details.  Fields given without a value are prompted for.",
+        );
 
         help.add_param("NAME", "Name of the entry to edit.");
+        help.add_flag(
+            "--field",
+            "Add or replace a plain custom field, included when searching.",
+        );
+        help.add_flag(
+            "--secret",
+            "Add or replace a concealed custom field, masked when shown and excluded from searches.",
+        );
+        help.add_flag("--attach", "Attach file, named after it and replacing any of the same name.  Limited to 1 MB per file and 5 MB per user.");
+        help.add_flag("--remove", "Remove custom field or attachment.");
         help.add_example("nyx edit mysite/cloudflare");
+        help.add_example("nyx edit mybank --field \"Account Number=12345678\" --secret PIN");
+        help.add_example("nyx edit mybank --attach ~/recovery-codes.pdf");
         help
     }
 }
+
+impl CliUserEdit {
+    /// Apply custom field and attachment flags
+    fn edit_fields(&self, req: &CliRequest, user: &mut User) -> anyhow::Result<()> {
+        if let Some(field) = req.get_flag("--field") {
+            let (name, value) = self.split_field(&field, false);
+            user.set_field(&name, &value, false);
+        }
+
+        if let Some(field) = req.get_flag("--secret") {
+            let (name, value) = self.split_field(&field, true);
+            user.set_field(&name, &value, true);
+        }
+
+        if let Some(filename) = req.get_flag("--attach") {
+            let name = Path::new(&filename)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or(filename.to_string());
+            user.set_attachment(&name, fs::read(&filename)?);
+        }
+
+        if let Some(name) = req.get_flag("--remove")
+            && !user.remove_field(&name)
+        {
+            return Err(CliError::Generic(format!(
+                "No custom field or attachment named {} exists.",
+                name
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Split NAME=VALUE, prompting for the value if not given
+    fn split_field(&self, field: &str, concealed: bool) -> (String, String) {
+        if let Some((name, value)) = field.split_once('=') {
+            return (name.trim().to_string(), value.to_string());
+        }
+
+        let prompt = format!("{}: ", field.trim());
+        let value = if concealed {
+            cli_get_password(&prompt, false)
+        } else {
+            cli_get_input(&prompt, "")
+        };
+        (field.trim().to_string(), value)
+    }
+
+    /// Save user
+    fn save(&self, name: &str, user: &User) -> anyhow::Result<()> {
+        let user_value = serde_json::to_value(user)
+            .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
+
+        // Edit user
+        rpc::send::<Value, bool>("user.edit", &vec![Value::from(name), user_value])?;
+
+        cli_info!("Updated user info for {}", name);
+
+        Ok(())
+    }
+}
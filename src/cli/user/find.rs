@@ -13,14 +13,17 @@ pub struct CliUserFind {}
 impl CliCommand for CliUserFind {
     fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
         // Check
-        if req.args.is_empty() {
+        if req.args.is_empty() && !req.has_flag("--tag") {
             cli_info!("You did not specify a search string.");
-            cli_info!("    Usage:  nyx find <SEARCH>\n");
+            cli_info!("    Usage:  nyx find <SEARCH> [--tag <TAG>]\n");
             return Err(CliError::MissingParams.into());
         }
 
         // Send RPC
-        let users: Vec<String> = rpc::send("user.find", &vec![req.args[0].to_string()])?;
+        let search = req.args.first().cloned().unwrap_or_default();
+        let tag = req.get_flag("--tag").unwrap_or_default();
+        let users: Vec<String> =
+            rpc::send("user.find", &vec![search.to_string(), tag.to_string()])?;
 
         // Get table rows
         let rows = users
@@ -30,16 +33,27 @@ impl CliCommand for CliUserFind {
             .collect::<Vec<Vec<String>>>();
 
         // Display table
-        cli_header(&format!("Results for {}", req.args[0]));
+        let title = if tag.is_empty() {
+            search.to_string()
+        } else {
+            format!("{} #{}", search, tag)
+        };
+        cli_header(&format!("Results for {}", title.trim()));
         cli_display_table(&["#", "Name"], &rows);
         Ok(())
     }
 
     fn help(&self) -> CliHelpScreen {
-        let mut help = CliHelpScreen::new("Search Users", "nyx find <TEXT>", "Search all users");
+        let mut help = CliHelpScreen::new(
+            "Search Users",
+            "nyx find [<TEXT>] [--tag <TAG>]",
+            "Search all users",
+        );
 
         help.add_param("TEXT", "The text to search all entries for.");
+        help.add_flag("--tag", "Only list entries with tag.");
         help.add_example("nyx find my-username");
+        help.add_example("nyx find --tag prod");
         help
     }
 }
@@ -0,0 +1,87 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::cli;
+use crate::database::{User, VersionInfo};
+use crate::rpc;
+use chrono::{DateTime, Utc};
+use falcon_cli::*;
+
+#[derive(Default)]
+pub struct CliUserHistory {}
+
+impl CliCommand for CliUserHistory {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check params
+        if req.args.is_empty() {
+            cli_error!("You did not specify a name of a user");
+            cli_info!("    Usage: nyx user history <NAME>\n");
+            return Err(CliError::MissingParams.into());
+        }
+        cli::check_exists("user", &req.args[0], true)?;
+
+        // Get current user and prior versions
+        let current: User = rpc::send("user.get", &vec![&req.args[0]])?;
+        let versions: Vec<VersionInfo> = rpc::send("user.history", &vec![&req.args[0]])?;
+        if versions.is_empty() {
+            cli_info!("No prior versions of {} exist.\n", req.args[0]);
+            return Ok(());
+        }
+
+        // Get table rows, noting fields each version had changed by the one after it
+        let mut newer = current.clone();
+        let mut rows = vec![];
+        for version in versions.iter() {
+            let user: User = serde_json::from_value(version.item.clone())?;
+            let time_str = DateTime::<Utc>::from_timestamp(version.timestamp as i64, 0)
+                .map(|dt| dt.format("%b %d, %Y %H:%M:%S").to_string())
+                .unwrap_or_default();
+
+            rows.push(vec![
+                version.version.to_string(),
+                time_str,
+                user.username.to_string(),
+                user.url.to_string(),
+                changed_fields(&user, &newer).join(", "),
+            ]);
+            newer = user;
+        }
+
+        // Display table
+        cli_header(&format!("History: {}", req.args[0]));
+        cli_display_table(&["#", "Replaced", "Username", "URL", "Changed"], &rows);
+        cli_info!(
+            "Restore a version with:  nyx user revert {} <#>\n",
+            req.args[0]
+        );
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "User History",
+            "nyx user history <NAME>",
+            "Lists prior versions of user kept each time it was edited, newest first, along with which fields were changed.  Passwords are never displayed.",
+        );
+
+        help.add_param("NAME", "Name of entry to list prior versions of.");
+        help.add_example("nyx user history mysite/cloudflare");
+        help
+    }
+}
+
+/// Names of fields that differ between two versions
+fn changed_fields(old: &User, new: &User) -> Vec<&'static str> {
+    [
+        ("username", old.username != new.username),
+        ("password", old.password != new.password),
+        ("url", old.url != new.url),
+        ("notes", old.notes != new.notes),
+    ]
+    .into_iter()
+    .filter_map(|(name, changed)| changed.then_some(name))
+    .collect()
+}
@@ -19,9 +19,10 @@ impl CliCommand for CliUserList {
             String::new()
         };
         let start = req.get_flag("-n").unwrap_or("0".to_string());
+        let tag = req.get_flag("--tag").unwrap_or_default();
 
         // Send RPC
-        let users: Vec<String> = rpc::send("user.list", &vec![&dirname.to_string(), &start])?;
+        let users: Vec<String> = rpc::send("user.list", &vec![&dirname.to_string(), &start, &tag])?;
 
         // Get table rows
         let rows = users
@@ -31,7 +32,11 @@ impl CliCommand for CliUserList {
             .collect::<Vec<Vec<String>>>();
 
         // Display table
-        cli_header(&format!("{}/", dirname));
+        if tag.is_empty() {
+            cli_header(&format!("{}/", dirname));
+        } else {
+            cli_header(&format!("{}/ #{}", dirname, tag));
+        }
         cli_display_table(&["#", "Name"], &rows);
         Ok(())
     }
@@ -39,13 +44,18 @@ impl CliCommand for CliUserList {
     fn help(&self) -> CliHelpScreen {
         let mut help = CliHelpScreen::new(
             "List Users",
-            "nyx ls [<DIRNAME>] [-n XX]",
-            "Lists all users within directory in alphabetical order.",
+            "nyx ls [<DIRNAME>] [-n XX] [--tag <TAG>]",
+            "Lists all users within directory in alphabetical order.  Favorites are listed first.",
         );
 
         help.add_param("DIRNAME", "Optional directory name to list entries from.");
         help.add_flag("-n", "Optional offset / start position of entries.");
+        help.add_flag(
+            "--tag",
+            "Only list entries with tag, by full name, from within directory and below.",
+        );
         help.add_example("nyx ls mysite");
+        help.add_example("nyx ls --tag prod");
         help
     }
 }
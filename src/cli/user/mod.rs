@@ -8,10 +8,13 @@ pub use self::copy::CliUserCopy;
 pub use self::delete::CliUserDelete;
 pub use self::edit::CliUserEdit;
 pub use self::find::CliUserFind;
+pub use self::history::CliUserHistory;
 pub use self::list::CliUserList;
 pub use self::new::CliUserNew;
 pub use self::rename::CliUserRename;
+pub use self::revert::CliUserRevert;
 pub use self::show::CliUserShow;
+pub use self::x::CliUserX;
 pub use self::xp::CliUserXp;
 pub use self::xu::CliUserXu;
 pub use self::xw::CliUserXw;
@@ -20,10 +23,13 @@ mod copy;
 mod delete;
 mod edit;
 mod find;
+mod history;
 mod list;
 mod new;
 mod rename;
+mod revert;
 mod show;
+mod x;
 mod xp;
 mod xu;
 mod xw;
@@ -9,6 +9,7 @@ use crate::database::User;
 use crate::rpc;
 use crate::security::password;
 use falcon_cli::*;
+use serde_json::Value;
 
 #[derive(Default)]
 pub struct CliUserNew {}
@@ -40,13 +41,18 @@ impl CliCommand for CliUserNew {
             password,
             url,
             notes,
+            fields: vec![],
+            attachments: vec![],
         };
 
-        let user_str = serde_json::to_string(&user)
+        let user_value = serde_json::to_value(&user)
             .map_err(|e| CliError::Generic(format!("Unable to serialize JSON object: {}", e)))?;
 
         // Create user
-        rpc::send::<&String, bool>("user.new", &vec![&req.args[0].to_lowercase(), &user_str])?;
+        rpc::send::<Value, bool>(
+            "user.new",
+            &vec![Value::from(req.args[0].to_lowercase()), user_value],
+        )?;
 
         cli_info!("Created new entry, {}", req.args[0]);
 
@@ -0,0 +1,53 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::cli;
+use crate::rpc;
+use falcon_cli::*;
+
+#[derive(Default)]
+pub struct CliUserRevert {}
+
+impl CliCommand for CliUserRevert {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check params
+        if req.args.len() < 2 {
+            cli_error!("You did not specify a user and version number to revert to.");
+            cli_info!("    Usage: nyx user revert <NAME> <VERSION>\n");
+            return Err(CliError::MissingParams.into());
+        }
+        cli::check_exists("user", &req.args[0], true)?;
+
+        // Revert user
+        rpc::send::<String, bool>(
+            "user.revert",
+            &vec![req.args[0].to_string(), req.args[1].to_string()],
+        )?;
+
+        cli_info!(
+            "Reverted {} to version {}, the replaced details are now version 1.\n",
+            req.args[0],
+            req.args[1]
+        );
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Revert User",
+            "nyx user revert <NAME> <VERSION>",
+            "Restores a prior version of user, as numbered by 'nyx user history'.  The details being replaced are kept as the newest prior version, so a revert can itself be undone.",
+        );
+
+        help.add_param("NAME", "Name of entry to revert.");
+        help.add_param(
+            "VERSION",
+            "Number of prior version to restore, 1 being the most recent.",
+        );
+        help.add_example("nyx user revert mysite/cloudflare 1");
+        help
+    }
+}
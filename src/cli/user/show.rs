@@ -5,7 +5,7 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 use crate::cli;
-use crate::database::User;
+use crate::database::{EntryTags, User};
 use crate::rpc;
 use falcon_cli::*;
 
@@ -28,13 +28,43 @@ impl CliCommand for CliUserShow {
         let user: User = rpc::send("user.get", &vec![&req.args[0]])?;
 
         // Get vector
-        let userdata = indexmap! {
-            "Username:" => user.username.to_string(),
-            "Password:" => user.password.to_string(),
-            "URL:" => user.url.to_string(),
-            "Notes:" => user.notes.to_string()
+        let mut userdata = indexmap! {
+            "Username:".to_string() => user.username.to_string(),
+            "Password:".to_string() => user.password.to_string(),
+            "URL:".to_string() => user.url.to_string(),
+            "Notes:".to_string() => user.notes.to_string()
         };
 
+        // Custom fields, masking concealed ones
+        for field in user.fields.iter() {
+            let value = if field.concealed {
+                "********".to_string()
+            } else {
+                field.value.to_string()
+            };
+            userdata.insert(format!("{}:", field.name), value);
+        }
+
+        // Attachments
+        if !user.attachments.is_empty() {
+            let names = user
+                .attachments
+                .iter()
+                .map(|attachment| format!("{} ({} bytes)", attachment.name, attachment.data.len()))
+                .collect::<Vec<String>>();
+            userdata.insert("Attachments:".to_string(), names.join(", "));
+        }
+
+        // Tags
+        let tags: EntryTags = rpc::send("user.tags", &vec![&req.args[0]])?;
+        if !tags.tags.is_empty() {
+            let tag_list = tags.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<String>>();
+            userdata.insert("Tags:".to_string(), tag_list.join(" "));
+        }
+        if tags.favorite {
+            userdata.insert("Favorite:".to_string(), "Yes".to_string());
+        }
+
         // Show user info
         cli_header(&format!("User: {}", req.args[0]));
         cli_display_array(&userdata);
@@ -45,7 +75,7 @@ impl CliCommand for CliUserShow {
         let mut help = CliHelpScreen::new(
             "Show User Details",
             "nyx show <NAME>",
-            "Displays all details on user",
+            "Displays all details on user, with concealed custom fields masked.  Use nyx x to copy them.",
         );
 
         help.add_param("NAME", "Name of entry to show details of.");
@@ -0,0 +1,94 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::cli::{self, clipboard};
+use crate::database::User;
+use crate::rpc;
+use falcon_cli::*;
+use std::fs::OpenOptions;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+#[derive(Default)]
+pub struct CliUserX {}
+
+impl CliCommand for CliUserX {
+    fn process(&self, req: &CliRequest) -> anyhow::Result<()> {
+        // Check params
+        if req.args.len() < 2 {
+            cli_error!("You did not specify a name of a user and field");
+            cli_info!("    Usage: nyx x <NAME> <FIELD> [--file <PATH>]\n");
+            return Err(CliError::MissingParams.into());
+        }
+
+        // Check if user already exists
+        cli::check_exists("user", &req.args[0], true)?;
+
+        // Get user, only logged as a clipboard copy when not saving to file
+        let copy = if req.has_flag("--file") { "0" } else { "1" };
+        let user: User = rpc::send("user.get", &vec![&req.args[0], &copy.to_string()])?;
+
+        // Get field contents
+        let field = req.args[1].to_lowercase();
+        let data = match field.as_str() {
+            "username" => user.username.as_bytes().to_vec(),
+            "password" => user.password.as_bytes().to_vec(),
+            "url" => user.url.as_bytes().to_vec(),
+            "notes" => user.notes.as_bytes().to_vec(),
+            _ => {
+                if let Some(custom) = user.field(&field) {
+                    custom.value.as_bytes().to_vec()
+                } else if let Some(attachment) = user.attachment(&field) {
+                    if !req.has_flag("--file") {
+                        return Err(CliError::Generic(format!(
+                            "{} is an attachment, save it with --file <PATH>",
+                            attachment.name
+                        ))
+                        .into());
+                    }
+                    attachment.data.clone()
+                } else {
+                    return Err(CliError::Generic(format!(
+                        "No field or attachment named {} exists on {}",
+                        req.args[1], req.args[0]
+                    ))
+                    .into());
+                }
+            }
+        };
+
+        // Save to file
+        if let Some(filename) = req.get_flag("--file") {
+            let mut options = OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            options.open(&filename)?.write_all(&data)?;
+            cli_info!("Saved {} of {} to {}\n", req.args[1], req.args[0], filename);
+            return Ok(());
+        }
+
+        // Copy to clipboard
+        clipboard::copy(&String::from_utf8_lossy(&data))?;
+        Ok(())
+    }
+
+    fn help(&self) -> CliHelpScreen {
+        let mut help = CliHelpScreen::new(
+            "Copy User Field",
+            "nyx x <NAME> <FIELD> [--file <PATH>]",
+            "Copy any field of user to clipboard, being username, password, url, notes or the name of a custom field.  Attachments must be saved with the --file flag.",
+        );
+
+        help.add_param("NAME", "Name of entry to copy from.");
+        help.add_param("FIELD", "Name of field or attachment, case insensitive.");
+        help.add_flag("--file", "Save to file instead of copying to clipboard.");
+        help.add_example("nyx x mybank \"Account Number\"");
+        help.add_example("nyx x mybank recovery-codes.pdf --file ~/recovery-codes.pdf");
+        help
+    }
+}
@@ -5,6 +5,8 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 use crate::database::DatabaseTimeout;
+use crate::rpc::logind::LockTriggers;
+use crate::rpc::transport::RpcTransport;
 use falcon_cli::*;
 use std::env;
 use std::process::exit;
@@ -12,11 +14,21 @@ use std::str::FromStr;
 
 pub struct NyxConfig {
     pub dbfile: String,
+    pub db_alias: Option<String>,
     pub host: String,
     pub port: u16,
     pub timeout: Option<DatabaseTimeout>,
+    pub lock_on: LockTriggers,
     pub clipboard_timeout: u64,
     pub fuse_mount_dir: String,
+    pub ssh_agent_sock: String,
+    pub rpc_token_file: String,
+    pub rpc_sock: String,
+    pub rpc_transport: RpcTransport,
+    pub tls: bool,
+    pub tls_cert: String,
+    pub tls_key: String,
+    pub tls_fingerprint: Option<String>,
 }
 
 /// Gather CLI arguments, create config
@@ -26,7 +38,15 @@ pub fn load() -> NyxConfig {
     let mut args: Vec<String> = env::args().collect();
     args.remove(0);
 
+    let mut has_transport = false;
     while !args.is_empty() {
+        // Check for boolean flag
+        if args[0] == "-S" || args[0] == "--tls" {
+            config.tls = true;
+            args.remove(0);
+            continue;
+        }
+
         if args.len() < 2 {
             break;
         }
@@ -34,16 +54,38 @@ pub fn load() -> NyxConfig {
         // Check for value based flag
         match args[0].as_str() {
             "-f" | "--dbfile" => set_dbfile(&args[1], &mut config),
+            "-D" | "--db" => config.db_alias = Some(args[1].to_string()),
             "-h" | "--host" => config.host = args[1].to_string(),
             "-p" | "--port" => set_port(&args[1], &mut config),
             "-t" | "--timeout" => set_timeout(&args[1], &mut config),
+            "-L" | "--lock-on" => set_lock_on(&args[1], &mut config),
             "-c" | "--cb-timeout" => set_clipboard_timeout(&args[1], &mut config),
             "-m" | "--mount-dir" => config.fuse_mount_dir = args[1].to_string(),
+            "-a" | "--agent-sock" => config.ssh_agent_sock = args[1].to_string(),
+            "-k" | "--token-file" => config.rpc_token_file = args[1].to_string(),
+            "-s" | "--rpc-sock" => config.rpc_sock = args[1].to_string(),
+            "-r" | "--rpc-transport" => {
+                set_rpc_transport(&args[1], &mut config);
+                has_transport = true;
+            }
+            "--tls-cert" => config.tls_cert = args[1].to_string(),
+            "--tls-key" => config.tls_key = args[1].to_string(),
+            "--tls-fingerprint" => config.tls_fingerprint = Some(args[1].to_string()),
             _ => {}
         };
         args.drain(0..2);
     }
 
+    // TLS is served over TCP, alongside the Unix socket unless specified
+    if config.tls && !has_transport {
+        config.rpc_transport = RpcTransport::Both;
+    }
+
+    // Token file is specific to the port
+    if config.rpc_token_file.is_empty() {
+        config.rpc_token_file = default_token_file(config.port);
+    }
+
     config
 }
 
@@ -75,6 +117,28 @@ fn set_timeout(value: &str, config: &mut NyxConfig) {
     config.timeout = Some(timeout);
 }
 
+/// Validate and set desktop session events to lock on
+fn set_lock_on(value: &str, config: &mut NyxConfig) {
+    config.lock_on = match LockTriggers::from_str(value) {
+        Ok(r) => r,
+        Err(e) => {
+            cli_error!("{}", e);
+            exit(1);
+        }
+    };
+}
+
+/// Validate and set RPC transport
+fn set_rpc_transport(value: &str, config: &mut NyxConfig) {
+    config.rpc_transport = match RpcTransport::from_str(value) {
+        Ok(r) => r,
+        Err(e) => {
+            cli_error!("{}", e);
+            exit(1);
+        }
+    };
+}
+
 /// Set clipboard timeout
 fn set_clipboard_timeout(secs_str: &str, config: &mut NyxConfig) {
     config.clipboard_timeout = match secs_str.parse::<u64>() {
@@ -86,15 +150,57 @@ fn set_clipboard_timeout(secs_str: &str, config: &mut NyxConfig) {
     };
 }
 
+/// Get default location of SSH agent socket
+fn default_agent_sock() -> String {
+    let mut sock_path = dirs::runtime_dir().unwrap_or_else(env::temp_dir);
+    sock_path.push("nyx-agent.sock");
+    sock_path.to_string_lossy().into_owned()
+}
+
+/// Get default location of RPC socket
+fn default_rpc_sock() -> String {
+    let mut sock_path = match dirs::runtime_dir() {
+        Some(dir) => dir.join("nyx"),
+        None => env::temp_dir().join("nyx-rpc"),
+    };
+    sock_path.push("rpc.sock");
+    sock_path.to_string_lossy().into_owned()
+}
+
+/// Get default location of TLS certificate and key
+fn default_tls_file(filename: &str) -> String {
+    let mut tls_path = dirs::data_dir().unwrap_or_else(env::temp_dir);
+    tls_path.push("nyx/tls");
+    tls_path.push(filename);
+    tls_path.to_string_lossy().into_owned()
+}
+
+/// Get default location of RPC token file
+fn default_token_file(port: u16) -> String {
+    let mut token_path = dirs::runtime_dir().unwrap_or_else(env::temp_dir);
+    token_path.push(format!("nyx-{}.token", port));
+    token_path.to_string_lossy().into_owned()
+}
+
 impl Default for NyxConfig {
     fn default() -> Self {
         Self {
             dbfile: String::new(),
+            db_alias: None,
             host: "127.0.0.1".to_string(),
             port: 7924,
             timeout: None,
+            lock_on: LockTriggers::default(),
             clipboard_timeout: 120,
             fuse_mount_dir: "/tmp/nyx".to_string(),
+            ssh_agent_sock: default_agent_sock(),
+            rpc_token_file: String::new(),
+            rpc_sock: default_rpc_sock(),
+            rpc_transport: RpcTransport::default(),
+            tls: false,
+            tls_cert: default_tls_file("cert.pem"),
+            tls_key: default_tls_file("key.pem"),
+            tls_fingerprint: None,
         }
     }
 }
@@ -5,33 +5,45 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 use crate::Error;
-use crate::rpc::{CmdResponse, message};
+use crate::rpc::{CmdResponse, RpcId, message};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::ops::{Deref, DerefMut};
+use zeroize::Zeroize;
 
 pub trait BaseDbItem {
     fn get_name(&self) -> String;
     fn set_name(&mut self, name: &str);
     fn contains(&self, search: &str) -> bool;
+
+    /// Check item is valid before storing it
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 pub trait BaseDbFunctions:
     Deref<Target = HashMap<String, Self::Item>> + DerefMut<Target = HashMap<String, Self::Item>>
 where
-    Self::Item: Clone + Serialize + for<'a> Deserialize<'a> + BaseDbItem,
+    Self::Item: Clone + Serialize + for<'a> Deserialize<'a> + BaseDbItem + Zeroize,
 {
     type Item;
 
-    fn secure_clear(&mut self);
+    /// Secure clear
+    fn secure_clear(&mut self) {
+        for item in self.values_mut() {
+            item.zeroize();
+        }
+    }
 
     /// Add new item
-    fn add_item(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    fn add_item(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         if params.is_empty() {
             return Err(Error::Validate("Invalid parameters.".to_string()));
         }
 
         let item: Self::Item = serde_json::from_str(&params[1])?;
+        item.validate()?;
 
         // Check if exists
         if self.contains_key(&params[0].to_lowercase()) {
@@ -47,7 +59,7 @@ where
     }
 
     /// Copy item
-    fn copy_item(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    fn copy_item(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         // Validate
         if params.len() < 2 {
             return Err(Error::Validate("Invalid parameters.".to_string()));
@@ -74,7 +86,7 @@ where
     }
 
     /// Delete item
-    fn delete_item(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    fn delete_item(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         // Validate
         if params.is_empty() {
             return Err(Error::Validate("Invalid parameters.".to_string()));
@@ -91,7 +103,7 @@ where
     }
 
     /// Edit item
-    fn edit_item(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    fn edit_item(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         // Ensure item exists
         if params.is_empty() {
             return Err(Error::Validate("Invalid parameters.".to_string()));
@@ -104,6 +116,7 @@ where
 
         // Decode JSON
         let item: Self::Item = serde_json::from_str(&params[1])?;
+        item.validate()?;
 
         // Update
         self.insert(params[0].to_lowercase(), item);
@@ -111,7 +124,7 @@ where
     }
 
     /// Check whether or not item exists
-    fn exists(&self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    fn exists(&self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         if params.is_empty() {
             return Err(Error::Validate("Invalid parameters.".to_string()));
         }
@@ -124,7 +137,7 @@ where
     }
 
     /// Find items
-    fn find_items(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    fn find_items(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         if params.is_empty() {
             return Err(Error::Validate("Invalid parameters.".to_string()));
         }
@@ -143,7 +156,7 @@ where
     }
 
     /// Get single item
-    fn get_item(&self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    fn get_item(&self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         if params.is_empty() {
             return Err(Error::Validate("Invalid parameters.".to_string()));
         }
@@ -155,26 +168,32 @@ where
         )))?;
 
         // Check if password copied
-        let is_copy = params.len() > 1 && params[1].as_str() == "1";
+        let is_copy = params.len() > 1 && ["1", "true"].contains(&params[1].as_str());
         Ok(CmdResponse::new(false, is_copy, message::ok(req_id, item)))
     }
 
     /// List items
-    fn list_items(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
-        // Get dirname
-        let dirname = if params[0].is_empty() {
-            String::new()
-        } else {
-            format!("{}/", params[0])
-        };
-        let (mut dirs, mut files) = (HashSet::new(), vec![]);
-
+    fn list_items(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         let start = if params.len() >= 2 {
             params[1].parse::<usize>().unwrap_or(0)
         } else {
             0
         };
 
+        let items = self.list_names(&params[0], &HashSet::new());
+        Ok(CmdResponse::none(message::ok(req_id, page(items, start))))
+    }
+
+    /// Get names within directory, favorites first, then sub-directories and remaining items
+    fn list_names(&self, dir: &str, favorites: &HashSet<String>) -> Vec<String> {
+        // Get dirname
+        let dirname = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir)
+        };
+        let (mut dirs, mut starred, mut files) = (HashSet::new(), vec![], vec![]);
+
         // Get items
         for value in self.keys() {
             if !value.starts_with(&dirname) {
@@ -186,31 +205,26 @@ where
                 if let Some(short_name) = name_str.split("/").next() {
                     dirs.insert(format!("{}/", short_name));
                 }
+            } else if favorites.contains(value) {
+                starred.push(name_str);
             } else {
                 files.push(name_str);
             }
         }
 
         // Sort and finish items
-        let mut items: Vec<String> = dirs.into_iter().collect();
-        items.sort();
+        let mut dirs: Vec<String> = dirs.into_iter().collect();
+        dirs.sort();
+        starred.sort();
         files.sort();
-        items.extend(files);
 
-        // Get items to display
-        let end = (start + 25).min(items.len());
-        let res = if start >= items.len() {
-            vec![]
-        } else {
-            items[start..end].to_vec()
-        };
-
-        // Return
-        Ok(CmdResponse::none(message::ok(req_id, res)))
+        starred.extend(dirs);
+        starred.extend(files);
+        starred
     }
 
     /// Rename item
-    fn rename_item(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    fn rename_item(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         // Validate
         if params.len() < 2 {
             return Err(Error::Validate("Invalid parameters.".to_string()));
@@ -238,3 +252,12 @@ where
         Ok(CmdResponse::new(true, false, message::ok(req_id, true)))
     }
 }
+
+/// Get page of 25 items from start position
+pub fn page(items: Vec<String>, start: usize) -> Vec<String> {
+    if start >= items.len() {
+        return vec![];
+    }
+    let end = (start + 25).min(items.len());
+    items[start..end].to_vec()
+}
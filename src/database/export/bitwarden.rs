@@ -0,0 +1,170 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::ExportDump;
+use crate::Error;
+use crate::database::User;
+use rand::RngCore;
+use serde_json::{Value, json};
+use ssh_key::{HashAlg, PublicKey};
+use std::collections::BTreeMap;
+
+// Item types within Bitwarden exports
+const TYPE_LOGIN: u8 = 1;
+const TYPE_SECURE_NOTE: u8 = 2;
+const TYPE_SSH_KEY: u8 = 5;
+
+/// Render dump as unencrypted Bitwarden JSON export
+pub fn render(dump: &ExportDump) -> Result<Vec<u8>, Error> {
+    let mut folders: BTreeMap<String, String> = BTreeMap::new();
+    let mut items = vec![];
+
+    // Users, with OTP of the same name
+    for (name, user) in dump.users.iter() {
+        let mut item = new_item(&mut folders, &user.display_name, TYPE_LOGIN, &user.notes);
+        let mut totp = Value::Null;
+        if let Some(otp) = dump.oauth.get(name) {
+            totp = json!(otp.to_uri()?);
+            append_recovery_keys(&mut item, &otp.recovery_keys);
+        }
+
+        item["login"] = json!({
+            "username": user.username,
+            "password": user.password,
+            "totp": totp,
+            "uris": if user.url.is_empty() { json!([]) } else { json!([{ "match": null, "uri": user.url }]) },
+        });
+        append_custom_fields(&mut item, user);
+        items.push(item);
+    }
+
+    // Remaining OTP entries
+    for (_, otp) in dump.oauth.iter().filter(|(name, _)| !dump.users.contains_key(*name)) {
+        let mut item = new_item(&mut folders, &otp.display_name, TYPE_LOGIN, "");
+        append_recovery_keys(&mut item, &otp.recovery_keys);
+        item["login"] = json!({
+            "username": otp.account,
+            "password": null,
+            "totp": otp.to_uri()?,
+            "uris": if otp.url.is_empty() { json!([]) } else { json!([{ "match": null, "uri": otp.url }]) },
+        });
+        items.push(item);
+    }
+
+    // SSH keys
+    for key in dump.ssh_keys.values() {
+        let mut item = new_item(&mut folders, &key.display_name, TYPE_SSH_KEY, &key.notes);
+        let fingerprint = PublicKey::from_openssh(&key.public_key)
+            .map(|pubkey| pubkey.fingerprint(HashAlg::Sha256).to_string())
+            .unwrap_or_default();
+        item["sshKey"] = json!({
+            "privateKey": key.private_key,
+            "publicKey": key.public_key,
+            "keyFingerprint": fingerprint,
+        });
+        item["fields"] = json!([
+            { "name": "host", "value": key.host, "type": 0 },
+            { "name": "port", "value": key.port.to_string(), "type": 0 },
+            { "name": "username", "value": key.username, "type": 0 },
+            { "name": "password", "value": key.password, "type": 1 },
+        ]);
+        items.push(item);
+    }
+
+    // Strings and notes, as secure notes
+    for item in dump.strings.values() {
+        items.push(new_item(
+            &mut folders,
+            &item.display_name,
+            TYPE_SECURE_NOTE,
+            &item.value,
+        ));
+    }
+    for note in dump.notes.values() {
+        items.push(new_item(
+            &mut folders,
+            &note.display_name,
+            TYPE_SECURE_NOTE,
+            &note.note,
+        ));
+    }
+
+    let export = json!({
+        "encrypted": false,
+        "folders": folders.iter().map(|(name, id)| json!({ "id": id, "name": name })).collect::<Vec<Value>>(),
+        "items": items,
+    });
+    Ok(serde_json::to_vec_pretty(&export)?)
+}
+
+/// Create item, registering its folder
+fn new_item(
+    folders: &mut BTreeMap<String, String>,
+    display_name: &str,
+    item_type: u8,
+    notes: &str,
+) -> Value {
+    let (folder_id, name) = match display_name.rsplit_once('/') {
+        Some((folder, name)) => {
+            let id = folders.entry(folder.to_string()).or_insert_with(uuid).to_string();
+            (json!(id), name)
+        }
+        None => (Value::Null, display_name),
+    };
+
+    let mut item = json!({
+        "id": uuid(),
+        "organizationId": null,
+        "folderId": folder_id,
+        "type": item_type,
+        "reprompt": 0,
+        "name": name,
+        "notes": if notes.is_empty() { Value::Null } else { json!(notes) },
+        "favorite": false,
+        "collectionIds": null,
+    });
+    if item_type == TYPE_SECURE_NOTE {
+        item["secureNote"] = json!({ "type": 0 });
+    }
+    item
+}
+
+/// Add OTP recovery keys as hidden field
+fn append_recovery_keys(item: &mut Value, recovery_keys: &str) {
+    if !recovery_keys.is_empty() {
+        item["fields"] = json!([{ "name": "Recovery Keys", "value": recovery_keys, "type": 1 }]);
+    }
+}
+
+/// Append custom fields of user, concealed ones as hidden fields
+fn append_custom_fields(item: &mut Value, user: &User) {
+    let mut fields = item["fields"].as_array().cloned().unwrap_or_default();
+    for field in user.fields.iter() {
+        let field_type = if field.concealed { 1 } else { 0 };
+        fields.push(json!({ "name": field.name, "value": field.value, "type": field_type }));
+    }
+    if !fields.is_empty() {
+        item["fields"] = json!(fields);
+    }
+}
+
+/// Random v4 UUID
+fn uuid() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
@@ -0,0 +1,211 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+// JSON dump format, version 2:
+//
+// {
+//   "format": "nyx",
+//   "version": 2,
+//   "exported_at": <unix timestamp>,
+//   "users":    { "<name>": { display_name, username, password, url, notes,
+//                             fields: [{ name, value, concealed }],
+//                             attachments: [{ name, data (base64) }] } },
+//   "oauth":    { "<name>": { display_name, secret_code, url, recovery_keys, issuer, account,
+//                             kind ("Totp" | "Hotp"), algorithm ("Sha1" | "Sha256" | "Sha512"),
+//                             digits, period, counter } },
+//   "ssh_keys": { "<name>": { display_name, host, port, username, password, public_key,
+//                             private_key (OpenSSH PEM), notes } },
+//   "strings":  { "<name>": { display_name, value } },
+//   "notes":    { "<name>": { display_name, note } },
+//   "tags":     { "<type>/<name>": { tags: [<tag>], favorite } }
+// }
+//
+// Entry names are the lowercase, slash separated names used within Nyx, and types within tags
+// are the RPC method prefixes user, otp, ssh, str and note.  Version 1 has no tags.
+
+use super::{EntryTags, Note, NyxDb, Oauth, SshKey, StrItem, User};
+use crate::Error;
+use crate::rpc::{CmdResponse, RpcId, message};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod bitwarden;
+
+const DUMP_FORMAT: &str = "nyx";
+const DUMP_VERSION: u32 = 2;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Bitwarden,
+    Kdbx,
+}
+
+/// Plaintext dump of all data stores
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ExportDump {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: u64,
+    pub users: BTreeMap<String, User>,
+    pub oauth: BTreeMap<String, Oauth>,
+    pub ssh_keys: BTreeMap<String, ExportSshKey>,
+    pub strings: BTreeMap<String, StrItem>,
+    pub notes: BTreeMap<String, Note>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, EntryTags>,
+}
+
+/// SSH key, with the private key as PEM text
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExportSshKey {
+    pub display_name: String,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub public_key: String,
+    pub private_key: String,
+    pub notes: String,
+}
+
+impl ExportDump {
+    /// Render dump in given format, password is required for KeePass
+    pub fn render(&self, format: ExportFormat, password: &str) -> Result<Vec<u8>, Error> {
+        match format {
+            ExportFormat::Json => Ok(serde_json::to_vec_pretty(self)?),
+            ExportFormat::Bitwarden => bitwarden::render(self),
+            ExportFormat::Kdbx => super::kdbx::write(self, password),
+        }
+    }
+
+    /// Parse JSON dump
+    pub fn from_json(data: &[u8]) -> Result<Self, Error> {
+        let dump: Self = serde_json::from_slice(data)?;
+        if dump.format != DUMP_FORMAT {
+            return Err(Error::Validate(
+                "File is not a Nyx JSON export.".to_string(),
+            ));
+        } else if dump.version > DUMP_VERSION {
+            return Err(Error::Validate(format!(
+                "Nyx export version {} is newer than supported, please upgrade Nyx.",
+                dump.version
+            )));
+        }
+        Ok(dump)
+    }
+
+    /// Total number of entries
+    pub fn len(&self) -> usize {
+        self.users.len()
+            + self.oauth.len()
+            + self.ssh_keys.len()
+            + self.strings.len()
+            + self.notes.len()
+    }
+
+    /// Check whether dump is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl NyxDb {
+    /// Get plaintext dump of entire database
+    pub fn export(&self, req_id: &RpcId, _params: &Vec<String>) -> Result<CmdResponse, Error> {
+        let dump = ExportDump {
+            format: DUMP_FORMAT.to_string(),
+            version: DUMP_VERSION,
+            exported_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            users: collect(&self.users),
+            oauth: collect(&self.oauth),
+            ssh_keys: self
+                .ssh_keys
+                .iter()
+                .map(|(name, key)| (name.to_string(), ExportSshKey::from(key)))
+                .collect(),
+            strings: collect(&self.strings),
+            notes: collect(&self.notes),
+            tags: self
+                .tags
+                .entries
+                .iter()
+                .map(|((data_type, name), tags)| {
+                    (format!("{}/{}", data_type.prefix(), name), tags.clone())
+                })
+                .collect(),
+        };
+
+        Ok(CmdResponse::none(message::ok(req_id, dump)))
+    }
+}
+
+/// Copy data store into sorted map
+fn collect<T: Clone>(items: &HashMap<String, T>) -> BTreeMap<String, T> {
+    items.iter().map(|(name, item)| (name.to_string(), item.clone())).collect()
+}
+
+impl From<&SshKey> for ExportSshKey {
+    fn from(key: &SshKey) -> Self {
+        Self {
+            display_name: key.display_name.to_string(),
+            host: key.host.to_string(),
+            port: key.port,
+            username: key.username.to_string(),
+            password: key.password.to_string(),
+            public_key: key.public_key.to_string(),
+            private_key: String::from_utf8_lossy(&key.private_key).to_string(),
+            notes: key.notes.to_string(),
+        }
+    }
+}
+
+impl From<&ExportSshKey> for SshKey {
+    fn from(key: &ExportSshKey) -> Self {
+        Self {
+            display_name: key.display_name.to_string(),
+            ino: 0,
+            host: key.host.to_string(),
+            port: key.port,
+            username: key.username.to_string(),
+            password: key.password.to_string(),
+            public_key: key.public_key.to_string(),
+            private_key: key.private_key.as_bytes().to_vec(),
+            notes: key.notes.to_string(),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "bitwarden" => Ok(Self::Bitwarden),
+            "kdbx" | "keepass" => Ok(Self::Kdbx),
+            _ => Err(Error::Validate(format!(
+                "Invalid export format '{}', supported formats are: json, bitwarden, kdbx",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Json => "JSON",
+            Self::Bitwarden => "Bitwarden",
+            Self::Kdbx => "KeePass",
+        };
+        write!(f, "{}", name)
+    }
+}
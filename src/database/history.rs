@@ -5,42 +5,100 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 use crate::Error;
-use crate::rpc::{CmdResponse, message};
+use crate::rpc::{CmdResponse, RpcId, message};
 use bincode::{Decode, Encode};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fmt;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Default, Decode, Encode)]
-pub struct HistoryDb(pub Vec<HistoryItem>);
+/// Entries kept in the history log, unless changed
+pub const DEFAULT_MAX_ITEMS: u32 = 10_000;
 
-#[derive(Clone, Decode, Encode, Serialize, Deserialize)]
+/// Hash the first entry of the chain links to
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Decode, Encode)]
+pub struct HistoryDb {
+    pub items: Vec<HistoryItem>,
+    pub max_items: u32,
+    pub max_days: u32,
+    pub anchor: HistoryHead,
+}
+
+#[derive(Clone, Decode, Encode, Serialize, Deserialize, JsonSchema)]
 pub struct HistoryItem {
     pub action: HistoryAction,
     pub data_type: HistoryDataType,
     pub source: String,
     pub dest: String,
     pub timestamp: u64,
+    pub client: String,
+    pub seq: u64,
+    pub hash: String,
 }
 
-#[derive(Decode, Encode, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
+#[derive(Decode, Encode, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, JsonSchema, Debug)]
 pub enum HistoryAction {
     Create,
     Update,
     Delete,
     Copy,
     Rename,
+    Read,
+    Clipboard,
+    Generate,
+    Unlock,
+    UnlockFailed,
 }
 
-#[derive(Decode, Encode, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
+#[derive(
+    Decode, Encode, Eq, PartialEq, Hash, Copy, Clone, Serialize, Deserialize, JsonSchema, Debug,
+)]
 pub enum HistoryDataType {
     User,
     Otp,
     SshKey,
     StrItem,
     Note,
+    Database,
+}
+
+/// Criteria history entries are listed by, all optional
+#[derive(Default)]
+pub struct HistoryFilter {
+    pub data_type: Option<HistoryDataType>,
+    pub action: Option<HistoryAction>,
+    pub prefix: String,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+/// Position and hash of entry within the chain, as attested to by third parties
+#[derive(Clone, Decode, Encode, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryHead {
+    pub seq: u64,
+    pub hash: String,
+    pub timestamp: u64,
+}
+
+/// Result of verifying the chain, valid when no problems were found
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct HistoryVerify {
+    pub entries: usize,
+    pub head: HistoryHead,
+    pub anchor: HistoryHead,
+    pub problems: Vec<String>,
+}
+
+/// Limits on entries kept in the history log, 0 being unlimited
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct HistoryRetention {
+    pub max_items: u32,
+    pub max_days: u32,
 }
 
 impl HistoryDb {
@@ -52,10 +110,6 @@ impl HistoryDb {
         source: &str,
         dest: &str,
     ) -> Result<(), Error> {
-        if action == HistoryAction::Create && data_type == HistoryDataType::Otp {
-            return Ok(());
-        }
-
         self.insert(
             0,
             HistoryItem {
@@ -64,40 +118,282 @@ impl HistoryDb {
                 source: source.to_string(),
                 dest: dest.to_string(),
                 timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+                client: String::new(),
+                seq: 0,
+                hash: String::new(),
             },
         );
 
         Ok(())
     }
 
-    /// List items
+    /// Chain items added since last sealed to the entries before them, then prune beyond limits
+    pub fn seal(&mut self) {
+        let unsealed = self.items.iter().take_while(|item| item.hash.is_empty()).count();
+        let mut prev =
+            self.items.get(unsealed).map(HistoryHead::from).unwrap_or(self.anchor.clone());
+
+        for item in self.items[..unsealed].iter_mut().rev() {
+            item.seq = prev.seq + 1;
+            item.hash = item.compute_hash(&prev.hash);
+            prev = HistoryHead::from(&*item);
+        }
+        self.prune();
+    }
+
+    /// Newest entry of the chain
+    pub fn head(&self) -> HistoryHead {
+        self.items.first().map(HistoryHead::from).unwrap_or(self.anchor.clone())
+    }
+
+    /// Remove oldest items beyond the maximum count or age, anchoring the chain to the last removed
+    fn prune(&mut self) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let cutoff = now.saturating_sub(self.max_days as u64 * 86400);
+
+        while let Some(item) = self.items.last() {
+            let over_count = self.max_items > 0 && self.items.len() > self.max_items as usize;
+            let over_age = self.max_days > 0 && item.timestamp < cutoff;
+            if !over_count && !over_age {
+                break;
+            }
+            self.anchor = HistoryHead::from(item);
+            self.items.pop();
+        }
+    }
+
+    /// List items matching filter
     pub fn list_items(
         &mut self,
-        req_id: usize,
+        req_id: &RpcId,
         params: &Vec<String>,
     ) -> Result<CmdResponse, Error> {
         let start = params[0]
             .parse::<usize>()
             .map_err(|e| Error::Validate(format!("Invalid start number: {}", e)))?;
+        let filter = HistoryFilter::from_params(&params[1..])?;
 
-        let end = (start + 25).min(self.len());
-
-        let items = self[start..end].to_vec();
+        let items: Vec<HistoryItem> =
+            self.iter().filter(|item| filter.matches(item)).skip(start).take(25).cloned().collect();
         Ok(CmdResponse::none(message::ok(req_id, items)))
     }
+
+    /// Set maximum count and age of items, or get them if none given
+    pub fn retention(&mut self, req_id: &RpcId, params: &[String]) -> Result<CmdResponse, Error> {
+        let mut is_modified = false;
+        if let Some(max_items) = params.first().filter(|p| !p.is_empty()) {
+            self.max_items = max_items
+                .parse::<u32>()
+                .map_err(|e| Error::Validate(format!("Invalid maximum entries: {}", e)))?;
+            is_modified = true;
+        }
+        if let Some(max_days) = params.get(1).filter(|p| !p.is_empty()) {
+            self.max_days = max_days
+                .parse::<u32>()
+                .map_err(|e| Error::Validate(format!("Invalid maximum days: {}", e)))?;
+            is_modified = true;
+        }
+        self.prune();
+
+        let retention = HistoryRetention {
+            max_items: self.max_items,
+            max_days: self.max_days,
+        };
+        Ok(CmdResponse::new(
+            is_modified,
+            false,
+            message::ok(req_id, retention),
+        ))
+    }
+
+    /// Verify hash chain, and optionally that attested head is still part of it
+    pub fn verify(&self, req_id: &RpcId, params: &[String]) -> Result<CmdResponse, Error> {
+        let mut problems = vec![];
+
+        // Walk chain oldest first, from the anchor
+        let mut prev = self.anchor.clone();
+        for item in self.items.iter().rev() {
+            if item.seq == prev.seq + 2 {
+                problems.push(format!("Entry {} is missing.", prev.seq + 1));
+            } else if item.seq > prev.seq + 2 {
+                problems.push(format!(
+                    "Entries {} to {} are missing.",
+                    prev.seq + 1,
+                    item.seq - 1
+                ));
+            } else if item.seq <= prev.seq {
+                problems.push(format!(
+                    "Entry {} is out of sequence, following entry {}.",
+                    item.seq, prev.seq
+                ));
+            } else if item.hash != item.compute_hash(&prev.hash) {
+                problems.push(format!(
+                    "Entry {} was altered, its hash does not match.",
+                    item.seq
+                ));
+            }
+            prev = HistoryHead::from(item);
+        }
+
+        // Check attested head
+        if let Some(hash) = params.first().filter(|p| !p.is_empty()) {
+            let seq = params
+                .get(1)
+                .ok_or(Error::Validate(
+                    "No sequence number given for attested head.".to_string(),
+                ))?
+                .parse::<u64>()
+                .map_err(|e| Error::Validate(format!("Invalid sequence number: {}", e)))?;
+
+            let found = if seq == self.anchor.seq {
+                Some(self.anchor.hash.as_str())
+            } else {
+                self.items.iter().find(|item| item.seq == seq).map(|item| item.hash.as_str())
+            };
+            match found {
+                Some(found) if found == hash => {}
+                Some(_) => problems.push(format!(
+                    "Attested entry {} no longer matches, the history was rewritten.",
+                    seq
+                )),
+                None if seq < self.anchor.seq => problems.push(format!(
+                    "Attested entry {} has since been removed by the history limits, unable to check it.",
+                    seq
+                )),
+                None => problems.push(format!("Attested entry {} is missing.", seq)),
+            }
+        }
+
+        let res = HistoryVerify {
+            entries: self.items.len(),
+            head: self.head(),
+            anchor: self.anchor.clone(),
+            problems,
+        };
+        Ok(CmdResponse::none(message::ok(req_id, res)))
+    }
+}
+
+impl HistoryItem {
+    /// Hash of item, chained to the hash of the entry before it
+    pub fn compute_hash(&self, prev_hash: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(prev_hash.as_bytes());
+        hasher.update(self.seq.to_le_bytes());
+        for field in [
+            self.action.to_string(),
+            self.data_type.to_string(),
+            self.source.to_string(),
+            self.dest.to_string(),
+            self.client.to_string(),
+        ] {
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field.as_bytes());
+        }
+        hasher.update(self.timestamp.to_le_bytes());
+
+        hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+impl Default for HistoryDb {
+    fn default() -> Self {
+        Self {
+            items: vec![],
+            max_items: DEFAULT_MAX_ITEMS,
+            max_days: 0,
+            anchor: HistoryHead::default(),
+        }
+    }
+}
+
+impl Default for HistoryHead {
+    fn default() -> Self {
+        Self {
+            seq: 0,
+            hash: GENESIS_HASH.to_string(),
+            timestamp: 0,
+        }
+    }
+}
+
+impl From<&HistoryItem> for HistoryHead {
+    fn from(item: &HistoryItem) -> Self {
+        Self {
+            seq: item.seq,
+            hash: item.hash.to_string(),
+            timestamp: item.timestamp,
+        }
+    }
+}
+
+impl HistoryFilter {
+    /// Get filter from data type, action, name prefix, and since / until timestamps
+    pub fn from_params(params: &[String]) -> Result<Self, Error> {
+        let param = |x: usize| params.get(x).filter(|p| !p.is_empty());
+        let timestamp = |x: usize| -> Result<Option<u64>, Error> {
+            param(x)
+                .map(|p| p.parse::<u64>())
+                .transpose()
+                .map_err(|e| Error::Validate(format!("Invalid timestamp: {}", e)))
+        };
+
+        Ok(Self {
+            data_type: param(0).map(|p| HistoryDataType::from_str(p)).transpose()?,
+            action: param(1).map(|p| HistoryAction::from_str(p)).transpose()?,
+            prefix: param(2).map(|p| p.to_lowercase()).unwrap_or_default(),
+            since: timestamp(3)?,
+            until: timestamp(4)?,
+        })
+    }
+
+    /// Check whether item matches
+    pub fn matches(&self, item: &HistoryItem) -> bool {
+        self.data_type.is_none_or(|data_type| item.data_type == data_type)
+            && self.action.is_none_or(|action| item.action == action)
+            && (item.source.to_lowercase().starts_with(&self.prefix)
+                || item.dest.to_lowercase().starts_with(&self.prefix))
+            && self.since.is_none_or(|since| item.timestamp >= since)
+            && self.until.is_none_or(|until| item.timestamp <= until)
+    }
+}
+
+impl HistoryAction {
+    /// Action recorded for RPC method, if any
+    pub fn from_method(method: &str, is_copy: bool) -> Option<Self> {
+        match method {
+            "get" if is_copy => Some(Self::Clipboard),
+            "get" => Some(Self::Read),
+            "generate" => Some(Self::Generate),
+            "edit" | "revert" | "tag" | "favorite" => Some(Self::Update),
+            "copy" => Some(Self::Copy),
+            "delete" => Some(Self::Delete),
+            "new" | "import" | "set" => Some(Self::Create),
+            "rename" => Some(Self::Rename),
+            _ => None,
+        }
+    }
+
+    /// Whether action changes entries, rather than only being audited
+    pub fn is_change(&self) -> bool {
+        matches!(
+            self,
+            Self::Create | Self::Update | Self::Delete | Self::Copy | Self::Rename
+        )
+    }
 }
 
 impl Deref for HistoryDb {
     type Target = Vec<HistoryItem>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.items
     }
 }
 
 impl DerefMut for HistoryDb {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.items
     }
 }
 
@@ -105,27 +401,47 @@ impl FromStr for HistoryAction {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "edit" => Ok(Self::Update),
-            "copy" => Ok(Self::Copy),
+        match s.to_lowercase().replace(['_', '-'], "").as_str() {
+            "create" => Ok(Self::Create),
+            "update" => Ok(Self::Update),
             "delete" => Ok(Self::Delete),
-            "new" | "import" | "generate"|"set" => Ok(Self::Create),
+            "copy" => Ok(Self::Copy),
             "rename" => Ok(Self::Rename),
+            "read" => Ok(Self::Read),
+            "clipboard" => Ok(Self::Clipboard),
+            "generate" => Ok(Self::Generate),
+            "unlock" => Ok(Self::Unlock),
+            "unlockfailed" => Ok(Self::UnlockFailed),
             _ => Err(Error::Validate(format!("No history action for: {}", s))),
         }
     }
 }
 
+impl HistoryDataType {
+    /// Name of data type, same as its RPC method prefix
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Otp => "otp",
+            Self::SshKey => "ssh",
+            Self::StrItem => "str",
+            Self::Note => "note",
+            Self::Database => "db",
+        }
+    }
+}
+
 impl FromStr for HistoryDataType {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
+        match s.to_lowercase().as_str() {
             "user" => Ok(Self::User),
             "otp" => Ok(Self::Otp),
             "ssh" => Ok(Self::SshKey),
             "str" => Ok(Self::StrItem),
             "note" => Ok(Self::Note),
+            "db" => Ok(Self::Database),
             _ => Err(Error::Validate(format!("No history data type for: {}", s))),
         }
     }
@@ -0,0 +1,110 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::ImportEntry;
+use crate::Error;
+use crate::database::CustomField;
+use serde_json::Value;
+use std::collections::HashMap;
+
+// Item types within Bitwarden exports
+const TYPE_LOGIN: u64 = 1;
+const TYPE_SECURE_NOTE: u64 = 2;
+const TYPE_SSH_KEY: u64 = 5;
+
+// Custom field type holding a hidden value
+const FIELD_HIDDEN: u64 = 1;
+
+/// Parse unencrypted Bitwarden JSON export
+pub fn parse(data: &[u8]) -> Result<Vec<ImportEntry>, Error> {
+    let json: Value = serde_json::from_slice(data)?;
+    if json["encrypted"].as_bool().unwrap_or(false) {
+        return Err(Error::Validate("Encrypted Bitwarden exports are not supported, please export again using the unencrypted JSON format.".to_string()));
+    }
+
+    // Folders
+    let folders: HashMap<&str, &str> = json["folders"]
+        .as_array()
+        .map(|folders| {
+            folders.iter().filter_map(|f| Some((f["id"].as_str()?, f["name"].as_str()?))).collect()
+        })
+        .unwrap_or_default();
+
+    let items = json["items"].as_array().ok_or(Error::Validate(
+        "Invalid Bitwarden export, no items found.".to_string(),
+    ))?;
+
+    let mut entries = vec![];
+    for item in items.iter() {
+        let mut entry = ImportEntry {
+            title: text(&item["name"]),
+            notes: text(&item["notes"]),
+            ..Default::default()
+        };
+
+        if let Some(folder) = item["folderId"].as_str().and_then(|id| folders.get(id)) {
+            entry.folder = folder.split('/').map(|dir| dir.to_string()).collect();
+        }
+
+        // Type specific fields
+        match item["type"].as_u64().unwrap_or(0) {
+            TYPE_LOGIN => {
+                let login = &item["login"];
+                entry.username = text(&login["username"]);
+                entry.password = text(&login["password"]);
+                entry.totp = text(&login["totp"]);
+                entry.url = text(&login["uris"][0]["uri"]);
+            }
+            TYPE_SECURE_NOTE => entry.is_note = true,
+            TYPE_SSH_KEY => entry.ssh_private_key = text(&item["sshKey"]["privateKey"]),
+            _ => {
+                // Cards and identities, stored as notes
+                entry.is_note = true;
+                let details = ["card", "identity"]
+                    .iter()
+                    .filter_map(|key| item[*key].as_object())
+                    .flat_map(|obj| obj.iter())
+                    .filter_map(|(key, value)| Some(format!("{}: {}", key, value.as_str()?)))
+                    .collect::<Vec<String>>();
+                append(&mut entry.notes, &details);
+            }
+        };
+
+        // Custom fields, hidden ones concealed
+        entry.fields = item["fields"]
+            .as_array()
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|f| CustomField {
+                        name: text(&f["name"]),
+                        value: text(&f["value"]),
+                        concealed: f["type"].as_u64() == Some(FIELD_HIDDEN),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Get string value, empty if null
+fn text(value: &Value) -> String {
+    value.as_str().unwrap_or_default().to_string()
+}
+
+/// Append lines to notes
+fn append(notes: &mut String, lines: &[String]) {
+    if lines.is_empty() {
+        return;
+    } else if !notes.is_empty() {
+        notes.push_str("\n\n");
+    }
+    notes.push_str(&lines.join("\n"));
+}
@@ -0,0 +1,86 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::ImportEntry;
+use crate::Error;
+use std::collections::HashMap;
+
+// Column names used by various exports, eg. 1Password, Bitwarden, KeePassXC, browsers
+const COLUMNS: &[(&str, &[&str])] = &[
+    ("title", &["title", "name"]),
+    ("url", &["url", "website", "uri", "login_uri", "web_site"]),
+    (
+        "username",
+        &["username", "user_name", "login_username", "login"],
+    ),
+    ("password", &["password", "login_password"]),
+    (
+        "totp",
+        &["otpauth", "totp", "otp", "login_totp", "one_time_password"],
+    ),
+    ("notes", &["notes", "note", "extra", "comments"]),
+    (
+        "folder",
+        &["folder", "group", "grouping", "category", "vault"],
+    ),
+    ("type", &["type"]),
+];
+
+/// Parse CSV export, mapping columns by header name
+pub fn parse(data: &[u8]) -> Result<Vec<ImportEntry>, Error> {
+    let mut reader = ::csv::ReaderBuilder::new().flexible(true).from_reader(data);
+
+    // Map header columns
+    let headers = reader
+        .headers()
+        .map_err(|e| Error::Validate(format!("Unable to read CSV header: {}", e)))?;
+    let mut columns: HashMap<&str, usize> = HashMap::new();
+    for (x, header) in headers.iter().enumerate() {
+        let header = header.trim().to_lowercase().replace([' ', '-'], "_");
+        if let Some((field, _)) =
+            COLUMNS.iter().find(|(_, aliases)| aliases.contains(&header.as_str()))
+        {
+            columns.entry(field).or_insert(x);
+        }
+    }
+
+    if !["title", "url", "username", "password"].iter().any(|f| columns.contains_key(f)) {
+        return Err(Error::Validate("Unable to identify columns within CSV file, the first line must be a header containing columns such as title, url, username and password.".to_string()));
+    }
+
+    // Parse rows
+    let mut entries = vec![];
+    for record in reader.records() {
+        let record = record.map_err(|e| Error::Validate(format!("Invalid CSV file: {}", e)))?;
+        let get = |field: &str| -> String {
+            columns.get(field).and_then(|x| record.get(*x)).unwrap_or_default().trim().to_string()
+        };
+
+        // Folders, KeePassXC prefixes all groups with Root
+        let mut folder: Vec<String> = get("folder")
+            .split('/')
+            .filter(|dir| !dir.trim().is_empty())
+            .map(|dir| dir.to_string())
+            .collect();
+        if folder.first().map(|dir| dir == "Root").unwrap_or(false) {
+            folder.remove(0);
+        }
+
+        entries.push(ImportEntry {
+            folder,
+            title: get("title"),
+            username: get("username"),
+            password: get("password"),
+            url: get("url"),
+            notes: get("notes"),
+            totp: get("totp"),
+            is_note: get("type").to_lowercase().contains("note"),
+            ..Default::default()
+        });
+    }
+
+    Ok(entries)
+}
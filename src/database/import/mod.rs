@@ -0,0 +1,549 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::users::BUILTIN_FIELDS;
+use super::{
+    BaseDbFunctions, BaseDbItem, CustomField, EntryTags, ExportDump, HistoryAction,
+    HistoryDataType, Note, NyxDb, Oauth, OtpAlgorithm, SshKey, StrItem, User,
+};
+use crate::Error;
+use crate::rpc::{CmdResponse, RpcId, message};
+use crate::security::crypto;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ssh_key::{LineEnding, PrivateKey};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+use zeroize::Zeroize;
+
+mod bitwarden;
+mod csv;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImportFormat {
+    Json,
+    Kdbx,
+    Bitwarden,
+    OnePassword,
+    Csv,
+}
+
+/// Single entry read from another password manager, prior to mapping
+#[derive(Default)]
+pub struct ImportEntry {
+    pub folder: Vec<String>,
+    pub title: String,
+    pub username: String,
+    pub password: String,
+    pub url: String,
+    pub notes: String,
+    pub totp: String,
+    pub ssh_private_key: String,
+    pub fields: Vec<CustomField>,
+    pub is_note: bool,
+}
+
+/// Entries mapped to Nyx items, sent to the daemon for import
+#[derive(Default, Serialize, Deserialize, JsonSchema)]
+pub struct ImportBundle {
+    pub users: Vec<User>,
+    pub oauth: Vec<Oauth>,
+    pub ssh_keys: Vec<SshKey>,
+    pub strings: Vec<StrItem>,
+    pub notes: Vec<Note>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, EntryTags>,
+    pub warnings: Vec<String>,
+    #[serde(skip)]
+    names: HashSet<String>,
+}
+
+#[derive(Default, Serialize, Deserialize, JsonSchema)]
+pub struct ImportSummary {
+    pub users: usize,
+    pub oauth: usize,
+    pub ssh_keys: usize,
+    pub strings: usize,
+    pub notes: usize,
+    pub skipped: Vec<String>,
+}
+
+impl ImportBundle {
+    /// Parse export file of another password manager
+    pub fn parse(format: ImportFormat, data: &[u8], password: &str) -> Result<Self, Error> {
+        let entries = match format {
+            ImportFormat::Json => return Self::from_dump(data, password),
+            ImportFormat::Kdbx => super::kdbx::parse(data, password)?,
+            ImportFormat::Bitwarden => bitwarden::parse(data)?,
+            ImportFormat::OnePassword | ImportFormat::Csv => csv::parse(data)?,
+        };
+
+        let mut bundle = Self::default();
+        for entry in entries {
+            bundle.add(entry);
+        }
+        Ok(bundle)
+    }
+
+    /// Check whether a password is required to read the file
+    pub fn needs_password(format: ImportFormat, data: &[u8]) -> bool {
+        match format {
+            ImportFormat::Kdbx => true,
+            ImportFormat::Json => is_encrypted(data),
+            _ => false,
+        }
+    }
+
+    /// Load Nyx JSON export, decrypting it first if needed
+    fn from_dump(data: &[u8], password: &str) -> Result<Self, Error> {
+        let dump = if is_encrypted(data) {
+            let mut decrypted = crypto::decrypt(data, crypto::normalize_password(password))?;
+            let dump = ExportDump::from_json(&decrypted);
+            decrypted.zeroize();
+            dump?
+        } else {
+            ExportDump::from_json(data)?
+        };
+
+        Ok(Self {
+            users: dump.users.into_values().collect(),
+            oauth: dump.oauth.into_values().collect(),
+            ssh_keys: dump.ssh_keys.values().map(SshKey::from).collect(),
+            strings: dump.strings.into_values().collect(),
+            notes: dump.notes.into_values().collect(),
+            tags: dump.tags,
+            ..Default::default()
+        })
+    }
+
+    /// Total number of items within bundle
+    pub fn len(&self) -> usize {
+        self.users.len()
+            + self.oauth.len()
+            + self.ssh_keys.len()
+            + self.strings.len()
+            + self.notes.len()
+    }
+
+    /// Check whether bundle is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Map entry to the appropriate Nyx item types
+    fn add(&mut self, mut entry: ImportEntry) {
+        let name = entry.name();
+
+        // SSH key, with connection details from custom fields as exported by Bitwarden
+        if !entry.ssh_private_key.is_empty() {
+            if entry.url.is_empty()
+                && let Some(host) = entry.take_field("host").filter(|host| !host.is_empty())
+            {
+                let port = entry.take_field("port").unwrap_or("22".to_string());
+                entry.url = format!("ssh://{}:{}", host, port);
+            }
+            if entry.username.is_empty() {
+                entry.username = entry.take_field("username").unwrap_or_default();
+            }
+            if entry.password.is_empty() {
+                entry.password = entry.take_field("password").unwrap_or_default();
+            }
+            entry.fields_to_notes();
+
+            match entry.to_ssh_key() {
+                Ok(mut ssh_key) => {
+                    ssh_key.display_name = self.unique_name("ssh", &name);
+                    self.ssh_keys.push(ssh_key);
+                    return;
+                }
+                Err(e) => self.warnings.push(format!("{}: {}", name, e)),
+            };
+        }
+
+        // Secure note
+        if entry.is_note
+            || (entry.username.is_empty()
+                && entry.password.is_empty()
+                && entry.url.is_empty()
+                && entry.totp.is_empty())
+        {
+            entry.fields_to_notes();
+            if entry.notes.is_empty() {
+                self.warnings.push(format!("{}: Entry is empty, skipping", name));
+                return;
+            }
+
+            let display_name = self.unique_name("note", &name);
+            self.notes.push(Note {
+                display_name,
+                note: entry.notes,
+            });
+            return;
+        }
+
+        // OTP
+        if !entry.totp.is_empty() {
+            match parse_totp(&entry.totp) {
+                Ok(mut otp) => {
+                    otp.display_name = self.unique_name("otp", &name);
+                    otp.recovery_keys = entry.take_field("Recovery Keys").unwrap_or_default();
+                    self.oauth.push(otp);
+                }
+                Err(e) => self.warnings.push(format!("{}: {}", name, e)),
+            };
+
+            if entry.username.is_empty() && entry.password.is_empty() && entry.fields.is_empty() {
+                return;
+            }
+        }
+
+        // User, keeping fields it can not hold within notes
+        if !entry.has_valid_fields() {
+            entry.fields_to_notes();
+        }
+        let display_name = self.unique_name("user", &name);
+        self.users.push(User {
+            display_name,
+            username: entry.username,
+            password: entry.password,
+            url: entry.url,
+            notes: entry.notes,
+            fields: entry.fields,
+            attachments: vec![],
+        });
+    }
+
+    /// Check every item and tag of bundle is valid, with no name used twice
+    fn validate(&self) -> Result<(), Error> {
+        let mut names = HashSet::new();
+        check_items(HistoryDataType::User, &self.users, &mut names)?;
+        check_items(HistoryDataType::Otp, &self.oauth, &mut names)?;
+        check_items(HistoryDataType::SshKey, &self.ssh_keys, &mut names)?;
+        check_items(HistoryDataType::StrItem, &self.strings, &mut names)?;
+        check_items(HistoryDataType::Note, &self.notes, &mut names)?;
+
+        for key in self.tags.keys() {
+            match key.split_once('/') {
+                Some((data_type, _)) => HistoryDataType::from_str(data_type)?,
+                None => return Err(Error::Validate(format!("Invalid tags entry, {}", key))),
+            };
+        }
+        Ok(())
+    }
+
+    /// Get name not yet used within the bundle for given data type
+    fn unique_name(&mut self, data_type: &str, name: &str) -> String {
+        let mut unique = name.to_string();
+        let mut x = 2;
+        while self.names.contains(&format!("{}:{}", data_type, unique.to_lowercase())) {
+            unique = format!("{}-{}", name, x);
+            x += 1;
+        }
+
+        self.names.insert(format!("{}:{}", data_type, unique.to_lowercase()));
+        unique
+    }
+}
+
+impl ImportEntry {
+    /// Slash separated entry name, folders followed by title
+    fn name(&self) -> String {
+        let mut title = sanitize(&self.title);
+        if title.is_empty() {
+            title = Url::parse(&self.url)
+                .ok()
+                .and_then(|url| url.host_str().map(sanitize))
+                .unwrap_or("untitled".to_string());
+        }
+
+        let mut parts: Vec<String> =
+            self.folder.iter().map(|dir| sanitize(dir)).filter(|dir| !dir.is_empty()).collect();
+        parts.push(title);
+        parts.join("/")
+    }
+
+    /// Remove custom field, returning its value
+    fn take_field(&mut self, name: &str) -> Option<String> {
+        let pos = self.fields.iter().position(|f| f.name.eq_ignore_ascii_case(name))?;
+        Some(self.fields.remove(pos).value.to_string())
+    }
+
+    /// Append custom fields to notes, for items which have no fields of their own
+    fn fields_to_notes(&mut self) {
+        if self.fields.is_empty() {
+            return;
+        } else if !self.notes.is_empty() {
+            self.notes.push_str("\n\n");
+        }
+        let lines: Vec<String> =
+            self.fields.drain(..).map(|f| format!("{}: {}", f.name, f.value)).collect();
+        self.notes.push_str(&lines.join("\n"));
+    }
+
+    /// Check no custom field is unnamed, or named as a built in field or another field
+    fn has_valid_fields(&self) -> bool {
+        let mut names = HashSet::new();
+        self.fields.iter().all(|field| {
+            let name = field.name.trim().to_lowercase();
+            !name.is_empty() && !BUILTIN_FIELDS.contains(&name.as_str()) && names.insert(name)
+        })
+    }
+
+    /// Convert to SSH key, decrypting with the entry password if needed
+    fn to_ssh_key(&self) -> Result<SshKey, Error> {
+        let mut privkey = PrivateKey::from_openssh(self.ssh_private_key.trim())
+            .map_err(|_| Error::Validate("Invalid SSH private key attachment".to_string()))?;
+
+        if privkey.is_encrypted() {
+            privkey = privkey.decrypt(&self.password).map_err(|_| {
+                Error::Validate("Unable to decrypt SSH private key with entry password".to_string())
+            })?;
+        }
+
+        let private_key = privkey
+            .to_openssh(LineEnding::LF)
+            .map_err(|e| Error::Validate(format!("Unable to convert SSH key: {}", e)))?;
+        let public_key = privkey
+            .public_key()
+            .to_openssh()
+            .map_err(|e| Error::Validate(format!("Unable to convert SSH key: {}", e)))?;
+
+        // Host and port, from URL
+        let (host, port) = match Url::parse(&self.url) {
+            Ok(url) if url.host_str().is_some() => (
+                url.host_str().unwrap_or_default().to_string(),
+                url.port().unwrap_or(22),
+            ),
+            _ => (self.url.to_string(), 22),
+        };
+
+        Ok(SshKey {
+            display_name: String::new(),
+            ino: 0,
+            host,
+            port,
+            username: self.username.to_string(),
+            password: self.password.to_string(),
+            public_key,
+            private_key: private_key.as_bytes().to_vec(),
+            notes: self.notes.to_string(),
+        })
+    }
+}
+
+impl NyxDb {
+    /// Import bundle of entries, skipping any that already exist
+    pub fn import(&mut self, req_id: &RpcId, params: &[String]) -> Result<CmdResponse, Error> {
+        if params.is_empty() {
+            return Err(Error::Validate("Invalid parameters.".to_string()));
+        }
+        let bundle: ImportBundle = serde_json::from_str(&params[0])?;
+        let dry_run = params.get(1).map(|p| p == "dry-run").unwrap_or(false);
+
+        // Check whole bundle first, so a failed import leaves the database untouched
+        bundle.validate()?;
+        let mut summary = ImportSummary::default();
+        let mut imported = HashSet::new();
+
+        // Users
+        for item in bundle.users.iter() {
+            let name = item.display_name.to_string();
+            if self.users.contains_key(&name.to_lowercase()) {
+                summary.skipped.push(format!("user {}", name));
+                continue;
+            } else if !dry_run {
+                self.users.add_item(
+                    req_id,
+                    &vec![name.to_string(), serde_json::to_string(item)?],
+                )?;
+                self.history.add(HistoryAction::Create, HistoryDataType::User, &name, "")?;
+            }
+            summary.users += 1;
+            imported.insert((HistoryDataType::User, name.to_lowercase()));
+        }
+
+        // OTP
+        for item in bundle.oauth.iter() {
+            let name = item.display_name.to_string();
+            if self.oauth.contains_key(&name.to_lowercase()) {
+                summary.skipped.push(format!("otp {}", name));
+                continue;
+            } else if !dry_run {
+                self.oauth.add_item(
+                    req_id,
+                    &vec![name.to_string(), serde_json::to_string(item)?],
+                )?;
+                self.history.add(HistoryAction::Create, HistoryDataType::Otp, &name, "")?;
+            }
+            summary.oauth += 1;
+            imported.insert((HistoryDataType::Otp, name.to_lowercase()));
+        }
+
+        // SSH keys
+        for item in bundle.ssh_keys.iter() {
+            let name = item.display_name.to_string();
+            if self.ssh_keys.contains_key(&name.to_lowercase()) {
+                summary.skipped.push(format!("ssh {}", name));
+                continue;
+            } else if !dry_run {
+                self.ssh_keys.import(
+                    req_id,
+                    &vec![name.to_string(), serde_json::to_string(item)?],
+                )?;
+                self.history.add(HistoryAction::Create, HistoryDataType::SshKey, &name, "")?;
+            }
+            summary.ssh_keys += 1;
+            imported.insert((HistoryDataType::SshKey, name.to_lowercase()));
+        }
+
+        // Strings
+        for item in bundle.strings.iter() {
+            let name = item.display_name.to_string();
+            if self.strings.contains_key(&name.to_lowercase()) {
+                summary.skipped.push(format!("str {}", name));
+                continue;
+            } else if !dry_run {
+                self.strings.add_item(
+                    req_id,
+                    &vec![name.to_string(), serde_json::to_string(item)?],
+                )?;
+                self.history.add(HistoryAction::Create, HistoryDataType::StrItem, &name, "")?;
+            }
+            summary.strings += 1;
+            imported.insert((HistoryDataType::StrItem, name.to_lowercase()));
+        }
+
+        // Notes
+        for item in bundle.notes.iter() {
+            let name = item.display_name.to_string();
+            if self.notes.contains_key(&name.to_lowercase()) {
+                summary.skipped.push(format!("note {}", name));
+                continue;
+            } else if !dry_run {
+                self.notes.add_item(
+                    req_id,
+                    &vec![name.to_string(), serde_json::to_string(item)?],
+                )?;
+                self.history.add(HistoryAction::Create, HistoryDataType::Note, &name, "")?;
+            }
+            summary.notes += 1;
+            imported.insert((HistoryDataType::Note, name.to_lowercase()));
+        }
+
+        // Tags of imported entries
+        for (key, tags) in bundle.tags.iter() {
+            let Some((data_type, name)) = key.split_once('/') else {
+                continue;
+            };
+            let key = (HistoryDataType::from_str(data_type)?, name.to_lowercase());
+            if !dry_run && imported.contains(&key) {
+                self.tags.entries.insert(key, tags.clone());
+            }
+        }
+
+        let is_modified = !dry_run && summary.total() > 0;
+        Ok(CmdResponse::new(
+            is_modified,
+            false,
+            message::ok(req_id, summary),
+        ))
+    }
+}
+
+impl ImportSummary {
+    /// Total number of items imported
+    pub fn total(&self) -> usize {
+        self.users + self.oauth + self.ssh_keys + self.strings + self.notes
+    }
+}
+
+/// Parse TOTP field, either otpauth URI, KeeOtp style parameters or base32 secret
+fn parse_totp(value: &str) -> Result<Oauth, Error> {
+    let value = value.trim();
+    if value.starts_with("otpauth://") {
+        return Oauth::from_uri(value);
+    }
+
+    let mut otp = Oauth::default();
+    if value.contains("key=") {
+        for (key, val) in url::form_urlencoded::parse(value.as_bytes()) {
+            match key.as_ref() {
+                "key" => otp.secret_code = val.to_uppercase(),
+                "step" => otp.period = val.parse().unwrap_or(otp.period),
+                "size" => otp.digits = val.parse().unwrap_or(otp.digits),
+                "otpHashMode" => otp.algorithm = OtpAlgorithm::from_str(&val)?,
+                _ => {}
+            };
+        }
+    } else {
+        otp.secret_code = value.replace(' ', "").to_uppercase();
+    }
+
+    otp.validate().map_err(|e| Error::Validate(format!("Unsupported TOTP value, {}", e)))?;
+    Ok(otp)
+}
+
+/// Validate items of one data type, and check none share a name
+fn check_items<T: BaseDbItem>(
+    data_type: HistoryDataType,
+    items: &[T],
+    names: &mut HashSet<(HistoryDataType, String)>,
+) -> Result<(), Error> {
+    for item in items.iter() {
+        item.validate().map_err(|e| Error::Validate(format!("{}: {}", item.get_name(), e)))?;
+        if !names.insert((data_type, item.get_name().to_lowercase())) {
+            return Err(Error::Validate(format!(
+                "More than one {} entry to import is named {}.",
+                data_type.prefix(),
+                item.get_name()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Check whether Nyx JSON export was encrypted with a passphrase
+fn is_encrypted(data: &[u8]) -> bool {
+    data.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'{')
+}
+
+/// Sanitize single component of entry name
+fn sanitize(value: &str) -> String {
+    value.trim().replace('/', "-").split_whitespace().collect::<Vec<&str>>().join("-")
+}
+
+impl FromStr for ImportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" | "nyx" => Ok(Self::Json),
+            "kdbx" | "keepass" => Ok(Self::Kdbx),
+            "bitwarden" => Ok(Self::Bitwarden),
+            "1password" | "onepassword" => Ok(Self::OnePassword),
+            "csv" => Ok(Self::Csv),
+            _ => Err(Error::Validate(format!(
+                "Invalid import format '{}', supported formats are: json, kdbx, bitwarden, 1password, csv",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Json => "Nyx JSON",
+            Self::Kdbx => "KeePass",
+            Self::Bitwarden => "Bitwarden",
+            Self::OnePassword => "1Password",
+            Self::Csv => "CSV",
+        };
+        write!(f, "{}", name)
+    }
+}
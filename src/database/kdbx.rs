@@ -0,0 +1,840 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::CustomField;
+use super::export::ExportDump;
+use super::import::ImportEntry;
+use crate::Error;
+use aes::Aes256;
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::{
+    BlockDecryptMut, BlockEncrypt, BlockEncryptMut, KeyInit, KeyIvInit, StreamCipher,
+};
+use base64::{Engine as _, engine::general_purpose};
+use chacha20::ChaCha20;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use roxmltree::{Document, Node, NodeId};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use zeroize::Zeroize;
+
+const SIGNATURE_1: u32 = 0x9AA2D903;
+const SIGNATURE_2: u32 = 0xB54BFB67;
+const BLOCK_SIZE: usize = 1024 * 1024;
+
+// Outer header fields
+const HEADER_END: u8 = 0;
+const HEADER_CIPHER_ID: u8 = 2;
+const HEADER_COMPRESSION: u8 = 3;
+const HEADER_MASTER_SEED: u8 = 4;
+const HEADER_IV: u8 = 7;
+const HEADER_KDF_PARAMS: u8 = 11;
+
+// Inner header fields
+const INNER_END: u8 = 0;
+const INNER_STREAM_ID: u8 = 1;
+const INNER_STREAM_KEY: u8 = 2;
+const INNER_BINARY: u8 = 3;
+const STREAM_CHACHA20: u32 = 3;
+
+// Cipher and KDF UUIDs
+const CIPHER_AES256: [u8; 16] = [
+    0x31, 0xc1, 0xf2, 0xe6, 0xbf, 0x71, 0x43, 0x50, 0xbe, 0x58, 0x05, 0x21, 0x6a, 0xfc, 0x5a, 0xff,
+];
+const CIPHER_CHACHA20: [u8; 16] = [
+    0xd6, 0x03, 0x8a, 0x2b, 0x8b, 0x6f, 0x4c, 0xb5, 0xa5, 0x24, 0x33, 0x9a, 0x31, 0xdb, 0xb5, 0x9a,
+];
+const KDF_AES: [u8; 16] = [
+    0xc9, 0xd9, 0xf3, 0x9a, 0x62, 0x8a, 0x44, 0x60, 0xbf, 0x74, 0x0d, 0x08, 0xc1, 0x8a, 0x4f, 0xea,
+];
+const KDF_ARGON2D: [u8; 16] = [
+    0xef, 0x63, 0x6d, 0xdf, 0x8c, 0x29, 0x44, 0x4b, 0x91, 0xf7, 0xa9, 0xa4, 0x03, 0xe3, 0x0a, 0x0c,
+];
+const KDF_ARGON2ID: [u8; 16] = [
+    0x9e, 0x29, 0x8b, 0x19, 0x56, 0xdb, 0x47, 0x73, 0xb2, 0x3d, 0xfc, 0x3e, 0xc6, 0xf0, 0xa1, 0xe6,
+];
+
+// Fields mapped directly, all others are imported as custom fields
+const KNOWN_FIELDS: &[&str] = &[
+    "Title",
+    "UserName",
+    "Password",
+    "URL",
+    "Notes",
+    "otp",
+    "TOTP Seed",
+    "TOTP Settings",
+];
+
+/// Parse KeePass KDBX 4 database
+pub fn parse(data: &[u8], password: &str) -> Result<Vec<ImportEntry>, Error> {
+    let mut reader = Reader(data);
+
+    // Signature and version
+    if reader.u32()? != SIGNATURE_1 || reader.u32()? != SIGNATURE_2 {
+        return Err(Error::Validate(
+            "File is not a KeePass database.".to_string(),
+        ));
+    }
+    let major_version = reader.u32()? >> 16;
+    if major_version < 4 {
+        return Err(Error::Validate("Only KDBX 4 databases are supported, please open the database in KeePassXC or KeePass 2.35+ and save it as KDBX 4 first.".to_string()));
+    } else if major_version > 4 {
+        return Err(Error::Validate(format!(
+            "Unsupported KDBX version {}",
+            major_version
+        )));
+    }
+
+    // Outer header
+    let mut header = HashMap::new();
+    loop {
+        let field_id = reader.u8()?;
+        let size = reader.u32()? as usize;
+        let value = reader.take(size)?;
+        if field_id == HEADER_END {
+            break;
+        }
+        header.insert(field_id, value);
+    }
+    let header_bytes = &data[..data.len() - reader.0.len()];
+    let get_header = |field_id: u8| {
+        header.get(&field_id).copied().ok_or(Error::Validate(
+            "Invalid KeePass database, missing header field.".to_string(),
+        ))
+    };
+
+    // Verify header checksum
+    let header_hash = reader.take(32)?;
+    if Sha256::digest(header_bytes).as_slice() != header_hash {
+        return Err(Error::Validate(
+            "KeePass database header is corrupted.".to_string(),
+        ));
+    }
+
+    // Derive keys
+    let (mut cipher_key, mut hmac_base) = derive_keys(
+        password,
+        get_header(HEADER_MASTER_SEED)?,
+        get_header(HEADER_KDF_PARAMS)?,
+    )?;
+
+    // Verify header HMAC, confirms password
+    let header_hmac = reader.take(32)?;
+    if block_hmac(&hmac_base, u64::MAX, &[header_bytes]).verify_slice(header_hmac).is_err() {
+        return Err(Error::Validate("Invalid KeePass password.".to_string()));
+    }
+
+    // Read HMAC block stream
+    let mut ciphertext = vec![];
+    for index in 0u64.. {
+        let hmac = reader.take(32)?;
+        let size_bytes = reader.take(4)?;
+        let size = u32::from_le_bytes(size_bytes.try_into().unwrap()) as usize;
+        let block = reader.take(size)?;
+
+        let parts: [&[u8]; 3] = [&index.to_le_bytes(), size_bytes, block];
+        if block_hmac(&hmac_base, index, &parts).verify_slice(hmac).is_err() {
+            return Err(Error::Validate(
+                "KeePass database is corrupted, block HMAC mismatch.".to_string(),
+            ));
+        } else if size == 0 {
+            break;
+        }
+        ciphertext.extend_from_slice(block);
+    }
+    hmac_base.zeroize();
+
+    // Decrypt
+    let iv = get_header(HEADER_IV)?;
+    let mut payload = match get_header(HEADER_CIPHER_ID)? {
+        id if id == CIPHER_AES256 => cbc::Decryptor::<Aes256>::new_from_slices(&cipher_key, iv)
+            .map_err(|e| Error::Crypto(format!("Invalid KeePass cipher parameters: {}", e)))?
+            .decrypt_padded_vec_mut::<Pkcs7>(&ciphertext)
+            .map_err(|_| Error::Crypto("Unable to decrypt KeePass database.".to_string()))?,
+        id if id == CIPHER_CHACHA20 => {
+            let mut cipher = ChaCha20::new_from_slices(&cipher_key, iv)
+                .map_err(|e| Error::Crypto(format!("Invalid KeePass cipher parameters: {}", e)))?;
+            cipher.apply_keystream(&mut ciphertext);
+            ciphertext
+        }
+        _ => {
+            return Err(Error::Validate(
+                "Unsupported KeePass cipher, only AES-256 and ChaCha20 databases can be imported."
+                    .to_string(),
+            ));
+        }
+    };
+    cipher_key.zeroize();
+
+    // Decompress
+    if get_header(HEADER_COMPRESSION)? == 1u32.to_le_bytes() {
+        let mut decompressed = vec![];
+        GzDecoder::new(payload.as_slice()).read_to_end(&mut decompressed).map_err(|e| {
+            Error::Validate(format!("Unable to decompress KeePass database: {}", e))
+        })?;
+        payload.zeroize();
+        payload = decompressed;
+    }
+
+    // Inner header
+    let mut reader = Reader(&payload);
+    let (mut stream_id, mut stream_key, mut binaries) = (0, vec![], vec![]);
+    loop {
+        let field_id = reader.u8()?;
+        let size = reader.u32()? as usize;
+        let value = reader.take(size)?;
+        match field_id {
+            INNER_END => break,
+            INNER_STREAM_ID if size == 4 => {
+                stream_id = u32::from_le_bytes(value.try_into().unwrap())
+            }
+            INNER_STREAM_KEY => stream_key = value.to_vec(),
+            INNER_BINARY if size > 0 => binaries.push(value[1..].to_vec()),
+            _ => {}
+        };
+    }
+
+    if stream_id != STREAM_CHACHA20 {
+        return Err(Error::Validate(
+            "Unsupported KeePass inner stream cipher.".to_string(),
+        ));
+    }
+    let xml = std::str::from_utf8(reader.0)
+        .map_err(|_| Error::Validate("KeePass database contains invalid UTF-8 XML.".to_string()))?;
+
+    let entries = parse_xml(xml, &stream_key, &binaries);
+    payload.zeroize();
+    stream_key.zeroize();
+    entries
+}
+
+/// Parse decrypted XML document
+fn parse_xml(
+    xml: &str,
+    stream_key: &[u8],
+    binaries: &[Vec<u8>],
+) -> Result<Vec<ImportEntry>, Error> {
+    let doc = Document::parse(xml)
+        .map_err(|e| Error::Validate(format!("Invalid KeePass XML document: {}", e)))?;
+
+    // Decrypt protected values, in document order
+    let mut key = Sha512::digest(stream_key);
+    let mut cipher = ChaCha20::new_from_slices(&key[..32], &key[32..44])
+        .map_err(|e| Error::Crypto(format!("Invalid inner stream key: {}", e)))?;
+    key.zeroize();
+
+    let mut protected: HashMap<NodeId, String> = HashMap::new();
+    for node in doc.descendants().filter(|n| n.has_tag_name("Value")) {
+        if node.attribute("Protected") != Some("True") {
+            continue;
+        }
+        let mut value =
+            general_purpose::STANDARD.decode(node.text().unwrap_or_default().trim()).map_err(
+                |_| Error::Validate("Invalid protected value within KeePass database.".to_string()),
+            )?;
+        cipher.apply_keystream(&mut value);
+        protected.insert(node.id(), String::from_utf8_lossy(&value).to_string());
+        value.zeroize();
+    }
+
+    // Recycle bin
+    let root = doc.root_element();
+    let meta = child(root, "Meta");
+    let recycle_bin = match meta.and_then(|m| child_text(m, "RecycleBinEnabled")) {
+        Some(enabled) if enabled == "True" => meta.and_then(|m| child_text(m, "RecycleBinUUID")),
+        _ => None,
+    };
+
+    // Walk groups, root group name is omitted from entry names
+    let mut ctx = XmlContext {
+        protected,
+        binaries,
+        recycle_bin,
+        entries: vec![],
+    };
+    if let Some(root_group) = child(root, "Root").and_then(|r| child(r, "Group")) {
+        ctx.walk_group(root_group, &mut vec![]);
+    }
+
+    Ok(ctx.entries)
+}
+
+struct XmlContext<'a> {
+    protected: HashMap<NodeId, String>,
+    binaries: &'a [Vec<u8>],
+    recycle_bin: Option<String>,
+    entries: Vec<ImportEntry>,
+}
+
+impl XmlContext<'_> {
+    /// Walk group recursively
+    fn walk_group(&mut self, group: Node, path: &mut Vec<String>) {
+        for node in group.children().filter(|n| n.is_element()) {
+            if node.has_tag_name("Entry") {
+                let entry = self.parse_entry(node, path);
+                self.entries.push(entry);
+            } else if node.has_tag_name("Group") {
+                if self.recycle_bin.is_some() && child_text(node, "UUID") == self.recycle_bin {
+                    continue;
+                }
+                path.push(child_text(node, "Name").unwrap_or_default());
+                self.walk_group(node, path);
+                path.pop();
+            }
+        }
+    }
+
+    /// Parse single entry, ignoring its history
+    fn parse_entry(&self, node: Node, path: &[String]) -> ImportEntry {
+        let mut fields: Vec<(String, String, bool)> = vec![];
+        let mut attachments: Vec<&Vec<u8>> = vec![];
+
+        for field in node.children().filter(|n| n.is_element()) {
+            let Some(value_node) = child(field, "Value") else {
+                continue;
+            };
+
+            if field.has_tag_name("String") {
+                let (value, protected) = match self.protected.get(&value_node.id()) {
+                    Some(value) => (value.to_string(), true),
+                    None => (value_node.text().unwrap_or_default().to_string(), false),
+                };
+                fields.push((
+                    child_text(field, "Key").unwrap_or_default(),
+                    value,
+                    protected,
+                ));
+            } else if field.has_tag_name("Binary")
+                && let Some(binary) = value_node
+                    .attribute("Ref")
+                    .and_then(|r| r.parse::<usize>().ok())
+                    .and_then(|r| self.binaries.get(r))
+            {
+                attachments.push(binary);
+            }
+        }
+
+        let get = |key: &str| -> String {
+            fields
+                .iter()
+                .find(|(k, ..)| k == key)
+                .map(|(_, v, _)| v.to_string())
+                .unwrap_or_default()
+        };
+        let mut entry = ImportEntry {
+            folder: path.to_vec(),
+            title: get("Title"),
+            username: get("UserName"),
+            password: get("Password"),
+            url: get("URL"),
+            notes: get("Notes"),
+            ..Default::default()
+        };
+
+        // TOTP, as stored by KeePassXC, KeePass 2.47+ and the legacy TrayTOTP plugin
+        if !get("otp").is_empty() {
+            entry.totp = get("otp");
+        } else if !get("TimeOtp-Secret-Base32").is_empty() {
+            let algorithm = get("TimeOtp-Algorithm").replace("HMAC-", "").replace('-', "");
+            entry.totp = format!("key={}", get("TimeOtp-Secret-Base32"));
+            for (param, value) in [
+                ("step", get("TimeOtp-Period")),
+                ("size", get("TimeOtp-Length")),
+                ("otpHashMode", algorithm),
+            ] {
+                if !value.is_empty() {
+                    entry.totp.push_str(&format!("&{}={}", param, value));
+                }
+            }
+        } else if !get("TOTP Seed").is_empty() {
+            entry.totp = format!("key={}", get("TOTP Seed"));
+            let settings = get("TOTP Settings");
+            if let Some((step, size)) = settings.split_once(';')
+                && size.parse::<u32>().is_ok()
+            {
+                entry.totp.push_str(&format!("&step={}&size={}", step, size));
+            }
+        }
+
+        // SSH key attachment
+        if let Some(key) = attachments
+            .iter()
+            .map(|a| String::from_utf8_lossy(a))
+            .find(|a| a.trim_start().starts_with("-----BEGIN") && a.contains("PRIVATE KEY"))
+        {
+            entry.ssh_private_key = key.to_string();
+        }
+
+        // Remaining custom fields, protected ones concealed
+        entry.fields = fields
+            .iter()
+            .filter(|(k, v, _)| {
+                !v.is_empty() && !KNOWN_FIELDS.contains(&k.as_str()) && !k.starts_with("TimeOtp-")
+            })
+            .map(|(k, v, protected)| CustomField {
+                name: k.to_string(),
+                value: v.to_string(),
+                concealed: *protected,
+            })
+            .collect();
+
+        entry
+    }
+}
+
+/// Write KeePass KDBX 4 database, encrypted with given password
+pub fn write(dump: &ExportDump, password: &str) -> Result<Vec<u8>, Error> {
+    let mut rng = OsRng;
+    let mut random = |len: usize| {
+        let mut bytes = vec![0u8; len];
+        rng.fill_bytes(&mut bytes);
+        bytes
+    };
+    let (master_seed, iv, kdf_salt) = (random(32), random(16), random(32));
+    let mut stream_key = random(64);
+
+    // Group tree, and attachments
+    let mut root = KdbxGroup::default();
+    let mut binaries: Vec<Vec<u8>> = vec![];
+    for (name, user) in dump.users.iter() {
+        let mut entry = KdbxEntry::new(&user.display_name)
+            .field("UserName", &user.username, false)
+            .field("Password", &user.password, true)
+            .field("URL", &user.url, false)
+            .field("Notes", &user.notes, false);
+        if let Some(otp) = dump.oauth.get(name) {
+            entry = entry.field("otp", &otp.to_uri()?, true).field(
+                "Recovery Keys",
+                &otp.recovery_keys,
+                true,
+            );
+        }
+        for field in user.fields.iter() {
+            entry = entry.field(&field.name, &field.value, field.concealed);
+        }
+        for attachment in user.attachments.iter() {
+            entry.binaries.push((attachment.name.to_string(), binaries.len()));
+            binaries.push(attachment.data.clone());
+        }
+        root.insert(&user.display_name, entry);
+    }
+
+    for otp in
+        dump.oauth.iter().filter(|(name, _)| !dump.users.contains_key(*name)).map(|(_, otp)| otp)
+    {
+        let entry = KdbxEntry::new(&otp.display_name)
+            .field("UserName", &otp.account, false)
+            .field("URL", &otp.url, false)
+            .field("otp", &otp.to_uri()?, true)
+            .field("Recovery Keys", &otp.recovery_keys, true);
+        root.insert(&otp.display_name, entry);
+    }
+
+    for key in dump.ssh_keys.values() {
+        let mut entry = KdbxEntry::new(&key.display_name)
+            .field("UserName", &key.username, false)
+            .field("Password", &key.password, true)
+            .field("URL", &format!("ssh://{}:{}", key.host, key.port), false)
+            .field("Notes", &key.notes, false);
+        entry.binaries.push(("private_key".to_string(), binaries.len()));
+        binaries.push(key.private_key.as_bytes().to_vec());
+        root.insert(&key.display_name, entry);
+    }
+
+    for item in dump.strings.values() {
+        root.insert(
+            &item.display_name,
+            KdbxEntry::new(&item.display_name).field("Password", &item.value, true),
+        );
+    }
+    for note in dump.notes.values() {
+        root.insert(
+            &note.display_name,
+            KdbxEntry::new(&note.display_name).field("Notes", &note.note, false),
+        );
+    }
+
+    // XML document, protected values encrypted in document order
+    let mut key = Sha512::digest(&stream_key);
+    let mut writer = XmlWriter {
+        xml: String::new(),
+        cipher: ChaCha20::new_from_slices(&key[..32], &key[32..44])
+            .map_err(|e| Error::Crypto(format!("Invalid inner stream key: {}", e)))?,
+    };
+    key.zeroize();
+    writer.xml.push_str(r#"<?xml version="1.0" encoding="utf-8" standalone="yes"?><KeePassFile><Meta><Generator>Nyx</Generator><DatabaseName>Nyx</DatabaseName></Meta><Root>"#);
+    writer.write_group("Nyx", &root);
+    writer.xml.push_str("</Root></KeePassFile>");
+
+    // Inner header
+    let mut payload = vec![];
+    header_field(
+        &mut payload,
+        INNER_STREAM_ID,
+        &STREAM_CHACHA20.to_le_bytes(),
+    );
+    header_field(&mut payload, INNER_STREAM_KEY, &stream_key);
+    for binary in binaries.iter_mut() {
+        binary.insert(0, 0x00);
+        header_field(&mut payload, INNER_BINARY, binary);
+        binary.zeroize();
+    }
+    header_field(&mut payload, INNER_END, &[]);
+    payload.extend_from_slice(writer.xml.as_bytes());
+    writer.xml.zeroize();
+    stream_key.zeroize();
+
+    // Compress
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(&payload)?;
+    payload.zeroize();
+    let compressed = encoder.finish()?;
+
+    // KDF parameters, Argon2id
+    let mut kdf_params = 0x0100u16.to_le_bytes().to_vec();
+    variant_field(&mut kdf_params, 0x42, "$UUID", &KDF_ARGON2ID);
+    variant_field(&mut kdf_params, 0x05, "I", &2u64.to_le_bytes());
+    variant_field(
+        &mut kdf_params,
+        0x05,
+        "M",
+        &(64u64 * 1024 * 1024).to_le_bytes(),
+    );
+    variant_field(&mut kdf_params, 0x04, "P", &2u32.to_le_bytes());
+    variant_field(&mut kdf_params, 0x04, "V", &0x13u32.to_le_bytes());
+    variant_field(&mut kdf_params, 0x42, "S", &kdf_salt);
+    kdf_params.push(0x00);
+
+    // Outer header
+    let mut data = vec![];
+    data.extend_from_slice(&SIGNATURE_1.to_le_bytes());
+    data.extend_from_slice(&SIGNATURE_2.to_le_bytes());
+    data.extend_from_slice(&0x0004_0000u32.to_le_bytes());
+    header_field(&mut data, HEADER_CIPHER_ID, &CIPHER_AES256);
+    header_field(&mut data, HEADER_COMPRESSION, &1u32.to_le_bytes());
+    header_field(&mut data, HEADER_MASTER_SEED, &master_seed);
+    header_field(&mut data, HEADER_IV, &iv);
+    header_field(&mut data, HEADER_KDF_PARAMS, &kdf_params);
+    header_field(&mut data, HEADER_END, b"\r\n\r\n");
+
+    // Header checksum and HMAC
+    let (mut cipher_key, mut hmac_base) = derive_keys(password, &master_seed, &kdf_params)?;
+    let header_hash = Sha256::digest(&data);
+    let header_hmac = block_hmac(&hmac_base, u64::MAX, &[&data]).finalize().into_bytes();
+    data.extend_from_slice(&header_hash);
+    data.extend_from_slice(&header_hmac);
+
+    // Encrypt
+    let ciphertext = cbc::Encryptor::<Aes256>::new_from_slices(&cipher_key, &iv)
+        .map_err(|e| Error::Crypto(format!("Invalid KeePass cipher parameters: {}", e)))?
+        .encrypt_padded_vec_mut::<Pkcs7>(&compressed);
+    cipher_key.zeroize();
+
+    // HMAC block stream, terminated by empty block
+    let mut blocks: Vec<&[u8]> = ciphertext.chunks(BLOCK_SIZE).collect();
+    blocks.push(&[]);
+    for (index, block) in blocks.iter().enumerate() {
+        let size = (block.len() as u32).to_le_bytes();
+        let parts: [&[u8]; 3] = [&(index as u64).to_le_bytes(), &size, block];
+        data.extend_from_slice(
+            &block_hmac(&hmac_base, index as u64, &parts).finalize().into_bytes(),
+        );
+        data.extend_from_slice(&size);
+        data.extend_from_slice(block);
+    }
+    hmac_base.zeroize();
+
+    Ok(data)
+}
+
+#[derive(Default)]
+struct KdbxGroup {
+    groups: BTreeMap<String, KdbxGroup>,
+    entries: Vec<KdbxEntry>,
+}
+
+struct KdbxEntry {
+    fields: Vec<(String, String, bool)>,
+    binaries: Vec<(String, usize)>,
+}
+
+impl KdbxGroup {
+    /// Insert entry, creating groups from slash separated name
+    fn insert(&mut self, display_name: &str, entry: KdbxEntry) {
+        let mut group = self;
+        let mut parts: Vec<&str> = display_name.split('/').collect();
+        parts.pop();
+        for dir in parts {
+            group = group.groups.entry(dir.to_string()).or_default();
+        }
+        group.entries.push(entry);
+    }
+}
+
+impl KdbxEntry {
+    fn new(display_name: &str) -> Self {
+        let title = display_name.rsplit('/').next().unwrap_or_default();
+        Self {
+            fields: vec![("Title".to_string(), title.to_string(), false)],
+            binaries: vec![],
+        }
+    }
+
+    /// Add string field, skipping empty values
+    fn field(mut self, key: &str, value: &str, protected: bool) -> Self {
+        if !value.is_empty() {
+            self.fields.push((key.to_string(), value.to_string(), protected));
+        }
+        self
+    }
+}
+
+struct XmlWriter {
+    xml: String,
+    cipher: ChaCha20,
+}
+
+impl XmlWriter {
+    /// Write group and its children
+    fn write_group(&mut self, name: &str, group: &KdbxGroup) {
+        self.xml.push_str(&format!(
+            "<Group><UUID>{}</UUID><Name>{}</Name>",
+            new_uuid(),
+            escape(name)
+        ));
+        for entry in group.entries.iter() {
+            self.xml.push_str(&format!("<Entry><UUID>{}</UUID>", new_uuid()));
+            for (key, value, protected) in entry.fields.iter() {
+                let value = if *protected {
+                    let mut bytes = value.as_bytes().to_vec();
+                    self.cipher.apply_keystream(&mut bytes);
+                    format!(
+                        r#"<Value Protected="True">{}</Value>"#,
+                        general_purpose::STANDARD.encode(&bytes)
+                    )
+                } else {
+                    format!("<Value>{}</Value>", escape(value))
+                };
+                self.xml.push_str(&format!(
+                    "<String><Key>{}</Key>{}</String>",
+                    escape(key),
+                    value
+                ));
+            }
+            for (key, index) in entry.binaries.iter() {
+                self.xml.push_str(&format!(
+                    r#"<Binary><Key>{}</Key><Value Ref="{}"/></Binary>"#,
+                    escape(key),
+                    index
+                ));
+            }
+            self.xml.push_str("</Entry>");
+        }
+        for (name, child) in group.groups.iter() {
+            self.write_group(name, child);
+        }
+        self.xml.push_str("</Group>");
+    }
+}
+
+impl Drop for KdbxEntry {
+    fn drop(&mut self) {
+        for (_, value, _) in self.fields.iter_mut() {
+            value.zeroize();
+        }
+    }
+}
+
+/// Derive cipher key and HMAC base key from password
+fn derive_keys(
+    password: &str,
+    master_seed: &[u8],
+    kdf_params: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let mut composite = Sha256::digest(Sha256::digest(password.as_bytes())).to_vec();
+    let mut transformed = transform_key(&composite, kdf_params)?;
+    composite.zeroize();
+
+    let cipher_key =
+        Sha256::new().chain_update(master_seed).chain_update(&transformed).finalize().to_vec();
+    let hmac_base = Sha512::new()
+        .chain_update(master_seed)
+        .chain_update(&transformed)
+        .chain_update([0x01])
+        .finalize()
+        .to_vec();
+    transformed.zeroize();
+
+    Ok((cipher_key, hmac_base))
+}
+
+/// Transform composite key with KDF defined in header
+fn transform_key(composite: &[u8], kdf_params: &[u8]) -> Result<Vec<u8>, Error> {
+    let params = parse_variant_dictionary(kdf_params)?;
+    let get = |name: &str| {
+        params.get(name).copied().ok_or(Error::Validate(format!(
+            "Invalid KeePass KDF parameters, missing {}",
+            name
+        )))
+    };
+    let get_u64 = |name: &str| -> Result<u64, Error> {
+        let value = get(name)?;
+        match value.len() {
+            4 => Ok(u32::from_le_bytes(value.try_into().unwrap()) as u64),
+            8 => Ok(u64::from_le_bytes(value.try_into().unwrap())),
+            _ => Err(Error::Validate(format!(
+                "Invalid KeePass KDF parameter, {}",
+                name
+            ))),
+        }
+    };
+
+    let uuid = get("$UUID")?;
+    if uuid == KDF_AES {
+        let cipher = Aes256::new_from_slice(get("S")?)
+            .map_err(|e| Error::Crypto(format!("Invalid AES-KDF seed: {}", e)))?;
+        let mut key = composite.to_vec();
+        for _ in 0..get_u64("R")? {
+            for block in key.chunks_mut(16) {
+                cipher.encrypt_block(block.into());
+            }
+        }
+        let transformed = Sha256::digest(&key).to_vec();
+        key.zeroize();
+        Ok(transformed)
+    } else if uuid == KDF_ARGON2D || uuid == KDF_ARGON2ID {
+        let algorithm = if uuid == KDF_ARGON2D {
+            argon2::Algorithm::Argon2d
+        } else {
+            argon2::Algorithm::Argon2id
+        };
+        let version = match get_u64("V")? {
+            0x10 => argon2::Version::V0x10,
+            _ => argon2::Version::V0x13,
+        };
+        let argon_params = argon2::Params::new(
+            (get_u64("M")? / 1024) as u32,
+            get_u64("I")? as u32,
+            get_u64("P")? as u32,
+            Some(32),
+        )
+        .map_err(|e| Error::Crypto(format!("Invalid Argon2 parameters: {}", e)))?;
+
+        let mut transformed = vec![0u8; 32];
+        argon2::Argon2::new(algorithm, version, argon_params)
+            .hash_password_into(composite, get("S")?, &mut transformed)
+            .map_err(|e| Error::Crypto(format!("Unable to derive KeePass key: {}", e)))?;
+        Ok(transformed)
+    } else {
+        Err(Error::Validate(
+            "Unsupported KeePass key derivation function.".to_string(),
+        ))
+    }
+}
+
+/// Parse KDBX VariantDictionary into name / raw value pairs
+fn parse_variant_dictionary(data: &[u8]) -> Result<HashMap<String, &[u8]>, Error> {
+    let mut reader = Reader(data);
+    reader.take(2)?;
+
+    let mut params = HashMap::new();
+    loop {
+        let value_type = reader.u8()?;
+        if value_type == 0 {
+            break;
+        }
+        let name_len = reader.u32()? as usize;
+        let name = String::from_utf8_lossy(reader.take(name_len)?).to_string();
+        let value_len = reader.u32()? as usize;
+        params.insert(name, reader.take(value_len)?);
+    }
+
+    Ok(params)
+}
+
+/// HMAC-SHA256 of data, keyed for block at given index
+fn block_hmac(hmac_base: &[u8], index: u64, parts: &[&[u8]]) -> Hmac<Sha256> {
+    let key = Sha512::new().chain_update(index.to_le_bytes()).chain_update(hmac_base).finalize();
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&key).expect("HMAC accepts any key size");
+    for part in parts {
+        mac.update(part);
+    }
+    mac
+}
+
+/// Append type-length-value header field
+fn header_field(out: &mut Vec<u8>, field_id: u8, value: &[u8]) {
+    out.push(field_id);
+    out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    out.extend_from_slice(value);
+}
+
+/// Append VariantDictionary item
+fn variant_field(out: &mut Vec<u8>, value_type: u8, name: &str, value: &[u8]) {
+    out.push(value_type);
+    out.extend_from_slice(&(name.len() as u32).to_le_bytes());
+    out.extend_from_slice(name.as_bytes());
+    out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    out.extend_from_slice(value);
+}
+
+/// Random base64 encoded UUID
+fn new_uuid() -> String {
+    let mut uuid = [0u8; 16];
+    OsRng.fill_bytes(&mut uuid);
+    general_purpose::STANDARD.encode(uuid)
+}
+
+/// Escape XML text
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Get child element by tag name
+fn child<'a, 'input>(node: Node<'a, 'input>, tag: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(tag))
+}
+
+/// Get text of child element
+fn child_text(node: Node, tag: &str) -> Option<String> {
+    child(node, tag).map(|n| n.text().unwrap_or_default().to_string())
+}
+
+/// Little endian byte reader
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::Validate(
+                "KeePass database is truncated.".to_string(),
+            ));
+        }
+        let (value, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(value)
+    }
+}
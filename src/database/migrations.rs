@@ -0,0 +1,354 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+// Layouts of historical database format versions, and the steps to upgrade them.
+//
+// When changing the layout of any struct stored within NyxDb, bump VERSION in
+// nyxdb.rs, freeze a copy of the previous layout here, add a variant to
+// Snapshot and a step converting it to the next version.
+
+use super::history::DEFAULT_MAX_ITEMS;
+use super::{
+    DatabaseTimeout, HistoryAction, HistoryDataType, HistoryDb, HistoryHead, HistoryItem, NotesDb,
+    NyxDb, Oauth, OauthDb, SshKeysDb, StringsDb, TagsDb, User, UsersDb, VersionsDb,
+};
+use crate::Error;
+use bincode::{Decode, config};
+use std::collections::HashMap;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Database decoded from any supported format version
+enum Snapshot {
+    V1(NyxDbV1),
+    V2(NyxDbV2),
+    V3(NyxDbV3),
+    V4(NyxDbV4),
+    V5(NyxDbV5),
+    V6(NyxDbV6),
+    Current(NyxDb),
+}
+
+/// Decode an older format version, and upgrade it one version at a time to the current layout
+pub fn upgrade(version: u8, bytes: &[u8]) -> Result<NyxDb, Error> {
+    let mut snapshot = match version {
+        1 => Snapshot::V1(decode(bytes)?),
+        2 => Snapshot::V2(decode(bytes)?),
+        3 => Snapshot::V3(decode(bytes)?),
+        4 => Snapshot::V4(decode(bytes)?),
+        5 => Snapshot::V5(decode(bytes)?),
+        6 => Snapshot::V6(decode(bytes)?),
+        _ => {
+            return Err(Error::Db(format!(
+                "Unsupported database format version, {}",
+                version
+            )));
+        }
+    };
+
+    loop {
+        snapshot = match snapshot {
+            Snapshot::V1(db) => Snapshot::V2(db.into()),
+            Snapshot::V2(db) => Snapshot::V3(db.into()),
+            Snapshot::V3(db) => Snapshot::V4(db.into()),
+            Snapshot::V4(db) => Snapshot::V5(db.into()),
+            Snapshot::V5(db) => Snapshot::V6(db.into()),
+            Snapshot::V6(db) => Snapshot::Current(db.into()),
+            Snapshot::Current(db) => return Ok(db),
+        };
+    }
+}
+
+/// Decode bincode payload
+fn decode<T: Decode<()>>(bytes: &[u8]) -> Result<T, Error> {
+    let (db, _len): (T, usize) = bincode::decode_from_slice(bytes, config::standard())
+        .map_err(|e| Error::Db(format!("Unable to load database: {}", e)))?;
+    Ok(db)
+}
+
+/// Version 1, OTP entries only had a TOTP secret
+#[derive(Decode)]
+struct NyxDbV1 {
+    default_timeout: DatabaseTimeout,
+    users: HashMap<String, UserV5>,
+    oauth: HashMap<String, OauthV1>,
+    ssh_keys: SshKeysDb,
+    strings: StringsDb,
+    notes: NotesDb,
+    history: Vec<HistoryItemV3>,
+}
+
+#[derive(Decode)]
+struct OauthV1 {
+    display_name: String,
+    secret_code: String,
+    url: String,
+    recovery_keys: String,
+}
+
+/// Version 1 to 2, adds token parameters and issuer / account to OTP entries
+impl From<NyxDbV1> for NyxDbV2 {
+    fn from(db: NyxDbV1) -> Self {
+        let oauth = db
+            .oauth
+            .into_iter()
+            .map(|(name, old)| {
+                let mut otp = Oauth::default();
+                otp.display_name = old.display_name;
+                otp.secret_code = old.secret_code;
+                otp.url = old.url;
+                otp.recovery_keys = old.recovery_keys;
+                (name, otp)
+            })
+            .collect();
+
+        Self {
+            default_timeout: db.default_timeout,
+            users: db.users,
+            oauth: OauthDb(oauth),
+            ssh_keys: db.ssh_keys,
+            strings: db.strings,
+            notes: db.notes,
+            history: db.history,
+        }
+    }
+}
+
+/// Version 2, no prior versions of entries were kept
+#[derive(Decode)]
+struct NyxDbV2 {
+    default_timeout: DatabaseTimeout,
+    users: HashMap<String, UserV5>,
+    oauth: OauthDb,
+    ssh_keys: SshKeysDb,
+    strings: StringsDb,
+    notes: NotesDb,
+    history: Vec<HistoryItemV3>,
+}
+
+/// Version 2 to 3, adds prior versions of entries with the default retention count
+impl From<NyxDbV2> for NyxDbV3 {
+    fn from(db: NyxDbV2) -> Self {
+        Self {
+            default_timeout: db.default_timeout,
+            users: db.users,
+            oauth: db.oauth,
+            ssh_keys: db.ssh_keys,
+            strings: db.strings,
+            notes: db.notes,
+            history: db.history,
+            versions: VersionsDb::default(),
+        }
+    }
+}
+
+/// Version 3, history only recorded changes, without the client making them or any limits
+#[derive(Decode)]
+struct NyxDbV3 {
+    default_timeout: DatabaseTimeout,
+    users: HashMap<String, UserV5>,
+    oauth: OauthDb,
+    ssh_keys: SshKeysDb,
+    strings: StringsDb,
+    notes: NotesDb,
+    history: Vec<HistoryItemV3>,
+    versions: VersionsDb,
+}
+
+#[derive(Decode)]
+struct HistoryItemV3 {
+    action: HistoryAction,
+    data_type: HistoryDataType,
+    source: String,
+    dest: String,
+    timestamp: u64,
+}
+
+/// Version 3 to 4, adds client to history items and the default history limits
+impl From<NyxDbV3> for NyxDbV4 {
+    fn from(db: NyxDbV3) -> Self {
+        let items = db
+            .history
+            .into_iter()
+            .map(|old| HistoryItemV4 {
+                action: old.action,
+                data_type: old.data_type,
+                source: old.source,
+                dest: old.dest,
+                timestamp: old.timestamp,
+                client: String::new(),
+            })
+            .collect();
+
+        Self {
+            default_timeout: db.default_timeout,
+            users: db.users,
+            oauth: db.oauth,
+            ssh_keys: db.ssh_keys,
+            strings: db.strings,
+            notes: db.notes,
+            history: HistoryDbV4 {
+                items,
+                max_items: DEFAULT_MAX_ITEMS,
+                max_days: 0,
+            },
+            versions: db.versions,
+        }
+    }
+}
+
+/// Version 4, history entries were not chained by hash
+#[derive(Decode)]
+struct NyxDbV4 {
+    default_timeout: DatabaseTimeout,
+    users: HashMap<String, UserV5>,
+    oauth: OauthDb,
+    ssh_keys: SshKeysDb,
+    strings: StringsDb,
+    notes: NotesDb,
+    history: HistoryDbV4,
+    versions: VersionsDb,
+}
+
+#[derive(Decode)]
+struct HistoryDbV4 {
+    items: Vec<HistoryItemV4>,
+    max_items: u32,
+    max_days: u32,
+}
+
+#[derive(Decode)]
+struct HistoryItemV4 {
+    action: HistoryAction,
+    data_type: HistoryDataType,
+    source: String,
+    dest: String,
+    timestamp: u64,
+    client: String,
+}
+
+/// Version 4 to 5, chains existing history entries by hash, oldest first from the genesis hash
+impl From<NyxDbV4> for NyxDbV5 {
+    fn from(db: NyxDbV4) -> Self {
+        let mut history = HistoryDb {
+            items: vec![],
+            max_items: db.history.max_items,
+            max_days: db.history.max_days,
+            anchor: HistoryHead::default(),
+        };
+        history.items = db
+            .history
+            .items
+            .into_iter()
+            .map(|old| HistoryItem {
+                action: old.action,
+                data_type: old.data_type,
+                source: old.source,
+                dest: old.dest,
+                timestamp: old.timestamp,
+                client: old.client,
+                seq: 0,
+                hash: String::new(),
+            })
+            .collect();
+        history.seal();
+
+        Self {
+            default_timeout: db.default_timeout,
+            users: db.users,
+            oauth: db.oauth,
+            ssh_keys: db.ssh_keys,
+            strings: db.strings,
+            notes: db.notes,
+            history,
+            versions: db.versions,
+        }
+    }
+}
+
+/// Version 5, users had no custom fields or attachments
+#[derive(Decode)]
+struct NyxDbV5 {
+    default_timeout: DatabaseTimeout,
+    users: HashMap<String, UserV5>,
+    oauth: OauthDb,
+    ssh_keys: SshKeysDb,
+    strings: StringsDb,
+    notes: NotesDb,
+    history: HistoryDb,
+    versions: VersionsDb,
+}
+
+#[derive(Decode, Zeroize, ZeroizeOnDrop)]
+struct UserV5 {
+    display_name: String,
+    username: String,
+    password: String,
+    url: String,
+    notes: String,
+}
+
+/// Version 5 to 6, adds custom fields and attachments to users
+impl From<NyxDbV5> for NyxDbV6 {
+    fn from(db: NyxDbV5) -> Self {
+        let users = db
+            .users
+            .iter()
+            .map(|(name, old)| {
+                let user = User {
+                    display_name: old.display_name.to_string(),
+                    username: old.username.to_string(),
+                    password: old.password.to_string(),
+                    url: old.url.to_string(),
+                    notes: old.notes.to_string(),
+                    fields: vec![],
+                    attachments: vec![],
+                };
+                (name.to_string(), user)
+            })
+            .collect();
+
+        Self {
+            default_timeout: db.default_timeout,
+            users: UsersDb(users),
+            oauth: db.oauth,
+            ssh_keys: db.ssh_keys,
+            strings: db.strings,
+            notes: db.notes,
+            history: db.history,
+            versions: db.versions,
+        }
+    }
+}
+
+/// Version 6, entries had no tags or favorites
+#[derive(Decode)]
+struct NyxDbV6 {
+    default_timeout: DatabaseTimeout,
+    users: UsersDb,
+    oauth: OauthDb,
+    ssh_keys: SshKeysDb,
+    strings: StringsDb,
+    notes: NotesDb,
+    history: HistoryDb,
+    versions: VersionsDb,
+}
+
+/// Version 6 to 7, adds tags and favorites of entries
+impl From<NyxDbV6> for NyxDb {
+    fn from(db: NyxDbV6) -> Self {
+        Self {
+            default_timeout: db.default_timeout,
+            users: db.users,
+            oauth: db.oauth,
+            ssh_keys: db.ssh_keys,
+            strings: db.strings,
+            notes: db.notes,
+            history: db.history,
+            versions: db.versions,
+            tags: TagsDb::default(),
+        }
+    }
+}
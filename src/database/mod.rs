@@ -5,25 +5,39 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 pub use self::base::{BaseDbFunctions, BaseDbItem};
-#[cfg(any(target_os="linux", feature = "fuse"))]
+pub use self::export::{ExportDump, ExportFormat, ExportSshKey};
+#[cfg(any(target_os = "linux", feature = "fuse"))]
 pub use self::fs::NyxFs;
-pub use self::history::{HistoryAction, HistoryDataType, HistoryDb, HistoryItem};
+pub use self::history::{
+    HistoryAction, HistoryDataType, HistoryDb, HistoryFilter, HistoryHead, HistoryItem,
+    HistoryRetention, HistoryVerify,
+};
+pub use self::import::{ImportBundle, ImportFormat, ImportSummary};
 pub use self::loader::LoaderResponse;
 pub use self::notes::{Note, NotesDb};
 pub use self::nyxdb::{DatabaseTimeout, DbStats, NyxDb};
-pub use self::oauth::{Oauth, OauthDb};
+pub use self::oauth::{Oauth, OauthDb, OtpAlgorithm, OtpCode, OtpKind};
 pub use self::ssh_keys::{SshKey, SshKeysDb};
 pub use self::strings::{StrItem, StringsDb};
-pub use self::users::{User, UsersDb};
+pub use self::tags::{EntryTags, TagsDb};
+pub use self::users::{Attachment, CustomField, User, UsersDb};
+pub use self::versions::{EntryVersion, VersionInfo, VersionsDb};
 
 mod base;
-#[cfg(any(target_os="linux", feature = "fuse"))]
+mod export;
+#[cfg(any(target_os = "linux", feature = "fuse"))]
 mod fs;
 mod history;
+mod import;
+mod kdbx;
 pub mod loader;
+mod migrations;
 mod notes;
 mod nyxdb;
 mod oauth;
+mod otpauth;
 mod ssh_keys;
 mod strings;
+mod tags;
 mod users;
+mod versions;
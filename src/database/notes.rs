@@ -6,15 +6,16 @@
 
 use super::{BaseDbFunctions, BaseDbItem};
 use bincode::{Decode, Encode};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[derive(Default, Encode, Decode)]
 pub struct NotesDb(pub HashMap<String, Note>);
 
-#[derive(Clone, Encode, Decode, Serialize, Deserialize)]
+#[derive(Clone, Encode, Decode, Serialize, Deserialize, JsonSchema, Zeroize, ZeroizeOnDrop)]
 pub struct Note {
     pub display_name: String,
     pub note: String,
@@ -22,14 +23,6 @@ pub struct Note {
 
 impl BaseDbFunctions for NotesDb {
     type Item = Note;
-
-    /// Secure clear
-    fn secure_clear(&mut self) {
-        for (_, note) in self.iter_mut() {
-            note.display_name.zeroize();
-            note.note.zeroize();
-        }
-    }
 }
 
 impl BaseDbItem for Note {
@@ -4,21 +4,29 @@
 // Apache License text: https://www.apache.org/licenses/LICENSE-2.0
 // MIT License text: https://opensource.org/licenses/MIT
 
-use super::{BaseDbFunctions, HistoryDb, NotesDb, OauthDb, SshKeysDb, StringsDb, UsersDb};
+use super::migrations;
+use super::{
+    BaseDbFunctions, HistoryDb, NotesDb, OauthDb, SshKeysDb, StringsDb, TagsDb, UsersDb, VersionsDb,
+};
 use crate::Error;
 use crate::security::crypto;
 use bincode::{Decode, Encode, config};
 use falcon_cli::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::Duration;
 use zeroize::Zeroize;
 
 const MAGIC_BYTES: &[u8; 4] = b"NYX\0";
-const VERSION: u8 = 1;
+const VERSION: u8 = 7;
+
+/// Wrong passwords entered before unlocking, recorded in history once the daemon opens the database
+static FAILED_UNLOCKS: AtomicU32 = AtomicU32::new(0);
 
 #[derive(Default, Encode, Decode)]
 pub struct NyxDb {
@@ -29,6 +37,8 @@ pub struct NyxDb {
     pub strings: StringsDb,
     pub notes: NotesDb,
     pub history: HistoryDb,
+    pub versions: VersionsDb,
+    pub tags: TagsDb,
 }
 
 #[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Decode, Encode)]
@@ -38,7 +48,7 @@ pub enum DatabaseTimeout {
     Duration(Duration),
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct DbStats {
     pub dbfile: String,
     pub users: (u32, u32),
@@ -46,6 +56,8 @@ pub struct DbStats {
     pub ssh_keys: (u32, u32),
     pub strings: (u32, u32),
     pub notes: (u32, u32),
+    pub tags: BTreeMap<String, u32>,
+    pub favorites: u32,
 }
 
 impl NyxDb {
@@ -118,14 +130,45 @@ impl NyxDb {
         // Decrypt
         let bytes = crypto::decrypt(&encrypted_bytes, n_password)?;
 
-        // Decode
-        let (db, _len): (NyxDb, usize) =
-            bincode::decode_from_slice(&bytes[5..], config::standard())
-                .map_err(|e| Error::Db(format!("Unable to load database: {}", e)))?;
+        // Check header
+        let version = Self::check_header(&bytes)?;
+
+        // Decode current version
+        if version == VERSION {
+            let (db, _len): (NyxDb, usize) =
+                bincode::decode_from_slice(&bytes[5..], config::standard())
+                    .map_err(|e| Error::Db(format!("Unable to load database: {}", e)))?;
+            return Ok(db);
+        }
+
+        // Upgrade older version, keeping a copy of the original file
+        let mut db = migrations::upgrade(version, &bytes[5..])?;
+        fs::write(Self::backup_filename(dbfile, version), &encrypted_bytes)?;
+        db.save(dbfile, n_password, None)?;
 
         Ok(db)
     }
 
+    /// Location of original file kept when upgrading format versions
+    fn backup_filename(dbfile: &str, version: u8) -> String {
+        format!("{}.v{}.bak", dbfile, version)
+    }
+
+    /// Validate decrypted header, and get format version
+    fn check_header(data: &[u8]) -> Result<u8, Error> {
+        if data.len() < 5 || &data[0..4] != MAGIC_BYTES || data[4] == 0 {
+            return Err(Error::Db(
+                "This is not a valid Nyx database file.".to_string(),
+            ));
+        } else if data[4] > VERSION {
+            return Err(Error::Db(format!(
+                "This database uses format version {}, which is newer than supported by this version of Nyx.  Please upgrade Nyx.",
+                data[4]
+            )));
+        }
+        Ok(data[4])
+    }
+
     /// Unlock database, essentially just ensure password is correct before refreshing command to start daemon
     pub fn unlock(dbfile: &str) -> Result<[u8; 32], Error> {
         cli_info!("Opening Nyx database located at:");
@@ -144,24 +187,22 @@ impl NyxDb {
             let data = match crypto::decrypt(&encrypted_bytes, n_password) {
                 Ok(r) => r,
                 Err(_) => {
+                    FAILED_UNLOCKS.fetch_add(1, Ordering::Relaxed);
                     cli_info!("Invalid password, please double check and try again.\n");
                     continue;
                 }
             };
 
-            // Check header
-            if data.len() < 5 {
-                return Err(Error::Db(
-                    "This is not a valid Nyx database file.".to_string(),
-                ));
-            } else if &data[0..4] != MAGIC_BYTES {
-                return Err(Error::Db(
-                    "This is not a valid Nyx database file.".to_string(),
-                ));
-            } else if data[4] != VERSION {
-                return Err(Error::Db(
-                    "This is not a valid Nyx database file.".to_string(),
-                ));
+            // Check header, and upgrade older format versions in the foreground
+            let version = Self::check_header(&data)?;
+            if version < VERSION {
+                Self::load(dbfile, n_password)?.secure_clear();
+                cli_info!(
+                    "Database upgraded from format version {} to {}, original saved to {}\n",
+                    version,
+                    VERSION,
+                    Self::backup_filename(dbfile, version)
+                );
             }
             break;
         }
@@ -169,6 +210,11 @@ impl NyxDb {
         Ok(n_password)
     }
 
+    /// Take number of wrong passwords entered since last taken
+    pub fn take_failed_unlocks() -> u32 {
+        FAILED_UNLOCKS.swap(0, Ordering::Relaxed)
+    }
+
     /// Secure clear
     pub fn secure_clear(&mut self) {
         self.users.secure_clear();
@@ -176,6 +222,7 @@ impl NyxDb {
         self.ssh_keys.secure_clear();
         self.strings.secure_clear();
         self.notes.secure_clear();
+        self.versions.secure_clear();
     }
 }
 
@@ -230,6 +277,8 @@ impl DbStats {
             ssh_keys: Self::get_item(&nyxdb.ssh_keys),
             strings: Self::get_item(&nyxdb.strings),
             notes: Self::get_item(&nyxdb.notes),
+            tags: nyxdb.tags.counts(),
+            favorites: nyxdb.tags.favorite_count(),
         }
     }
 
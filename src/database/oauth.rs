@@ -6,62 +6,208 @@
 
 use super::{BaseDbFunctions, BaseDbItem};
 use crate::Error;
-use crate::rpc::{CmdResponse, message};
+use crate::rpc::{CmdResponse, RpcId, message};
 use bincode::{Decode, Encode};
-use otpauth::TOTP;
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
 use std::collections::HashMap;
+use std::fmt;
 use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[derive(Default, Encode, Decode)]
 pub struct OauthDb(pub HashMap<String, Oauth>);
 
-#[derive(Clone, Encode, Decode, Serialize, Deserialize)]
+// Defaults are per field, as struct update syntax cannot move out of a type that zeroizes on drop
+#[derive(Clone, Encode, Decode, Serialize, Deserialize, JsonSchema, Zeroize, ZeroizeOnDrop)]
 pub struct Oauth {
+    #[serde(default)]
     pub display_name: String,
+    #[serde(default)]
     pub secret_code: String,
+    #[serde(default)]
     pub url: String,
+    #[serde(default)]
     pub recovery_keys: String,
+    #[serde(default)]
+    pub issuer: String,
+    #[serde(default)]
+    pub account: String,
+    #[serde(default)]
+    #[zeroize(skip)]
+    pub kind: OtpKind,
+    #[serde(default)]
+    #[zeroize(skip)]
+    pub algorithm: OtpAlgorithm,
+    #[serde(default = "default_digits")]
+    pub digits: u32,
+    #[serde(default = "default_period")]
+    pub period: u64,
+    #[serde(default)]
+    pub counter: u64,
+}
+
+#[derive(
+    Default, Decode, Encode, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, JsonSchema, Debug,
+)]
+pub enum OtpKind {
+    #[default]
+    Totp,
+    Hotp,
+}
+
+#[derive(
+    Default, Decode, Encode, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, JsonSchema, Debug,
+)]
+pub enum OtpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OtpCode {
+    pub code: String,
+    pub remaining: Option<u64>,
+    pub period: Option<u64>,
+    pub next_code: Option<String>,
 }
 
 impl OauthDb {
     /// Generate OTP code
-    pub fn generate(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    pub fn generate(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         // Get oath
-        let oauth = self.get(&params[0].to_lowercase()).ok_or(Error::Validate(format!(
+        let oauth = self.get_mut(&params[0].to_lowercase()).ok_or(Error::Validate(format!(
             "Entry does not exist at, {}",
             params[0]
         )))?;
 
-        // Get totp client
-        let client = TOTP::from_base32(&oauth.secret_code)
-            .ok_or(Error::Rpc("Unable to initialize TOTP".to_string()))?;
-
-        let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
         // Generate OTP
-        let otp_num = client.generate(30, current_time);
-        let otp = format!("{:06}", otp_num);
+        let (otp, is_modified) = match oauth.kind {
+            OtpKind::Totp => {
+                let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+                let period = oauth.period.max(1);
+                let time_step = current_time / period;
+                let otp = OtpCode {
+                    code: oauth.code_at(time_step)?,
+                    remaining: Some(period - (current_time % period)),
+                    period: Some(period),
+                    next_code: Some(oauth.code_at(time_step + 1)?),
+                };
+                (otp, false)
+            }
+            OtpKind::Hotp => {
+                let otp = OtpCode {
+                    code: oauth.code_at(oauth.counter)?,
+                    remaining: None,
+                    period: None,
+                    next_code: None,
+                };
+                oauth.counter = oauth.counter.checked_add(1).ok_or(Error::Validate(
+                    "HOTP counter has reached its maximum value.".to_string(),
+                ))?;
+                (otp, true)
+            }
+        };
 
-        Ok(CmdResponse::new(false, true, message::ok(req_id, otp)))
+        Ok(CmdResponse::new(
+            is_modified,
+            true,
+            message::ok(req_id, otp),
+        ))
     }
 }
 
-impl BaseDbFunctions for OauthDb {
-    type Item = Oauth;
+impl Oauth {
+    /// Generate code for a moving factor, being the time step for TOTP or counter for HOTP (RFC 4226)
+    pub fn code_at(&self, moving_factor: u64) -> Result<String, Error> {
+        let secret = Self::decode_secret(&self.secret_code)?;
+        let msg = moving_factor.to_be_bytes();
+
+        let hash = match self.algorithm {
+            OtpAlgorithm::Sha1 => Self::hmac::<Hmac<Sha1>>(&secret, &msg)?,
+            OtpAlgorithm::Sha256 => Self::hmac::<Hmac<Sha256>>(&secret, &msg)?,
+            OtpAlgorithm::Sha512 => Self::hmac::<Hmac<Sha512>>(&secret, &msg)?,
+        };
+
+        // Dynamic truncation
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+
+        let modulus = 10u64.checked_pow(self.digits).ok_or(Error::Validate(
+            "Number of digits must be between 6 and 10.".to_string(),
+        ))?;
+        let code = binary as u64 % modulus;
+        Ok(format!("{:0width$}", code, width = self.digits as usize))
+    }
 
-    /// Secure clear
-    fn secure_clear(&mut self) {
-        for (_, item) in self.iter_mut() {
-            item.display_name.zeroize();
-            item.secret_code.zeroize();
-            item.url.zeroize();
-            item.recovery_keys.zeroize();
+    /// Decode Base32 secret, ignoring spaces, case and padding
+    pub fn decode_secret(secret: &str) -> Result<Vec<u8>, Error> {
+        let normalized: String = secret
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '=' && *c != '-')
+            .collect::<String>()
+            .to_uppercase();
+
+        match base32::decode(base32::Alphabet::Rfc4648 { padding: false }, &normalized) {
+            Some(bytes) if !bytes.is_empty() => Ok(bytes),
+            _ => Err(Error::Validate(
+                "Secret code is not a valid Base32 string.".to_string(),
+            )),
+        }
+    }
+
+    /// Calculate HMAC of message with secret
+    fn hmac<M: Mac + KeyInit>(secret: &[u8], msg: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut mac = <M as Mac>::new_from_slice(secret)
+            .map_err(|e| Error::Crypto(format!("Unable to initialize HMAC: {}", e)))?;
+        mac.update(msg);
+        Ok(mac.finalize().into_bytes().to_vec())
+    }
+}
+
+impl Default for Oauth {
+    fn default() -> Self {
+        Self {
+            display_name: String::new(),
+            secret_code: String::new(),
+            url: String::new(),
+            recovery_keys: String::new(),
+            issuer: String::new(),
+            account: String::new(),
+            kind: OtpKind::Totp,
+            algorithm: OtpAlgorithm::Sha1,
+            digits: default_digits(),
+            period: default_period(),
+            counter: 0,
         }
     }
 }
 
+fn default_digits() -> u32 {
+    6
+}
+
+fn default_period() -> u64 {
+    30
+}
+
+impl BaseDbFunctions for OauthDb {
+    type Item = Oauth;
+}
+
 impl BaseDbItem for Oauth {
     fn get_name(&self) -> String {
         self.display_name.to_string()
@@ -73,6 +219,22 @@ impl BaseDbItem for Oauth {
     fn contains(&self, search: &str) -> bool {
         self.display_name.to_lowercase().contains(search)
             || self.url.to_lowercase().contains(search)
+            || self.issuer.to_lowercase().contains(search)
+    }
+
+    /// Validate OTP parameters
+    fn validate(&self) -> Result<(), Error> {
+        Self::decode_secret(&self.secret_code)?;
+        if !(6..=10).contains(&self.digits) {
+            return Err(Error::Validate(
+                "Number of digits must be between 6 and 10.".to_string(),
+            ));
+        } else if self.kind == OtpKind::Totp && self.period == 0 {
+            return Err(Error::Validate(
+                "Period must be greater than zero seconds.".to_string(),
+            ));
+        }
+        Ok(())
     }
 }
 
@@ -89,3 +251,47 @@ impl DerefMut for OauthDb {
         &mut self.0
     }
 }
+
+impl FromStr for OtpKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "totp" => Ok(Self::Totp),
+            "hotp" => Ok(Self::Hotp),
+            _ => Err(Error::Validate(format!("Invalid OTP type, {}", s))),
+        }
+    }
+}
+
+impl FromStr for OtpAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().replace("-", "").as_str() {
+            "SHA1" => Ok(Self::Sha1),
+            "SHA256" => Ok(Self::Sha256),
+            "SHA512" => Ok(Self::Sha512),
+            _ => Err(Error::Validate(format!("Invalid OTP algorithm, {}", s))),
+        }
+    }
+}
+
+impl fmt::Display for OtpKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Totp => write!(f, "TOTP"),
+            Self::Hotp => write!(f, "HOTP"),
+        }
+    }
+}
+
+impl fmt::Display for OtpAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sha1 => write!(f, "SHA1"),
+            Self::Sha256 => write!(f, "SHA256"),
+            Self::Sha512 => write!(f, "SHA512"),
+        }
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::{BaseDbItem, Oauth, OtpAlgorithm, OtpKind};
+use crate::Error;
+use base64::{Engine as _, engine::general_purpose};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use std::str::FromStr;
+use url::Url;
+
+// Characters left unescaped within URI components, per RFC 3986
+const URI_COMPONENT: &AsciiSet =
+    &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+impl Oauth {
+    /// Parse an otpauth://totp/... or otpauth://hotp/... key URI
+    pub fn from_uri(uri: &str) -> Result<Self, Error> {
+        let url = Url::parse(uri.trim())
+            .map_err(|e| Error::Validate(format!("Invalid otpauth URI: {}", e)))?;
+        if url.scheme() != "otpauth" {
+            return Err(Error::Validate(format!(
+                "Invalid URI scheme, expected otpauth:// but got {}://",
+                url.scheme()
+            )));
+        }
+
+        let mut otp = Oauth::default();
+        otp.kind = OtpKind::from_str(url.host_str().unwrap_or(""))?;
+
+        // Label, either "issuer:account" or "account"
+        let label = percent_decode(url.path().trim_start_matches('/'));
+        match label.split_once(':') {
+            Some((issuer, account)) => {
+                otp.issuer = issuer.trim().to_string();
+                otp.account = account.trim().to_string();
+            }
+            None => otp.account = label.trim().to_string(),
+        };
+
+        // Parameters
+        for (key, value) in url.query_pairs() {
+            let value = value.trim();
+            match key.to_lowercase().as_str() {
+                "secret" => otp.secret_code = value.to_uppercase(),
+                "issuer" => otp.issuer = value.to_string(),
+                "algorithm" => otp.algorithm = OtpAlgorithm::from_str(value)?,
+                "digits" => otp.digits = parse_number(&key, value)?,
+                "period" => otp.period = parse_number(&key, value)?,
+                "counter" => otp.counter = parse_number(&key, value)?,
+                _ => {}
+            };
+        }
+
+        if otp.secret_code.is_empty() {
+            return Err(Error::Validate(
+                "The otpauth URI does not contain a secret.".to_string(),
+            ));
+        }
+        otp.validate()?;
+
+        Ok(otp)
+    }
+
+    /// Parse a Google Authenticator otpauth-migration://offline?data=... export
+    pub fn from_migration_uri(uri: &str) -> Result<Vec<Self>, Error> {
+        let url = Url::parse(uri.trim())
+            .map_err(|e| Error::Validate(format!("Invalid migration URI: {}", e)))?;
+        if url.scheme() != "otpauth-migration" {
+            return Err(Error::Validate(format!(
+                "Invalid URI scheme, expected otpauth-migration:// but got {}://",
+                url.scheme()
+            )));
+        }
+
+        let data = url
+            .query_pairs()
+            .find(|(key, _)| key == "data")
+            .map(|(_, value)| value.replace(' ', "+"))
+            .ok_or(Error::Validate(
+                "Migration URI does not contain a data parameter.".to_string(),
+            ))?;
+        let payload = general_purpose::STANDARD
+            .decode(&data)
+            .or_else(|_| general_purpose::STANDARD_NO_PAD.decode(&data))
+            .map_err(|e| Error::Validate(format!("Invalid migration payload: {}", e)))?;
+
+        // MigrationPayload, field 1 holds the repeated OtpParameters
+        let mut entries = vec![];
+        let mut reader = ProtoReader(&payload);
+        while let Some((field, value)) = reader.next_field()? {
+            if let (1, ProtoValue::Bytes(bytes)) = (field, value) {
+                entries.push(Self::from_migration_params(bytes)?);
+            }
+        }
+
+        if entries.is_empty() {
+            return Err(Error::Validate(
+                "Migration payload does not contain any entries.".to_string(),
+            ));
+        }
+        Ok(entries)
+    }
+
+    /// Decode a single OtpParameters message
+    fn from_migration_params(bytes: &[u8]) -> Result<Self, Error> {
+        let mut otp = Oauth::default();
+        let mut reader = ProtoReader(bytes);
+
+        while let Some((field, value)) = reader.next_field()? {
+            match (field, value) {
+                (1, ProtoValue::Bytes(secret)) => {
+                    otp.secret_code =
+                        base32::encode(base32::Alphabet::Rfc4648 { padding: false }, secret);
+                }
+                (2, ProtoValue::Bytes(name)) => {
+                    let name = String::from_utf8_lossy(name).to_string();
+                    otp.account = match name.split_once(':') {
+                        Some((_, account)) => account.trim().to_string(),
+                        None => name,
+                    };
+                }
+                (3, ProtoValue::Bytes(issuer)) => {
+                    otp.issuer = String::from_utf8_lossy(issuer).to_string();
+                }
+                (4, ProtoValue::Varint(algorithm)) => {
+                    otp.algorithm = match algorithm {
+                        0 | 1 => OtpAlgorithm::Sha1,
+                        2 => OtpAlgorithm::Sha256,
+                        3 => OtpAlgorithm::Sha512,
+                        _ => {
+                            return Err(Error::Validate(
+                                "Migration payload contains an unsupported algorithm.".to_string(),
+                            ));
+                        }
+                    };
+                }
+                (5, ProtoValue::Varint(digits)) => otp.digits = if digits == 2 { 8 } else { 6 },
+                (6, ProtoValue::Varint(kind)) => {
+                    otp.kind = if kind == 1 {
+                        OtpKind::Hotp
+                    } else {
+                        OtpKind::Totp
+                    };
+                }
+                (7, ProtoValue::Varint(counter)) => otp.counter = counter,
+                _ => {}
+            };
+        }
+
+        otp.validate()?;
+        Ok(otp)
+    }
+
+    /// Build otpauth:// key URI, as consumed by authenticator apps
+    pub fn to_uri(&self) -> Result<String, Error> {
+        let secret = base32::encode(
+            base32::Alphabet::Rfc4648 { padding: false },
+            &Self::decode_secret(&self.secret_code)?,
+        );
+
+        // Label, falling back to the entry name when no account is known
+        let account = if self.account.is_empty() {
+            self.display_name.rsplit('/').next().unwrap_or_default()
+        } else {
+            self.account.as_str()
+        };
+        let label = if self.issuer.is_empty() {
+            percent_encode(account)
+        } else {
+            format!(
+                "{}:{}",
+                percent_encode(&self.issuer),
+                percent_encode(account)
+            )
+        };
+
+        // Parameters
+        let mut uri = format!(
+            "otpauth://{}/{}?secret={}",
+            self.kind.to_string().to_lowercase(),
+            label,
+            secret
+        );
+        if !self.issuer.is_empty() {
+            uri.push_str(&format!("&issuer={}", percent_encode(&self.issuer)));
+        }
+        uri.push_str(&format!(
+            "&algorithm={}&digits={}",
+            self.algorithm, self.digits
+        ));
+        match self.kind {
+            OtpKind::Totp => uri.push_str(&format!("&period={}", self.period)),
+            OtpKind::Hotp => uri.push_str(&format!("&counter={}", self.counter)),
+        };
+
+        Ok(uri)
+    }
+
+    /// Suggested entry name, based on issuer and account
+    pub fn suggested_name(&self) -> String {
+        let name = match (self.issuer.is_empty(), self.account.is_empty()) {
+            (false, false) => format!("{}-{}", self.issuer, self.account),
+            (false, true) => self.issuer.to_string(),
+            (true, false) => self.account.to_string(),
+            (true, true) => "otp".to_string(),
+        };
+        name.replace(['/', ' '], "-").to_lowercase()
+    }
+}
+
+/// Parse numeric URI parameter
+fn parse_number<T: FromStr>(key: &str, value: &str) -> Result<T, Error> {
+    value
+        .parse::<T>()
+        .map_err(|_| Error::Validate(format!("Invalid value for {} parameter, {}", key, value)))
+}
+
+/// Percent encode URI component
+fn percent_encode(value: &str) -> String {
+    utf8_percent_encode(value, URI_COMPONENT).to_string()
+}
+
+/// Decode percent encoded string
+fn percent_decode(value: &str) -> String {
+    percent_decode_str(value).decode_utf8_lossy().to_string()
+}
+
+enum ProtoValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+/// Minimal reader for protobuf wire format
+struct ProtoReader<'a>(&'a [u8]);
+
+impl<'a> ProtoReader<'a> {
+    fn next_field(&mut self) -> Result<Option<(u64, ProtoValue<'a>)>, Error> {
+        if self.0.is_empty() {
+            return Ok(None);
+        }
+        let key = self.read_varint()?;
+
+        let value = match key & 0x07 {
+            0 => ProtoValue::Varint(self.read_varint()?),
+            2 => {
+                let len = self.read_varint()? as usize;
+                ProtoValue::Bytes(self.take(len)?)
+            }
+            1 => ProtoValue::Bytes(self.take(8)?),
+            5 => ProtoValue::Bytes(self.take(4)?),
+            wire_type => {
+                return Err(Error::Validate(format!(
+                    "Unsupported protobuf wire type in migration payload, {}",
+                    wire_type
+                )));
+            }
+        };
+
+        Ok(Some((key >> 3, value)))
+    }
+
+    fn read_varint(&mut self) -> Result<u64, Error> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::Validate(
+            "Invalid varint in migration payload.".to_string(),
+        ))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::Validate("Truncated migration payload.".to_string()));
+        }
+        let (value, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(value)
+    }
+}
@@ -6,16 +6,17 @@
 
 use super::{BaseDbFunctions, BaseDbItem};
 use crate::Error;
-use crate::rpc::{CmdResponse, message};
+use crate::rpc::{CmdResponse, RpcId, message};
 use bincode::{Decode, Encode};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-#[cfg(any(target_os="linux", feature = "fuse"))]
+#[cfg(any(target_os = "linux", feature = "fuse"))]
 use fuser::{FileAttr, FileType};
-#[cfg(any(target_os="linux", feature = "fuse"))]
+#[cfg(any(target_os = "linux", feature = "fuse"))]
 use std::time::{Duration, UNIX_EPOCH};
 
 #[derive(Encode, Decode)]
@@ -25,7 +26,7 @@ pub struct SshKeysDb {
     pub ino2name: HashMap<u64, SshFsEntry>,
 }
 
-#[derive(Clone, Encode, Decode, Serialize, Deserialize)]
+#[derive(Clone, Encode, Decode, Serialize, Deserialize, JsonSchema, Zeroize, ZeroizeOnDrop)]
 pub struct SshKey {
     pub display_name: String,
     pub ino: u64,
@@ -46,12 +47,16 @@ pub struct SshFsEntry {
 
 impl SshKeysDb {
     /// Generate ssh key
-    pub fn generate(&mut self, req_id: usize, _params: &Vec<String>) -> Result<CmdResponse, Error> {
+    pub fn generate(
+        &mut self,
+        req_id: &RpcId,
+        _params: &Vec<String>,
+    ) -> Result<CmdResponse, Error> {
         Ok(CmdResponse::none(message::ok(req_id, true)))
     }
 
     /// Copy item
-    pub fn copy_key(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    pub fn copy_key(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         // Validate
         if params.len() < 2 {
             return Err(Error::Validate("Invalid parameters.".to_string()));
@@ -93,7 +98,7 @@ impl SshKeysDb {
     /// Delete item
     pub fn delete_key(
         &mut self,
-        req_id: usize,
+        req_id: &RpcId,
         params: &Vec<String>,
     ) -> Result<CmdResponse, Error> {
         // Validate
@@ -119,7 +124,7 @@ impl SshKeysDb {
     }
 
     /// Import key
-    pub fn import(&mut self, req_id: usize, params: &Vec<String>) -> Result<CmdResponse, Error> {
+    pub fn import(&mut self, req_id: &RpcId, params: &Vec<String>) -> Result<CmdResponse, Error> {
         if params.is_empty() {
             return Err(Error::Validate("Invalid parameters.".to_string()));
         }
@@ -154,7 +159,7 @@ impl SshKeysDb {
     /// Rename item
     pub fn rename_key(
         &mut self,
-        req_id: usize,
+        req_id: &RpcId,
         params: &Vec<String>,
     ) -> Result<CmdResponse, Error> {
         // Validate
@@ -232,7 +237,7 @@ impl SshKeysDb {
         }
     }
 
-    #[cfg(any(target_os="linux", feature = "fuse"))]
+    #[cfg(any(target_os = "linux", feature = "fuse"))]
     /// Get attributes for file system
     pub fn get_attr(&self, ino: u64) -> Option<FileAttr> {
         let ts = UNIX_EPOCH + Duration::from_secs(1609459200); // Jan 1, 2021
@@ -281,19 +286,6 @@ impl SshKeysDb {
 
 impl BaseDbFunctions for SshKeysDb {
     type Item = SshKey;
-
-    /// Secure clear
-    fn secure_clear(&mut self) {
-        for (_, key) in self.iter_mut() {
-            key.display_name.zeroize();
-            key.host.zeroize();
-            key.port.zeroize();
-            key.username.zeroize();
-            key.public_key.zeroize();
-            key.private_key.zeroize();
-            key.notes.zeroize();
-        }
-    }
 }
 
 impl BaseDbItem for SshKey {
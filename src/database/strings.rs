@@ -6,15 +6,16 @@
 
 use super::{BaseDbFunctions, BaseDbItem};
 use bincode::{Decode, Encode};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[derive(Default, Encode, Decode)]
 pub struct StringsDb(pub HashMap<String, StrItem>);
 
-#[derive(Clone, Decode, Encode, Serialize, Deserialize)]
+#[derive(Clone, Decode, Encode, Serialize, Deserialize, JsonSchema, Zeroize, ZeroizeOnDrop)]
 pub struct StrItem {
     pub display_name: String,
     pub value: String,
@@ -22,14 +23,6 @@ pub struct StrItem {
 
 impl BaseDbFunctions for StringsDb {
     type Item = StrItem;
-
-    /// Secure clear
-    fn secure_clear(&mut self) {
-        for (_, item) in self.iter_mut() {
-            item.display_name.zeroize();
-            item.value.zeroize();
-        }
-    }
 }
 
 impl BaseDbItem for StrItem {
@@ -0,0 +1,261 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::base::page;
+use super::{BaseDbFunctions, BaseDbItem, HistoryDataType};
+use crate::Error;
+use crate::rpc::{CmdResponse, RpcId, message};
+use bincode::{Decode, Encode};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Default, Encode, Decode)]
+pub struct TagsDb {
+    pub entries: HashMap<(HistoryDataType, String), EntryTags>,
+}
+
+/// Tags and favorite flag of an entry
+#[derive(Clone, Default, Encode, Decode, Serialize, Deserialize, JsonSchema)]
+pub struct EntryTags {
+    pub tags: Vec<String>,
+    pub favorite: bool,
+}
+
+impl TagsDb {
+    /// Get tags of entry
+    pub fn get_item<T>(
+        &self,
+        data_type: HistoryDataType,
+        store: &T,
+        req_id: &RpcId,
+        params: &[String],
+    ) -> Result<CmdResponse, Error>
+    where
+        T: BaseDbFunctions,
+    {
+        let name = Self::check_exists(store, params)?;
+        let tags = self.entries.get(&(data_type, name)).cloned().unwrap_or_default();
+        Ok(CmdResponse::none(message::ok(req_id, tags)))
+    }
+
+    /// Add and remove comma separated tags of entry
+    pub fn tag_item<T>(
+        &mut self,
+        data_type: HistoryDataType,
+        store: &T,
+        req_id: &RpcId,
+        params: &[String],
+    ) -> Result<CmdResponse, Error>
+    where
+        T: BaseDbFunctions,
+    {
+        let name = Self::check_exists(store, params)?;
+        let add = parse_tags(params.get(1).map(|s| s.as_str()).unwrap_or_default())?;
+        let remove = parse_tags(params.get(2).map(|s| s.as_str()).unwrap_or_default())?;
+        if add.is_empty() && remove.is_empty() {
+            return Err(Error::Validate(
+                "No tags to add or remove were given.".to_string(),
+            ));
+        }
+
+        let entry = self.entries.entry((data_type, name.to_string())).or_default();
+        entry.tags.retain(|tag| !remove.contains(tag));
+        for tag in add {
+            if !entry.tags.contains(&tag) {
+                entry.tags.push(tag);
+            }
+        }
+        entry.tags.sort();
+
+        let tags = entry.clone();
+        self.prune(data_type, &name);
+        Ok(CmdResponse::new(true, false, message::ok(req_id, tags)))
+    }
+
+    /// Mark or unmark entry as favorite
+    pub fn favorite_item<T>(
+        &mut self,
+        data_type: HistoryDataType,
+        store: &T,
+        req_id: &RpcId,
+        params: &[String],
+    ) -> Result<CmdResponse, Error>
+    where
+        T: BaseDbFunctions,
+    {
+        let name = Self::check_exists(store, params)?;
+        let favorite = params.get(1).is_none_or(|flag| ["1", "true"].contains(&flag.as_str()));
+
+        let entry = self.entries.entry((data_type, name.to_string())).or_default();
+        entry.favorite = favorite;
+
+        let tags = entry.clone();
+        self.prune(data_type, &name);
+        Ok(CmdResponse::new(true, false, message::ok(req_id, tags)))
+    }
+
+    /// List items within directory, optionally only those with tag, favorites first
+    pub fn list_items<T>(
+        &self,
+        data_type: HistoryDataType,
+        store: &T,
+        req_id: &RpcId,
+        params: &[String],
+    ) -> Result<CmdResponse, Error>
+    where
+        T: BaseDbFunctions,
+    {
+        let dir = params.first().map(|s| s.as_str()).unwrap_or_default();
+        let start = params.get(1).and_then(|s| s.parse::<usize>().ok()).unwrap_or(0);
+        let tag = params.get(2).map(|s| s.trim().to_lowercase()).unwrap_or_default();
+
+        // Without tag, list directory as usual
+        if tag.is_empty() {
+            let items = store.list_names(dir, &self.favorites(data_type));
+            return Ok(CmdResponse::none(message::ok(req_id, page(items, start))));
+        }
+
+        // Full names of all items with tag, anywhere below directory
+        let dirname = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir)
+        };
+        let mut items: Vec<(bool, String)> = store
+            .keys()
+            .filter(|key| key.starts_with(&dirname) && self.has_tag(data_type, key, &tag))
+            .map(|key| (!self.is_favorite(data_type, key), key.to_string()))
+            .collect();
+        items.sort();
+
+        let items = items.into_iter().map(|(_, name)| name).collect();
+        Ok(CmdResponse::none(message::ok(req_id, page(items, start))))
+    }
+
+    /// Find items matching search term, optionally only those with tag, favorites first
+    pub fn find_items<T>(
+        &self,
+        data_type: HistoryDataType,
+        store: &T,
+        req_id: &RpcId,
+        params: &[String],
+    ) -> Result<CmdResponse, Error>
+    where
+        T: BaseDbFunctions,
+    {
+        if params.is_empty() {
+            return Err(Error::Validate("Invalid parameters.".to_string()));
+        }
+        let search = params[0].to_lowercase();
+        let tag = params.get(1).map(|s| s.trim().to_lowercase()).unwrap_or_default();
+
+        let mut items: Vec<(bool, String)> = store
+            .iter()
+            .filter(|(key, item)| {
+                item.contains(&search) && (tag.is_empty() || self.has_tag(data_type, key, &tag))
+            })
+            .map(|(key, item)| (!self.is_favorite(data_type, key), item.get_name()))
+            .collect();
+        items.sort();
+
+        let items: Vec<String> = items.into_iter().map(|(_, name)| name).collect();
+        Ok(CmdResponse::none(message::ok(req_id, items)))
+    }
+
+    /// Number of entries with each tag, across all data types
+    pub fn counts(&self) -> BTreeMap<String, u32> {
+        let mut counts = BTreeMap::new();
+        for tag in self.entries.values().flat_map(|entry| entry.tags.iter()) {
+            *counts.entry(tag.to_string()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Number of favorite entries, across all data types
+    pub fn favorite_count(&self) -> u32 {
+        self.entries.values().filter(|entry| entry.favorite).count() as u32
+    }
+
+    /// Give copy of entry the same tags
+    pub fn copy(&mut self, data_type: HistoryDataType, source: &str, dest: &str) {
+        if let Some(tags) = self.entries.get(&(data_type, source.to_lowercase())).cloned() {
+            self.entries.insert((data_type, dest.to_lowercase()), tags);
+        }
+    }
+
+    /// Move tags to new name of entry
+    pub fn rename(&mut self, data_type: HistoryDataType, source: &str, dest: &str) {
+        if let Some(tags) = self.entries.remove(&(data_type, source.to_lowercase())) {
+            self.entries.insert((data_type, dest.to_lowercase()), tags);
+        }
+    }
+
+    /// Remove tags of deleted entry
+    pub fn remove(&mut self, data_type: HistoryDataType, name: &str) {
+        self.entries.remove(&(data_type, name.to_lowercase()));
+    }
+
+    /// Check whether entry has tag
+    fn has_tag(&self, data_type: HistoryDataType, name: &str, tag: &str) -> bool {
+        self.entries
+            .get(&(data_type, name.to_string()))
+            .is_some_and(|entry| entry.tags.iter().any(|t| t == tag))
+    }
+
+    /// Check whether entry is a favorite
+    fn is_favorite(&self, data_type: HistoryDataType, name: &str) -> bool {
+        self.entries.get(&(data_type, name.to_string())).is_some_and(|entry| entry.favorite)
+    }
+
+    /// Names of favorite entries of data type
+    fn favorites(&self, data_type: HistoryDataType) -> HashSet<String> {
+        self.entries
+            .iter()
+            .filter(|((kind, _), entry)| *kind == data_type && entry.favorite)
+            .map(|((_, name), _)| name.to_string())
+            .collect()
+    }
+
+    /// Drop entry left without tags or favorite flag
+    fn prune(&mut self, data_type: HistoryDataType, name: &str) {
+        let key = (data_type, name.to_string());
+        if self.entries.get(&key).is_some_and(|entry| entry.tags.is_empty() && !entry.favorite) {
+            self.entries.remove(&key);
+        }
+    }
+
+    /// Get lowercase name of existing entry
+    fn check_exists<T>(store: &T, params: &[String]) -> Result<String, Error>
+    where
+        T: BaseDbFunctions,
+    {
+        let Some(name) = params.first() else {
+            return Err(Error::Validate("Invalid parameters.".to_string()));
+        };
+        if !store.contains_key(&name.to_lowercase()) {
+            return Err(Error::Validate(format!("No entry exists at, {}", name)));
+        }
+        Ok(name.to_lowercase())
+    }
+}
+
+/// Parse comma separated tags, lowercased
+fn parse_tags(tags: &str) -> Result<Vec<String>, Error> {
+    let mut parsed = vec![];
+    for tag in tags.split(',').map(|tag| tag.trim().to_lowercase()) {
+        if tag.is_empty() {
+            continue;
+        } else if tag.chars().any(|c| c.is_whitespace()) {
+            return Err(Error::Validate(format!(
+                "Invalid tag, {}, tags can not contain spaces.",
+                tag
+            )));
+        }
+        parsed.push(tag);
+    }
+    Ok(parsed)
+}
@@ -5,37 +5,105 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 use super::{BaseDbFunctions, BaseDbItem};
+use crate::Error;
+use base64::{Engine as _, engine::general_purpose};
 use bincode::{Decode, Encode};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
 use std::ops::{Deref, DerefMut};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Maximum size of a single attachment
+pub const MAX_ATTACHMENT_SIZE: usize = 1024 * 1024;
+
+/// Maximum combined size of attachments on one entry
+pub const MAX_ATTACHMENTS_SIZE: usize = 5 * 1024 * 1024;
+
+/// Fields every user has, which custom fields may not be named
+pub const BUILTIN_FIELDS: &[&str] = &["username", "password", "url", "notes"];
 
 #[derive(Default, Encode, Decode)]
 pub struct UsersDb(pub HashMap<String, User>);
 
-#[derive(Clone, Encode, Decode, Serialize, Deserialize)]
+#[derive(Clone, Encode, Decode, Serialize, Deserialize, JsonSchema, Zeroize, ZeroizeOnDrop)]
 pub struct User {
     pub display_name: String,
     pub username: String,
     pub password: String,
     pub url: String,
     pub notes: String,
+    #[serde(default)]
+    pub fields: Vec<CustomField>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
 }
 
-impl BaseDbFunctions for UsersDb {
-    type Item = User;
+/// Named field, concealed ones being masked when shown and excluded from searches
+#[derive(Clone, Encode, Decode, Serialize, Deserialize, JsonSchema, Zeroize, ZeroizeOnDrop)]
+pub struct CustomField {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub concealed: bool,
+}
+
+/// File attached to entry, base64 encoded within JSON
+#[derive(Clone, Encode, Decode, Serialize, Deserialize, JsonSchema, Zeroize, ZeroizeOnDrop)]
+pub struct Attachment {
+    pub name: String,
+    #[serde(serialize_with = "to_base64", deserialize_with = "from_base64")]
+    #[schemars(with = "String")]
+    pub data: Vec<u8>,
+}
 
-    /// Secure clear
-    fn secure_clear(&mut self) {
-        for (_, user) in self.iter_mut() {
-            user.display_name.zeroize();
-            user.username.zeroize();
-            user.password.zeroize();
-            user.url.zeroize();
-            user.notes.zeroize();
+impl User {
+    /// Get custom field by name
+    pub fn field(&self, name: &str) -> Option<&CustomField> {
+        self.fields.iter().find(|field| field.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Get attachment by name
+    pub fn attachment(&self, name: &str) -> Option<&Attachment> {
+        self.attachments.iter().find(|attachment| attachment.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Add custom field, or replace value of existing one in place
+    pub fn set_field(&mut self, name: &str, value: &str, concealed: bool) {
+        let field = CustomField {
+            name: name.to_string(),
+            value: value.to_string(),
+            concealed,
+        };
+        match self.fields.iter_mut().find(|f| f.name.eq_ignore_ascii_case(name)) {
+            Some(existing) => *existing = field,
+            None => self.fields.push(field),
         }
     }
+
+    /// Add attachment, or replace contents of existing one in place
+    pub fn set_attachment(&mut self, name: &str, data: Vec<u8>) {
+        let attachment = Attachment {
+            name: name.to_string(),
+            data,
+        };
+        match self.attachments.iter_mut().find(|a| a.name.eq_ignore_ascii_case(name)) {
+            Some(existing) => *existing = attachment,
+            None => self.attachments.push(attachment),
+        }
+    }
+
+    /// Remove custom field or attachment, returning whether one existed
+    pub fn remove_field(&mut self, name: &str) -> bool {
+        let count = self.fields.len() + self.attachments.len();
+        self.fields.retain(|field| !field.name.eq_ignore_ascii_case(name));
+        self.attachments.retain(|attachment| !attachment.name.eq_ignore_ascii_case(name));
+        count != self.fields.len() + self.attachments.len()
+    }
+}
+
+impl BaseDbFunctions for UsersDb {
+    type Item = User;
 }
 
 impl BaseDbItem for User {
@@ -50,9 +118,72 @@ impl BaseDbItem for User {
         self.display_name.to_lowercase().contains(search)
             || self.username.to_lowercase().contains(search)
             || self.url.to_lowercase().contains(search)
+            || self.fields.iter().filter(|field| !field.concealed).any(|field| {
+                field.name.to_lowercase().contains(search)
+                    || field.value.to_lowercase().contains(search)
+            })
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        // Field and attachment names, unique amongst each other
+        let mut names = HashSet::new();
+        let all_names = self
+            .fields
+            .iter()
+            .map(|field| &field.name)
+            .chain(self.attachments.iter().map(|attachment| &attachment.name));
+        for name in all_names {
+            if name.trim().is_empty() {
+                return Err(Error::Validate(
+                    "Custom field names can not be empty.".to_string(),
+                ));
+            } else if BUILTIN_FIELDS.contains(&name.to_lowercase().as_str()) {
+                return Err(Error::Validate(format!(
+                    "Custom field can not be named {}, as every user already has it.",
+                    name
+                )));
+            } else if !names.insert(name.to_lowercase()) {
+                return Err(Error::Validate(format!(
+                    "More than one custom field or attachment is named {}.",
+                    name
+                )));
+            }
+        }
+
+        // Attachment sizes
+        if let Some(attachment) =
+            self.attachments.iter().find(|attachment| attachment.data.len() > MAX_ATTACHMENT_SIZE)
+        {
+            return Err(Error::Validate(format!(
+                "Attachment {} is {} bytes, exceeding the limit of {} bytes.",
+                attachment.name,
+                attachment.data.len(),
+                MAX_ATTACHMENT_SIZE
+            )));
+        }
+        let total: usize = self.attachments.iter().map(|attachment| attachment.data.len()).sum();
+        if total > MAX_ATTACHMENTS_SIZE {
+            return Err(Error::Validate(format!(
+                "Attachments total {} bytes, exceeding the limit of {} bytes per entry.",
+                total, MAX_ATTACHMENTS_SIZE
+            )));
+        }
+
+        Ok(())
     }
 }
 
+/// Serialize bytes as base64
+fn to_base64<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&general_purpose::STANDARD.encode(data))
+}
+
+/// Deserialize bytes from base64
+fn from_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    general_purpose::STANDARD.decode(encoded.as_bytes()).map_err(serde::de::Error::custom)
+}
+
 impl Deref for UsersDb {
     type Target = HashMap<String, User>;
 
@@ -0,0 +1,347 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::{BaseDbFunctions, BaseDbItem, HistoryDataType};
+use crate::Error;
+use crate::rpc::{CmdResponse, RpcId, message};
+use bincode::{Decode, Encode};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Prior versions kept of each entry, unless changed
+pub const DEFAULT_RETENTION: u32 = 10;
+
+/// Largest combined size of prior versions of all entries, the oldest beyond it are discarded
+pub const MAX_VERSIONS_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Encode, Decode)]
+pub struct VersionsDb {
+    pub retention: u32,
+    pub entries: HashMap<(HistoryDataType, String), Vec<EntryVersion>>,
+}
+
+/// Prior version of an entry, stored as JSON of the item
+#[derive(Clone, Encode, Decode, Zeroize, ZeroizeOnDrop)]
+pub struct EntryVersion {
+    pub timestamp: u64,
+    pub item: String,
+}
+
+/// Prior version returned to clients, newest being number 1
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct VersionInfo {
+    pub version: usize,
+    pub timestamp: u64,
+    pub item: Value,
+}
+
+impl VersionsDb {
+    /// Replace entry, keeping its previous version
+    pub fn edit_item<T>(
+        &mut self,
+        data_type: HistoryDataType,
+        store: &mut T,
+        req_id: &RpcId,
+        params: &Vec<String>,
+    ) -> Result<CmdResponse, Error>
+    where
+        T: BaseDbFunctions,
+    {
+        let previous = params.first().and_then(|name| store.get(&name.to_lowercase()).cloned());
+        let res = store.edit_item(req_id, params)?;
+
+        if let Some(item) = previous {
+            let current = store.get(&params[0].to_lowercase());
+            self.add(data_type, &params[0], &item, current)?;
+        }
+        Ok(res)
+    }
+
+    /// List prior versions of entry, newest first
+    pub fn list_items<T>(
+        &self,
+        data_type: HistoryDataType,
+        store: &T,
+        req_id: &RpcId,
+        params: &[String],
+    ) -> Result<CmdResponse, Error>
+    where
+        T: BaseDbFunctions,
+    {
+        if params.is_empty() {
+            return Err(Error::Validate("Invalid parameters.".to_string()));
+        } else if !store.contains_key(&params[0].to_lowercase()) {
+            return Err(Error::Validate(format!(
+                "No entry exists at, {}",
+                params[0]
+            )));
+        }
+
+        let current = serde_json::to_value(store.get(&params[0].to_lowercase()))?;
+        let mut items = vec![];
+        for (x, version) in self.get(data_type, &params[0]).iter().enumerate() {
+            let mut item = serde_json::from_str(&version.item)?;
+            let _ = self.restore_attachments(data_type, &params[0], &current, &mut item);
+            items.push(VersionInfo {
+                version: x + 1,
+                timestamp: version.timestamp,
+                item,
+            });
+        }
+
+        Ok(CmdResponse::none(message::ok(req_id, items)))
+    }
+
+    /// Restore prior version of entry, keeping the current one as newest version
+    pub fn revert_item<T>(
+        &mut self,
+        data_type: HistoryDataType,
+        store: &mut T,
+        req_id: &RpcId,
+        params: &[String],
+    ) -> Result<CmdResponse, Error>
+    where
+        T: BaseDbFunctions,
+    {
+        // Validate
+        if params.len() < 2 {
+            return Err(Error::Validate("Invalid parameters.".to_string()));
+        }
+        let name = params[0].to_lowercase();
+        let current = store
+            .get(&name)
+            .ok_or(Error::Validate(format!(
+                "No entry exists at, {}",
+                params[0]
+            )))?
+            .clone();
+
+        let number = params[1]
+            .parse::<usize>()
+            .map_err(|e| Error::Validate(format!("Invalid version number: {}", e)))?;
+        let versions = self.get(data_type, &name);
+        if number == 0 || number > versions.len() {
+            return Err(Error::Validate(format!(
+                "No version {} of {} exists, it has {} prior versions",
+                number,
+                params[0],
+                versions.len()
+            )));
+        }
+
+        // Restore under current name, in case entry was renamed since
+        let mut value = serde_json::from_str(&versions[number - 1].item)?;
+        self.restore_attachments(
+            data_type,
+            &name,
+            &serde_json::to_value(&current)?,
+            &mut value,
+        )?;
+        let mut item: T::Item = serde_json::from_value(value)?;
+        item.set_name(&current.get_name());
+
+        self.add(data_type, &name, &current, Some(&item))?;
+        store.insert(name.to_string(), item);
+
+        Ok(CmdResponse::new(true, false, message::ok(req_id, true)))
+    }
+
+    /// Set number of prior versions kept, or get it if none given
+    pub fn retention(&mut self, req_id: &RpcId, params: &[String]) -> Result<CmdResponse, Error> {
+        let Some(count) = params.first() else {
+            return Ok(CmdResponse::none(message::ok(req_id, self.retention)));
+        };
+
+        self.retention = count
+            .parse::<u32>()
+            .map_err(|e| Error::Validate(format!("Invalid retention count: {}", e)))?;
+
+        // Prune versions beyond new count
+        let retention = self.retention as usize;
+        self.entries.retain(|_, versions| {
+            versions.truncate(retention);
+            !versions.is_empty()
+        });
+
+        Ok(CmdResponse::new(
+            true,
+            false,
+            message::ok(req_id, self.retention),
+        ))
+    }
+
+    /// Move versions to new name of entry
+    pub fn rename(&mut self, data_type: HistoryDataType, source: &str, dest: &str) {
+        if let Some(versions) = self.entries.remove(&(data_type, source.to_lowercase())) {
+            self.entries.insert((data_type, dest.to_lowercase()), versions);
+        }
+    }
+
+    /// Remove versions of deleted entry
+    pub fn remove(&mut self, data_type: HistoryDataType, name: &str) {
+        self.entries.remove(&(data_type, name.to_lowercase()));
+    }
+
+    /// Add version of entry, pruning the oldest beyond retention count and size limit
+    fn add<I: Serialize>(
+        &mut self,
+        data_type: HistoryDataType,
+        name: &str,
+        item: &I,
+        current: Option<&I>,
+    ) -> Result<(), Error> {
+        if self.retention == 0 {
+            return Ok(());
+        }
+
+        // Attachments the entry still has are kept once, on the entry itself
+        let mut value = serde_json::to_value(item)?;
+        if let Some(current) = current {
+            omit_attachments(&mut value, &serde_json::to_value(current)?);
+        }
+
+        let versions = self.entries.entry((data_type, name.to_lowercase())).or_default();
+        versions.insert(
+            0,
+            EntryVersion {
+                timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+                item: serde_json::to_string(&value)?,
+            },
+        );
+        versions.truncate(self.retention as usize);
+        self.prune_size();
+
+        Ok(())
+    }
+
+    /// Discard oldest versions across all entries, until within size limit
+    fn prune_size(&mut self) {
+        let mut total: usize =
+            self.entries.values().flatten().map(|version| version.item.len()).sum();
+
+        while total > MAX_VERSIONS_SIZE {
+            let Some(key) = self
+                .entries
+                .iter()
+                .filter_map(|(key, versions)| versions.last().map(|v| (v.timestamp, key)))
+                .min_by_key(|(timestamp, _)| *timestamp)
+                .map(|(_, key)| key.clone())
+            else {
+                break;
+            };
+
+            let versions = self.entries.get_mut(&key).unwrap();
+            if let Some(version) = versions.pop() {
+                total -= version.item.len();
+            }
+            if versions.is_empty() {
+                self.entries.remove(&key);
+            }
+        }
+    }
+
+    /// Fill in data of attachments omitted from version, from the entry or its other versions
+    fn restore_attachments(
+        &self,
+        data_type: HistoryDataType,
+        name: &str,
+        current: &Value,
+        item: &mut Value,
+    ) -> Result<(), Error> {
+        let Some(attachments) = item.get_mut("attachments").and_then(Value::as_array_mut) else {
+            return Ok(());
+        };
+
+        let versions: Vec<Value> = self
+            .get(data_type, name)
+            .iter()
+            .filter_map(|version| serde_json::from_str(&version.item).ok())
+            .collect();
+
+        for attachment in attachments.iter_mut() {
+            let Some(hash) = attachment.get("sha256").and_then(Value::as_str) else {
+                continue;
+            };
+            let data = std::iter::once(current)
+                .chain(versions.iter())
+                .flat_map(attachment_data)
+                .find(|data| data_hash(data) == hash)
+                .ok_or(Error::Db(format!(
+                    "Attachment {} of this version is no longer available.",
+                    attachment["name"].as_str().unwrap_or_default()
+                )))?
+                .to_string();
+
+            attachment["data"] = Value::from(data);
+            if let Some(obj) = attachment.as_object_mut() {
+                obj.remove("sha256");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get versions of entry
+    fn get(&self, data_type: HistoryDataType, name: &str) -> &[EntryVersion] {
+        self.entries
+            .get(&(data_type, name.to_lowercase()))
+            .map(|versions| versions.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Secure clear
+    pub fn secure_clear(&mut self) {
+        for versions in self.entries.values_mut() {
+            versions.iter_mut().for_each(|version| version.zeroize());
+        }
+    }
+}
+
+impl Default for VersionsDb {
+    fn default() -> Self {
+        Self {
+            retention: DEFAULT_RETENTION,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+/// Replace data of attachments the current entry has unchanged with its hash
+fn omit_attachments(item: &mut Value, current: &Value) {
+    let current_data: HashSet<&str> = attachment_data(current).collect();
+    let Some(attachments) = item.get_mut("attachments").and_then(Value::as_array_mut) else {
+        return;
+    };
+
+    for attachment in attachments.iter_mut() {
+        let hash = match attachment.get("data").and_then(Value::as_str) {
+            Some(data) if current_data.contains(data) => data_hash(data),
+            _ => continue,
+        };
+        attachment["data"] = Value::from("");
+        attachment["sha256"] = Value::from(hash);
+    }
+}
+
+/// Base64 data of each attachment of item stored in full
+fn attachment_data(item: &Value) -> impl Iterator<Item = &str> {
+    item.get("attachments")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|attachment| attachment.get("data").and_then(Value::as_str))
+        .filter(|data| !data.is_empty())
+}
+
+/// SHA-256 of attachment data, as hex
+fn data_hash(data: &str) -> String {
+    Sha256::digest(data.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
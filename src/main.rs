@@ -15,6 +15,9 @@ lazy_static! {
     pub static ref CONFIG: NyxConfig = config::load();
 }
 
+#[global_allocator]
+static ALLOCATOR: security::memory::LockingAllocator = security::memory::LockingAllocator;
+
 mod cli;
 mod config;
 pub mod database;
@@ -4,29 +4,50 @@
 // Apache License text: https://www.apache.org/licenses/LICENSE-2.0
 // MIT License text: https://opensource.org/licenses/MIT
 
-use super::{CmdResponse, RpcRequest, message};
+use super::databases::OpenDb;
+use super::events::{self, RpcEvent};
+use super::logind::LockTriggers;
+use super::methods::{self, Handler};
+use super::{CmdResponse, RpcId, RpcRequest, message, tls, token, transport};
 use crate::cli::clipboard;
-use crate::database::{
-    BaseDbFunctions, DatabaseTimeout, DbStats, HistoryAction, HistoryDataType, NyxDb,
-};
+use crate::database::{DatabaseTimeout, DbStats, HistoryAction, HistoryDataType, NyxDb};
+use crate::security::memory;
 use crate::{CONFIG, Error};
-use atlas_http::HttpRequest;
+use atlas_http::{HttpRequest, HttpResponse};
 use falcon_cli::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
 use std::process::exit;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
-use tokio::io::AsyncWriteExt;
-use tokio::net::TcpListener;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
 use tokio::task;
-
-#[cfg(any(target_os="linux", feature = "fuse"))]
+use zeroize::Zeroize;
+
+#[cfg(unix)]
+use nix::libc;
+#[cfg(unix)]
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+#[cfg(unix)]
+use std::path::Path;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+#[cfg(any(target_os = "linux", feature = "fuse"))]
 use fuser::BackgroundSession;
 
 pub struct RpcDaemon {
-    pub nyxdb: Arc<Mutex<NyxDb>>,
-    pub session: Mutex<RpcSession>,
-    #[cfg(any(target_os="linux", feature = "fuse"))]
+    pub databases: Mutex<BTreeMap<String, Arc<OpenDb>>>,
+    pub token: String,
+    pub events: broadcast::Sender<RpcEvent>,
+    #[cfg(any(target_os = "linux", feature = "fuse"))]
     pub fuse_point: Mutex<Option<BackgroundSession>>,
 }
 
@@ -35,24 +56,40 @@ pub struct RpcSession {
     pub lock: [u8; 32],
     pub is_modified: bool,
     pub timeout: DatabaseTimeout,
+    pub lock_on: LockTriggers,
     pub clipboard_timeout: u64,
     pub expires_at: Option<SystemTime>,
     pub clipboard_expires_at: Option<SystemTime>,
 }
 
 impl RpcDaemon {
-    pub fn new(nyxdb: NyxDb, dbfile: &str, n_password: [u8; 32]) -> Self {
+    pub fn new(
+        alias: &str,
+        nyxdb: NyxDb,
+        dbfile: &str,
+        n_password: [u8; 32],
+        token: String,
+    ) -> Self {
+        let open_db = OpenDb::new(
+            alias,
+            nyxdb,
+            dbfile,
+            n_password,
+            CONFIG.timeout,
+            CONFIG.lock_on,
+        );
         Self {
-            session: Mutex::new(RpcSession::new(&nyxdb, dbfile, n_password)),
-            nyxdb: Arc::new(Mutex::new(nyxdb)),
-            #[cfg(any(target_os="linux", feature = "fuse"))]
+            databases: Mutex::new(BTreeMap::from([(alias.to_string(), Arc::new(open_db))])),
+            token,
+            events: broadcast::channel(events::CHANNEL_CAPACITY).0,
+            #[cfg(any(target_os = "linux", feature = "fuse"))]
             fuse_point: Mutex::new(None),
         }
     }
 
     /// Start the daemon
     pub async fn start(self: Arc<Self>) -> Result<(), Error> {
-        #[cfg(any(target_os="linux", feature = "fuse"))]
+        #[cfg(any(target_os = "linux", feature = "fuse"))]
         // Mount fuse point
         {
             if let Err(e) = super::fs_launcher::mount(&self) {
@@ -60,10 +97,63 @@ impl RpcDaemon {
             }
         }
 
+        // Start SSH agent
+        #[cfg(unix)]
+        {
+            let agent_self = Arc::clone(&self);
+            task::spawn(async move {
+                if let Err(e) = super::ssh_agent::listen(agent_self).await {
+                    cli_error!("Unable to start SSH agent, skipping.  Error: {}", e);
+                }
+            });
+        }
+
+        // Lock on sleep, screen lock and logout
+        #[cfg(target_os = "linux")]
+        {
+            let logind_self = Arc::clone(&self);
+            task::spawn(async move {
+                if let Err(e) = super::logind::listen(logind_self).await {
+                    cli_warn!(
+                        "Unable to listen for logind signals, skipping.  Error: {}",
+                        e
+                    );
+                }
+            });
+        }
+
+        // Listen on Unix socket
+        #[cfg(unix)]
+        if CONFIG.rpc_transport.has_unix() {
+            let listener = self.bind_unix()?;
+            let unix_self = Arc::clone(&self);
+            task::spawn(async move {
+                unix_self.listen_unix(listener).await;
+            });
+        }
+
         // Bind to localhost
-        let rpc_host = format!("{}:{}", CONFIG.host, CONFIG.port);
-        let listener = TcpListener::bind(&rpc_host).await?;
-        cli_info!("Listening for connections on {}...", rpc_host);
+        let listener = if CONFIG.rpc_transport.has_tcp() {
+            let rpc_host = format!("{}:{}", CONFIG.host, CONFIG.port);
+            let listener = TcpListener::bind(&rpc_host).await?;
+            cli_info!("Listening for connections on {}...", rpc_host);
+            Some(listener)
+        } else {
+            None
+        };
+
+        // TLS
+        let tls_acceptor = if CONFIG.tls && listener.is_some() {
+            Some(tls::acceptor()?)
+        } else {
+            if listener.is_some() && !is_loopback(&CONFIG.host) {
+                cli_warn!(
+                    "RPC traffic to {} is not encrypted, use --tls when listening on a non-loopback address.",
+                    CONFIG.host
+                );
+            }
+            None
+        };
 
         // Create timer
         let mut timer_interval = tokio::time::interval(tokio::time::Duration::from_secs(15));
@@ -74,22 +164,23 @@ impl RpcDaemon {
                 _ = timer_interval.tick() => {
                     self.check_timer().await;
                 },
-                accept_result = listener.accept() => {
+                accept_result = accept_tcp(&listener) => {
 
-                    let (mut stream, _) = match accept_result {
+                    let (stream, addr) = match accept_result {
                         Ok(r) => r,
                         Err(_) => continue
                     };
 
                     let handler_self = Arc::clone(&self);
+                    let acceptor = tls_acceptor.clone();
                     task::spawn(async move {
-                        if let Ok(req) = HttpRequest::build_async(&mut stream).await {
-
-                            // Handle request
-                            let res = handler_self.handle(req);
-
-                            // Output response
-                            stream.write_all(res.http_res.raw().as_bytes()).await.unwrap();
+                        match acceptor {
+                            Some(acceptor) => {
+                                if let Ok(tls_stream) = acceptor.accept(stream).await {
+                                    handler_self.serve(tls_stream, format!("tls {}", addr), true).await;
+                                }
+                            }
+                            None => handler_self.serve(stream, format!("tcp {}", addr), false).await,
                         }
                     });
 
@@ -98,157 +189,266 @@ impl RpcDaemon {
         }
     }
 
+    /// Bind Unix socket, readable by owner only
+    #[cfg(unix)]
+    fn bind_unix(&self) -> Result<UnixListener, Error> {
+        let sock_path = Path::new(&CONFIG.rpc_sock);
+
+        // Ensure parent directory exists, and is private
+        if let Some(parent) = sock_path.parent()
+            && !parent.exists()
+        {
+            fs::DirBuilder::new().recursive(true).mode(0o700).create(parent)?;
+        }
+
+        // Remove stale socket from previous session
+        if sock_path.exists() {
+            fs::remove_file(sock_path)?;
+        }
+
+        let listener = UnixListener::bind(sock_path)?;
+        fs::set_permissions(sock_path, fs::Permissions::from_mode(0o600))?;
+        cli_info!("Listening for connections on {}...", CONFIG.rpc_sock);
+        Ok(listener)
+    }
+
+    /// Accept connections on Unix socket from the owning user only
+    #[cfg(unix)]
+    async fn listen_unix(self: Arc<Self>, listener: UnixListener) {
+        let uid = unsafe { libc::getuid() };
+        loop {
+            let (stream, _) = match listener.accept().await {
+                Ok(r) => r,
+                Err(_) => continue,
+            };
+
+            // Check peer credentials
+            let peer = match stream.peer_cred() {
+                Ok(cred) if cred.uid() == uid => unix_peer(cred.pid()),
+                Ok(cred) => {
+                    cli_warn!("Rejected RPC connection from uid {}", cred.uid());
+                    continue;
+                }
+                Err(_) => continue,
+            };
+
+            let handler_self = Arc::clone(&self);
+            task::spawn(async move {
+                handler_self.serve(stream, peer, true).await;
+            });
+        }
+    }
+
+    /// Read request from stream, and write response, is_secure if over Unix socket or TLS
+    async fn serve<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        mut stream: S,
+        peer: String,
+        is_secure: bool,
+    ) {
+        if let Ok(req) = transport::read_request(&mut stream, &self.token).await {
+            // Event stream, held open until subscriber disconnects
+            if req.method == "GET" && req.url.ends_with(events::EVENTS_PATH) {
+                if token::verify(&self.token, req.headers.get_lower("authorization")) {
+                    let _ = events::stream(&mut stream, self.events.subscribe()).await;
+                } else {
+                    let _ = stream.write_all(message::unauthorized().raw().as_bytes()).await;
+                }
+                let _ = stream.shutdown().await;
+                return;
+            }
+
+            // Identify client by its user agent and connection, for the history log
+            let client = match req.headers.get_lower("user-agent") {
+                Some(agent) => format!("{} via {}", agent.trim(), peer),
+                None => peer,
+            };
+
+            // Handle request
+            let res = self.handle(req, &client, is_secure);
+
+            // Output response
+            let _ = stream.write_all(res.raw().as_bytes()).await;
+            let _ = stream.shutdown().await;
+        }
+    }
+
     /// Handle incoming connection
-    fn handle(&self, http_req: HttpRequest) -> CmdResponse {
-        // Get HTTP body contents
-        let json_str = match String::from_utf8(http_req.body.get_raw()) {
-            Ok(r) => r,
-            Err(_) => return CmdResponse::none(message::err(0, 400, "Invalid JSON")),
-        };
+    fn handle(&self, http_req: HttpRequest, client: &str, is_secure: bool) -> HttpResponse {
+        // Check session token
+        if !token::verify(&self.token, http_req.headers.get_lower("authorization")) {
+            return message::unauthorized();
+        }
 
         // Decode JSON
-        let req: RpcRequest = match serde_json::from_str(&json_str) {
+        let body: Value = match serde_json::from_slice(&http_req.body.get_raw()) {
             Ok(r) => r,
-            Err(_) => return CmdResponse::none(message::err(0, 500, "Invalid JSON request")),
+            Err(e) => {
+                let msg = format!("Parse error, {}", e);
+                return message::http(Some(message::err(&Value::Null, message::PARSE_ERROR, &msg)));
+            }
         };
 
+        // Batch, leaving out responses to notifications
+        let response = match body {
+            Value::Array(calls) if calls.is_empty() => Some(message::err(
+                &Value::Null,
+                message::INVALID_REQUEST,
+                "Invalid request, empty batch",
+            )),
+            Value::Array(calls) => {
+                let responses: Vec<Value> = calls
+                    .into_iter()
+                    .filter_map(|call| self.call(call, client, is_secure))
+                    .collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            call => self.call(call, client, is_secure),
+        };
+
+        message::http(response)
+    }
+
+    /// Handle single call, giving no response for notifications
+    fn call(&self, call: Value, client: &str, is_secure: bool) -> Option<Value> {
+        let mut req: RpcRequest = match serde_json::from_value::<RpcRequest>(call) {
+            Ok(r) if r.jsonrpc == message::JSONRPC_VERSION => r,
+            _ => {
+                return Some(message::err(
+                    &Value::Null,
+                    message::INVALID_REQUEST,
+                    "Invalid request, expected JSON-RPC 2.0 request object",
+                ));
+            }
+        };
+
+        req.client = client.to_string();
+        req.is_secure = is_secure;
+        let res = self.dispatch(&req);
+        (!req.is_notification()).then_some(res.response)
+    }
+
+    /// Route request to its method
+    fn dispatch(&self, req: &RpcRequest) -> CmdResponse {
+        let req_id = &req.id();
+
         // Check for valid method
-        let parts: Vec<String> = req.method.split(".").map(|w| w.to_string()).collect();
-        if parts.len() != 2 {
+        let Some(method) = methods::get(&req.method) else {
             return CmdResponse::none(message::err(
-                req.id,
-                404,
+                req_id,
+                message::METHOD_NOT_FOUND,
                 &format!("Method does not exist, {}", req.method),
             ));
-        }
+        };
 
-        // Shutdown
-        if req.method.as_str() == "db.close" {
-            self.shutdown();
+        // Get parameters, and check required ones are given
+        let param_names: Vec<&str> = method.params.iter().map(|param| param.name).collect();
+        let params = match req.params.to_strings(&param_names) {
+            Ok(r) => r,
+            Err(e) => {
+                let res = message::err(req_id, message::INVALID_PARAMS, &e.to_string());
+                return CmdResponse::none(res);
+            }
+        };
+        if let Some(param) = method.params[params.len()..].iter().find(|param| param.required) {
+            let msg = format!("Missing parameter, {}", param.name);
+            return CmdResponse::none(message::err(req_id, message::INVALID_PARAMS, &msg));
         }
+        let parts: Vec<String> = req.method.split(".").map(|w| w.to_string()).collect();
+
+        // Methods of the daemon itself, such as opening and closing databases
+        let handler = match method.handler {
+            Handler::Daemon(handler) => {
+                return handler(self, req_id, req, &params)
+                    .unwrap_or_else(|e| CmdResponse::none(message::from_error(req_id, &e)));
+            }
+            Handler::Db(handler) => handler,
+        };
+
+        // Select database, and strip any alias prefix from parameters
+        let (open_db, params) = match self.select_db(req, params) {
+            Ok(r) => r,
+            Err(e) => {
+                let res = message::err(req_id, message::DB_NOT_OPEN, &e.to_string());
+                return CmdResponse::none(res);
+            }
+        };
 
         // Lock data store
-        let mut db = match self.nyxdb.lock() {
+        let mut db = match open_db.nyxdb.lock() {
             Ok(r) => r,
             Err(e) => {
                 return CmdResponse::none(message::err(
-                    req.id,
-                    500,
+                    req_id,
+                    message::INTERNAL_ERROR,
                     &format!("Unable to lock database, {}", e),
                 ));
             }
         };
 
         // Route request
-        let wrapped_res = match (parts[0].as_str(), parts[1].as_str()) {
-            // Database
-            ("db", "history") => db.history.list_items(req.id, &req.params),
-            ("db", "stats") => self.dbstats(req.id, &mut db),
-
-            // Users
-            ("user", "copy") => db.users.copy_item(req.id, &req.params),
-            ("user", "delete") => db.users.delete_item(req.id, &req.params),
-            ("user", "edit") => db.users.edit_item(req.id, &req.params),
-            ("user", "exists") => db.users.exists(req.id, &req.params),
-            ("user", "find") => db.users.find_items(req.id, &req.params),
-            ("user", "get") => db.users.get_item(req.id, &req.params),
-            ("user", "list") => db.users.list_items(req.id, &req.params),
-            ("user", "new") => db.users.add_item(req.id, &req.params),
-            ("user", "rename") => db.users.rename_item(req.id, &req.params),
-
-            // Oaut / OTP
-            ("otp", "copy") => db.oauth.copy_item(req.id, &req.params),
-            ("otp", "delete") => db.oauth.delete_item(req.id, &req.params),
-            ("otp", "edit") => db.oauth.edit_item(req.id, &req.params),
-            ("otp", "exists") => db.oauth.exists(req.id, &req.params),
-            ("otp", "find") => db.oauth.find_items(req.id, &req.params),
-            ("otp", "generate") => db.oauth.generate(req.id, &req.params),
-            ("otp", "get") => db.oauth.get_item(req.id, &req.params),
-            ("otp", "list") => db.oauth.list_items(req.id, &req.params),
-            ("otp", "new") => db.oauth.add_item(req.id, &req.params),
-            ("otp", "rename") => db.oauth.rename_item(req.id, &req.params),
-
-            // SSH keys
-            ("ssh", "copy") => db.ssh_keys.copy_key(req.id, &req.params),
-            ("ssh", "delete") => db.ssh_keys.delete_key(req.id, &req.params),
-            ("ssh", "edit") => db.ssh_keys.edit_item(req.id, &req.params),
-            ("ssh", "exists") => db.ssh_keys.exists(req.id, &req.params),
-            ("ssh", "find") => db.ssh_keys.find_items(req.id, &req.params),
-            ("ssh", "generate") => db.ssh_keys.generate(req.id, &req.params),
-            ("ssh", "import") => db.ssh_keys.import(req.id, &req.params),
-            ("ssh", "get") => db.ssh_keys.get_item(req.id, &req.params),
-            ("ssh", "list") => db.ssh_keys.list_items(req.id, &req.params),
-            ("ssh", "rename") => db.ssh_keys.rename_key(req.id, &req.params),
-
-            // Strings
-            ("str", "copy") => db.strings.copy_item(req.id, &req.params),
-            ("str", "delete") => db.strings.delete_item(req.id, &req.params),
-            ("str", "exists") => db.strings.exists(req.id, &req.params),
-            ("str", "find") => db.strings.find_items(req.id, &req.params),
-            ("str", "get") => db.strings.get_item(req.id, &req.params),
-            ("str", "list") => db.strings.list_items(req.id, &req.params),
-            ("str", "rename") => db.strings.rename_item(req.id, &req.params),
-            ("str", "set") => db.strings.add_item(req.id, &req.params),
-
-            // Notes
-            ("note", "copy") => db.notes.copy_item(req.id, &req.params),
-            ("note", "delete") => db.notes.delete_item(req.id, &req.params),
-            ("note", "edit") => db.notes.edit_item(req.id, &req.params),
-            ("note", "exists") => db.notes.exists(req.id, &req.params),
-            ("note", "find") => db.notes.find_items(req.id, &req.params),
-            ("note", "get") => db.notes.get_item(req.id, &req.params),
-            ("note", "list") => db.notes.list_items(req.id, &req.params),
-            ("note", "new") => db.notes.add_item(req.id, &req.params),
-            ("note", "rename") => db.notes.rename_item(req.id, &req.params),
-
-            _ => Ok(CmdResponse::none(message::err(
-                0,
-                404,
-                &format!("Method does not exist, {}", req.method),
-            ))),
-        };
+        let history_len = db.history.len();
+        let wrapped_res = handler(self, req_id, &open_db, &mut db, &params);
 
         // Check response
         let res = match wrapped_res {
             Ok(r) => r,
-            Err(e) => return CmdResponse::none(message::err(req.id, 500, &e.to_string())),
+            Err(e) => return CmdResponse::none(message::from_error(req_id, &e)),
         };
-
-        // Add history
-        if let Ok(history_action) = HistoryAction::from_str(&parts[1])
-            && let Ok(data_type) = HistoryDataType::from_str(&parts[0])
+        self.update_session(&open_db, &res);
+
+        // Add history, for methods acting on a named entry
+        let mut is_change = res.is_modified;
+        if let Ok(data_type) = HistoryDataType::from_str(&parts[0])
+            && data_type != HistoryDataType::Database
+            && let Some(history_action) = HistoryAction::from_method(&parts[1], res.is_copy)
+            && let Some(name) = params.first()
         {
-            let dest = if ["copy", "rename"].contains(&parts[1].as_str()) {
-                req.params[1].to_string()
-            } else {
-                "".to_string()
+            let dest = match params.get(1) {
+                Some(dest) if ["copy", "rename"].contains(&parts[1].as_str()) => dest.to_string(),
+                _ => "".to_string(),
             };
-            if let Err(e) = db.history.add(history_action, data_type, &req.params[0], &dest) {
+            if let Err(e) = db.history.add(history_action, data_type, name, &dest) {
                 return CmdResponse::none(message::err(
-                    req.id,
-                    500,
+                    req_id,
+                    message::INTERNAL_ERROR,
                     &format!("Unable to add history entry: {}", e),
                 ));
             }
+            is_change |= history_action.is_change();
+        }
+
+        // Stamp history written by this request with the client, chain it, and publish events
+        let added = db.history.len().saturating_sub(history_len);
+        for item in db.history.iter_mut().take(added) {
+            item.client = req.client.to_string();
+        }
+        db.history.seal();
+        for item in db.history.iter().take(added).rev() {
+            self.publish(RpcEvent::entry(&open_db.alias, item));
+        }
 
-            if let Err(e) = self.savedb(req.id, &mut db) {
+        // Save changes now, leaving reads to be saved along with the next change or when locked
+        if is_change {
+            if let Err(e) = self.savedb(req_id, &open_db, &mut db) {
                 return CmdResponse::none(message::err(
-                    req.id,
-                    500,
+                    req_id,
+                    message::INTERNAL_ERROR,
                     &format!("Unable to save database: {}", e),
                 ));
             }
+        } else if added > 0
+            && let Ok(mut session) = open_db.session.lock()
+        {
+            session.is_modified = true;
         }
 
-        // Update session
-        self.update_session(&res);
-
         res
     }
 
-    fn update_session(&self, res: &CmdResponse) {
+    fn update_session(&self, open_db: &OpenDb, res: &CmdResponse) {
         // Lock session
-        let mut session = match self.session.lock() {
+        let mut session = match open_db.session.lock() {
             Ok(r) => r,
             Err(_) => return,
         };
@@ -260,21 +460,29 @@ impl RpcDaemon {
             None
         };
 
-        if res.is_modified {
-            session.is_modified = true;
-        }
-
         if res.is_copy {
             session.clipboard_expires_at =
                 Some(SystemTime::now() + Duration::from_secs(session.clipboard_timeout));
         }
     }
 
+    /// Publish event to all subscribers
+    pub(super) fn publish(&self, event: RpcEvent) {
+        let _ = self.events.send(event);
+    }
+
     /// Save database
-    fn savedb(&self, req_id: usize, db: &mut NyxDb) -> Result<CmdResponse, Error> {
+    fn savedb(
+        &self,
+        req_id: &RpcId,
+        open_db: &OpenDb,
+        db: &mut NyxDb,
+    ) -> Result<CmdResponse, Error> {
         // Lock session
-        let mut session =
-            self.session.lock().map_err(|e| Error::Db(format!("Unable to load session: {}", e)))?;
+        let mut session = open_db
+            .session
+            .lock()
+            .map_err(|e| Error::Db(format!("Unable to load session: {}", e)))?;
 
         // Save
         db.save(&session.dbfile, session.lock, None)?;
@@ -284,9 +492,14 @@ impl RpcDaemon {
     }
 
     /// Get database stats
-    fn dbstats(&self, req_id: usize, db: &mut NyxDb) -> Result<CmdResponse, Error> {
+    pub(super) fn dbstats(
+        &self,
+        req_id: &RpcId,
+        open_db: &OpenDb,
+        db: &mut NyxDb,
+    ) -> Result<CmdResponse, Error> {
         // Lock session
-        let session = match self.session.lock() {
+        let session = match open_db.session.lock() {
             Ok(r) => r,
             Err(e) => return Err(Error::Db(format!("Unable to lock session: {}", e))),
         };
@@ -296,11 +509,27 @@ impl RpcDaemon {
     }
 
     /// Shutdown
-    fn shutdown(&self) {
-        // Secure clear database
-        if let Ok(mut db) = self.nyxdb.lock() {
-            db.secure_clear();
+    pub(super) fn shutdown(&self) {
+        // Save pending history, and secure clear databases
+        for open_db in self.databases() {
+            if let Err(e) = open_db.save_pending() {
+                cli_error!("Unable to save database '{}': {}", open_db.alias, e);
+            }
+            open_db.secure_clear();
+            self.publish(RpcEvent::lock(&open_db.alias, "closed"));
+        }
+
+        // Give subscribers a moment to receive the final events, letting this worker's tasks run
+        if self.events.receiver_count() > 0 {
+            task::block_in_place(|| std::thread::sleep(Duration::from_millis(250)));
+        }
+
+        #[cfg(unix)]
+        {
+            super::ssh_agent::cleanup();
+            let _ = fs::remove_file(&CONFIG.rpc_sock);
         }
+        token::cleanup();
 
         cli_info!("Received shutdown order, gracefully exiting.\n");
         exit(0);
@@ -308,32 +537,87 @@ impl RpcDaemon {
 
     /// Check timer
     async fn check_timer(&self) {
-        // Lock session
-        let mut session = match self.session.lock() {
-            Ok(r) => r,
-            Err(_) => return,
-        };
-
-        // Check clearing of clipboard
-        if let Some(clip_expires_at) = session.clipboard_expires_at
-            && SystemTime::now() >= clip_expires_at
-        {
-            let _ = clipboard::copy("");
-            session.clipboard_expires_at = None;
+        // Report allocations made past the memory lock limit
+        let lock_failures = memory::take_lock_failures();
+        if lock_failures > 0 {
+            cli_warn!(
+                "Memory lock limit reached, {} more allocations were not locked and may be swapped to disk.",
+                lock_failures
+            );
         }
 
-        // Check database expiration
-        if let Some(expires_at) = session.expires_at
-            && SystemTime::now() > expires_at
-        {
-            self.shutdown();
+        for open_db in self.databases() {
+            // Lock session
+            let (clip_expired, expired) = match open_db.session.lock() {
+                Ok(session) => (
+                    session.clipboard_expires_at.map(|at| SystemTime::now() >= at).unwrap_or(false),
+                    session.expires_at.map(|at| SystemTime::now() > at).unwrap_or(false),
+                ),
+                Err(_) => continue,
+            };
+
+            // Check clearing of clipboard
+            if clip_expired {
+                let _ = clipboard::copy("");
+                if let Ok(mut session) = open_db.session.lock() {
+                    session.clipboard_expires_at = None;
+                }
+                self.publish(RpcEvent::clipboard_cleared(&open_db.alias));
+            }
+
+            // Check database expiration
+            if expired {
+                cli_info!(
+                    "Database '{}' expired due to inactivity, locking.",
+                    open_db.alias
+                );
+                let _ = self.lock_db(&open_db.alias, "timeout");
+            }
         }
     }
 }
 
+/// Check whether host is a loopback address
+fn is_loopback(host: &str) -> bool {
+    host.parse::<IpAddr>().map(|ip| ip.is_loopback()).unwrap_or(host == "localhost")
+}
+
+/// Describe client connected over Unix socket by its process
+#[cfg(unix)]
+pub(super) fn unix_peer(pid: Option<i32>) -> String {
+    let Some(pid) = pid else {
+        return "unix".to_string();
+    };
+
+    match fs::read_to_string(format!("/proc/{}/comm", pid)) {
+        Ok(name) => format!("unix pid {} ({})", pid, name.trim()),
+        Err(_) => format!("unix pid {}", pid),
+    }
+}
+
+/// Accept TCP connection, or wait forever if not listening on TCP
+async fn accept_tcp(listener: &Option<TcpListener>) -> io::Result<(TcpStream, SocketAddr)> {
+    match listener {
+        Some(listener) => listener.accept().await,
+        None => std::future::pending().await,
+    }
+}
+
+impl Drop for RpcSession {
+    fn drop(&mut self) {
+        self.lock.zeroize();
+    }
+}
+
 impl RpcSession {
-    pub fn new(nyxdb: &NyxDb, dbfile: &str, lock: [u8; 32]) -> Self {
-        let timeout = if let Some(to) = CONFIG.timeout {
+    pub fn new(
+        nyxdb: &NyxDb,
+        dbfile: &str,
+        lock: [u8; 32],
+        timeout: Option<DatabaseTimeout>,
+        lock_on: LockTriggers,
+    ) -> Self {
+        let timeout = if let Some(to) = timeout {
             to
         } else {
             nyxdb.default_timeout
@@ -350,6 +634,7 @@ impl RpcSession {
             dbfile: dbfile.to_string(),
             lock,
             timeout,
+            lock_on,
             clipboard_timeout: CONFIG.clipboard_timeout,
             is_modified: false,
             expires_at,
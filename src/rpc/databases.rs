@@ -0,0 +1,356 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::daemon::RpcSession;
+use super::events::RpcEvent;
+use super::logind::LockTriggers;
+use super::{CmdResponse, RpcDaemon, RpcId, RpcRequest, message};
+use crate::Error;
+use crate::database::{DatabaseTimeout, HistoryAction, HistoryDataType, NyxDb};
+use base64::{Engine as _, engine::general_purpose};
+use falcon_cli::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+use zeroize::Zeroize;
+
+/// Alias of the database opened without -D
+pub const DEFAULT_ALIAS: &str = "default";
+
+/// Most failed unlock attempts recorded before an unlock, as given by the client
+const MAX_FAILED_ATTEMPTS: u32 = 1000;
+
+/// Unlocked database, with its own session and lock timeout
+pub struct OpenDb {
+    pub alias: String,
+    pub nyxdb: Arc<Mutex<NyxDb>>,
+    pub session: Mutex<RpcSession>,
+}
+
+/// Summary of an open database, as returned by db.list
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct OpenDbInfo {
+    pub alias: String,
+    pub dbfile: String,
+    pub expires_at: Option<u64>,
+}
+
+impl OpenDb {
+    pub fn new(
+        alias: &str,
+        nyxdb: NyxDb,
+        dbfile: &str,
+        n_password: [u8; 32],
+        timeout: Option<DatabaseTimeout>,
+        lock_on: LockTriggers,
+    ) -> Self {
+        Self {
+            alias: alias.to_string(),
+            session: Mutex::new(RpcSession::new(
+                &nyxdb, dbfile, n_password, timeout, lock_on,
+            )),
+            nyxdb: Arc::new(Mutex::new(nyxdb)),
+        }
+    }
+
+    /// Secure clear database and session key
+    pub fn secure_clear(&self) {
+        if let Ok(mut db) = self.nyxdb.lock() {
+            db.secure_clear();
+        }
+        if let Ok(mut session) = self.session.lock() {
+            session.lock.zeroize();
+        }
+    }
+
+    /// Save changes not yet written, such as history of reads
+    pub fn save_pending(&self) -> Result<(), Error> {
+        let mut db = self.nyxdb.lock().map_err(|e| Error::Db(e.to_string()))?;
+        let mut session = self.session.lock().map_err(|e| Error::Db(e.to_string()))?;
+        if session.is_modified {
+            db.save(&session.dbfile, session.lock, None)?;
+            session.is_modified = false;
+        }
+        Ok(())
+    }
+
+    /// Record unlock by client in history, after any wrong passwords entered before it
+    pub fn log_unlock(&self, client: &str, failed_attempts: u32) {
+        let (Ok(mut db), Ok(mut session)) = (self.nyxdb.lock(), self.session.lock()) else {
+            return;
+        };
+
+        // Wrong passwords as a single entry carrying their count
+        let mut actions = vec![];
+        match failed_attempts.min(MAX_FAILED_ATTEMPTS) {
+            0 => {}
+            1 => actions.push((HistoryAction::UnlockFailed, "1 attempt".to_string())),
+            n => actions.push((HistoryAction::UnlockFailed, format!("{} attempts", n))),
+        }
+        actions.push((HistoryAction::Unlock, String::new()));
+
+        for (action, dest) in actions {
+            if db.history.add(action, HistoryDataType::Database, &session.dbfile, &dest).is_ok() {
+                db.history[0].client = client.to_string();
+            }
+        }
+        db.history.seal();
+
+        // Saved along with the next change, keeping unlocking fast
+        session.is_modified = true;
+    }
+
+    /// Check whether the inactivity timeout has passed
+    pub fn is_expired(&self) -> bool {
+        let session = match self.session.lock() {
+            Ok(r) => r,
+            Err(_) => return true,
+        };
+
+        match session.expires_at {
+            Some(expires_at) => std::time::SystemTime::now() > expires_at,
+            None => false,
+        }
+    }
+}
+
+impl RpcDaemon {
+    /// Get all open databases
+    pub fn databases(&self) -> Vec<Arc<OpenDb>> {
+        match self.databases.lock() {
+            Ok(databases) => databases.values().cloned().collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Unlock database and add it under alias, replacing any database already open under it
+    pub(super) fn open_db(
+        &self,
+        req_id: &RpcId,
+        req: &RpcRequest,
+        params: &[String],
+    ) -> Result<CmdResponse, Error> {
+        if params.len() < 3 {
+            return Err(Error::Validate("Invalid parameters.".to_string()));
+        }
+
+        // Database key is never accepted in the clear
+        if !req.is_secure {
+            return Err(Error::Validate(
+                "Databases can only be opened over the Unix socket or TLS, not plain TCP."
+                    .to_string(),
+            ));
+        }
+        let (alias, dbfile) = (params[0].to_string(), params[1].to_string());
+        if alias.is_empty() || alias.contains([':', '/']) {
+            return Err(Error::Validate(format!(
+                "Invalid database alias, {}",
+                alias
+            )));
+        }
+
+        // Decode key
+        let mut tmp_password = general_purpose::STANDARD
+            .decode(&params[2])
+            .map_err(|e| Error::Generic(format!("Base64 decode error: {}", e)))?;
+        let n_password: [u8; 32] = tmp_password
+            .as_slice()
+            .try_into()
+            .map_err(|_| Error::Validate("Invalid database key.".to_string()))?;
+        tmp_password.zeroize();
+
+        let timeout = match params.get(3).filter(|t| !t.is_empty()) {
+            Some(timeout) => Some(DatabaseTimeout::from_str(timeout)?),
+            None => None,
+        };
+        let lock_on = match params.get(4).filter(|l| !l.is_empty()) {
+            Some(lock_on) => LockTriggers::from_str(lock_on)?,
+            None => LockTriggers::default(),
+        };
+        let failed_attempts = params.get(5).and_then(|f| f.parse::<u32>().ok()).unwrap_or(0);
+
+        // Same file may not be open under two aliases
+        for open_db in self.databases() {
+            if open_db.alias != alias
+                && open_db.session.lock().map(|s| s.dbfile == dbfile).unwrap_or(false)
+            {
+                return Err(Error::Validate(format!(
+                    "Database {} is already open as '{}'",
+                    dbfile, open_db.alias
+                )));
+            }
+        }
+
+        // Load
+        let nyxdb = NyxDb::load(&dbfile, n_password)?;
+        let mut databases = self
+            .databases
+            .lock()
+            .map_err(|e| Error::Db(format!("Unable to lock databases: {}", e)))?;
+
+        // Replace in place, so the fuse point keeps serving the same alias
+        let open_db = if let Some(open_db) = databases.get(&alias) {
+            let _ = open_db.save_pending();
+            let mut db = open_db.nyxdb.lock().map_err(|e| Error::Db(e.to_string()))?;
+            db.secure_clear();
+            *open_db.session.lock().map_err(|e| Error::Db(e.to_string()))? =
+                RpcSession::new(&nyxdb, &dbfile, n_password, timeout, lock_on);
+            *db = nyxdb;
+            Arc::clone(open_db)
+        } else {
+            let open_db = Arc::new(OpenDb::new(
+                &alias, nyxdb, &dbfile, n_password, timeout, lock_on,
+            ));
+            databases.insert(alias.to_string(), Arc::clone(&open_db));
+            open_db
+        };
+        open_db.log_unlock(&req.client, failed_attempts);
+        self.publish(RpcEvent::unlock(&alias));
+
+        Ok(CmdResponse::none(message::ok(req_id, true)))
+    }
+
+    /// Close database of alias, or all databases and the daemon if no alias given
+    pub(super) fn close_db(
+        &self,
+        req_id: &RpcId,
+        alias: &Option<String>,
+    ) -> Result<CmdResponse, Error> {
+        let Some(alias) = alias else {
+            self.shutdown();
+            return Ok(CmdResponse::none(message::ok(req_id, true)));
+        };
+
+        self.lock_db(alias, "closed")?;
+        Ok(CmdResponse::none(message::ok(req_id, true)))
+    }
+
+    /// Remove and secure clear database of alias, shutting down once none are left
+    pub(super) fn lock_db(&self, alias: &str, reason: &str) -> Result<(), Error> {
+        let (open_db, is_empty) = {
+            let mut databases = self
+                .databases
+                .lock()
+                .map_err(|e| Error::Db(format!("Unable to lock databases: {}", e)))?;
+            let open_db = databases.remove(alias).ok_or(Error::Validate(not_open(alias)))?;
+            (open_db, databases.is_empty())
+        };
+        if let Err(e) = open_db.save_pending() {
+            cli_error!("Unable to save database '{}': {}", alias, e);
+        }
+        open_db.secure_clear();
+        self.publish(RpcEvent::lock(alias, reason));
+
+        if is_empty {
+            self.shutdown();
+        }
+        Ok(())
+    }
+
+    /// List open databases
+    pub(super) fn list_dbs(&self, req_id: &RpcId) -> Result<CmdResponse, Error> {
+        let mut res = vec![];
+        for open_db in self.databases() {
+            let session = open_db
+                .session
+                .lock()
+                .map_err(|e| Error::Db(format!("Unable to lock session: {}", e)))?;
+            res.push(OpenDbInfo {
+                alias: open_db.alias.to_string(),
+                dbfile: session.dbfile.to_string(),
+                expires_at: session
+                    .expires_at
+                    .and_then(|at| at.duration_since(UNIX_EPOCH).ok())
+                    .map(|at| at.as_secs()),
+            });
+        }
+
+        Ok(CmdResponse::none(message::ok(req_id, res)))
+    }
+
+    /// Select database of request, from -D selector or alias:name prefix of the first parameter
+    pub(super) fn select_db(
+        &self,
+        req: &RpcRequest,
+        params: Vec<String>,
+    ) -> Result<(Arc<OpenDb>, Vec<String>), Error> {
+        let databases = self
+            .databases
+            .lock()
+            .map_err(|e| Error::Db(format!("Unable to lock databases: {}", e)))?;
+
+        // Explicit selector
+        if let Some(alias) = &req.db {
+            let open_db = databases.get(alias).ok_or(Error::Validate(not_open(alias)))?;
+            return Ok((Arc::clone(open_db), params));
+        }
+
+        // Prefix of entry name
+        if let Some((alias, name)) = params.first().and_then(|p| p.split_once(':'))
+            && let Some(open_db) = databases.get(alias)
+        {
+            let (open_db, prefix, name) =
+                (Arc::clone(open_db), format!("{}:", alias), name.to_string());
+            let mut params = params;
+            params[0] = name;
+
+            // Destination of copy / rename, and display name of new / edited items
+            if req.method.ends_with(".copy") || req.method.ends_with(".rename") {
+                if let Some(dest) = params[1..].first().and_then(|p| p.strip_prefix(&prefix)) {
+                    params[1] = dest.to_string();
+                } else if params.len() > 1 && params[1].contains(':') {
+                    return Err(Error::Validate(
+                        "Copying entries between databases is not supported.".to_string(),
+                    ));
+                }
+            } else if let Some(item) = params.get_mut(1) {
+                strip_display_name(item, &prefix);
+            }
+
+            return Ok((open_db, params));
+        }
+
+        // Default database
+        let open_db = match databases.len() {
+            1 => databases.values().next(),
+            _ => databases.get(DEFAULT_ALIAS),
+        }
+        .ok_or(Error::Validate(
+            "Multiple databases are open, select one with -D <ALIAS> or an <ALIAS>:<NAME> prefix."
+                .to_string(),
+        ))?;
+
+        Ok((Arc::clone(open_db), params))
+    }
+}
+
+/// Remove alias prefix from display name of JSON encoded item
+fn strip_display_name(item: &mut String, prefix: &str) {
+    let Ok(mut value) = serde_json::from_str::<Value>(item) else {
+        return;
+    };
+
+    if let Some(Value::String(name)) = value.get_mut("display_name")
+        && let Some(stripped) = name.strip_prefix(prefix)
+    {
+        *name = stripped.to_string();
+        if let Ok(json) = serde_json::to_string(&value) {
+            item.zeroize();
+            *item = json;
+        }
+    }
+}
+
+/// Error message for alias not open
+fn not_open(alias: &str) -> String {
+    format!(
+        "No database is open as '{}', open it with:  nyx -D {} -f <DBFILE> db open",
+        alias, alias
+    )
+}
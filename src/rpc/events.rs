@@ -0,0 +1,120 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::Error;
+use crate::database::HistoryItem;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{self, Duration};
+
+/// Path of the Server-Sent Events endpoint
+pub const EVENTS_PATH: &str = "/events";
+
+/// Events held for each subscriber before slow ones start missing them
+pub const CHANNEL_CAPACITY: usize = 256;
+
+// Seconds between keep-alive comments, also how quickly closed subscribers are noticed
+const KEEP_ALIVE_SECS: u64 = 15;
+
+/// Change within the daemon sent to subscribers, never including secret values
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcEvent {
+    pub event: String,
+    pub db: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dest: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub timestamp: u64,
+}
+
+impl RpcEvent {
+    /// Database unlocked
+    pub fn unlock(db: &str) -> Self {
+        Self::new("unlock", db)
+    }
+
+    /// Database locked, either closed or timed out
+    pub fn lock(db: &str, reason: &str) -> Self {
+        Self {
+            reason: Some(reason.to_string()),
+            ..Self::new("lock", db)
+        }
+    }
+
+    /// Clipboard cleared after copying a secret
+    pub fn clipboard_cleared(db: &str) -> Self {
+        Self::new("clipboard_cleared", db)
+    }
+
+    /// Entry created, updated, deleted, copied or renamed, from its history item
+    pub fn entry(db: &str, item: &HistoryItem) -> Self {
+        let event = format!("{:?}", item.action).to_lowercase();
+        Self {
+            data_type: Some(item.data_type.prefix().to_string()),
+            name: Some(item.source.to_string()),
+            dest: (!item.dest.is_empty()).then(|| item.dest.to_string()),
+            timestamp: item.timestamp,
+            ..Self::new(&event, db)
+        }
+    }
+
+    fn new(event: &str, db: &str) -> Self {
+        Self {
+            event: event.to_string(),
+            db: db.to_string(),
+            data_type: None,
+            name: None,
+            dest: None,
+            reason: None,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }
+    }
+
+    /// Format as Server-Sent Events message
+    pub fn to_sse(&self) -> String {
+        let data = serde_json::to_string(self).unwrap_or_default();
+        format!("event: {}\ndata: {}\n\n", self.event, data)
+    }
+}
+
+/// Stream events to subscriber until it disconnects
+pub async fn stream<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    mut rx: broadcast::Receiver<RpcEvent>,
+) -> Result<(), Error> {
+    let header = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+    stream.write_all(header.as_bytes()).await?;
+    stream.flush().await?;
+
+    let mut keep_alive = time::interval(Duration::from_secs(KEEP_ALIVE_SECS));
+    keep_alive.tick().await;
+
+    loop {
+        let message = tokio::select! {
+            _ = keep_alive.tick() => ": keep-alive\n\n".to_string(),
+            res = rx.recv() => match res {
+                Ok(event) => event.to_sse(),
+                Err(RecvError::Lagged(missed)) => {
+                    format!("event: lagged\ndata: {{\"missed\":{}}}\n\n", missed)
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        };
+
+        stream.write_all(message.as_bytes()).await?;
+        stream.flush().await?;
+    }
+}
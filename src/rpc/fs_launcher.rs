@@ -25,8 +25,13 @@ pub fn mount(daemon: &RpcDaemon) -> Result<(), Error> {
         vec![]
     };
 
-    // Mount
-    let fs_instance = NyxFs(Arc::clone(&daemon.nyxdb));
+    // Mount, serving the database the daemon was launched with
+    let open_db = daemon
+        .databases()
+        .into_iter()
+        .next()
+        .ok_or(Error::Db("No database is open.".to_string()))?;
+    let fs_instance = NyxFs(Arc::clone(&open_db.nyxdb));
     let fuse_session = fuser::spawn_mount2(fs_instance, &CONFIG.fuse_mount_dir, &options)
         .map_err(|e| Error::Db(format!("Unable to mount fuse filesystem: {}", e)))?;
     *daemon.fuse_point.lock().unwrap() = Some(fuse_session);
@@ -130,14 +135,15 @@ pub fn check_mount_successful() {
     }
     #[cfg(target_os = "macos")]
     {
-        let macfuse_installed = Path::new("/Library/Filesystems/macfuse.fs").exists() 
+        let macfuse_installed = Path::new("/Library/Filesystems/macfuse.fs").exists()
             || Path::new("/Library/Filesystems/osxfuse.fs").exists();
-        
+
         if !macfuse_installed {
-            cli_warn!("MacFUSE is not installed, to resolve visit https://macfuse.github.io/ for installation instructions.\n");
+            cli_warn!(
+                "MacFUSE is not installed, to resolve visit https://macfuse.github.io/ for installation instructions.\n"
+            );
         } else {
             cli_warn!("MacFUSE appears to be installed, unknown error.\n");
         }
     }
 }
-
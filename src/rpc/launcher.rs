@@ -4,17 +4,17 @@
 // Apache License text: https://www.apache.org/licenses/LICENSE-2.0
 // MIT License text: https://opensource.org/licenses/MIT
 
-use super::RpcDaemon;
+use super::{DEFAULT_ALIAS, RpcDaemon};
+use crate::database::DatabaseTimeout;
 use crate::database::NyxDb;
+use crate::security::memory;
 use crate::{CONFIG, Error};
 use base64::{Engine as _, engine::general_purpose};
 use falcon_cli::*;
-use std::convert::TryInto;
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
 use std::net::{SocketAddr, TcpStream};
 use std::process::{Command, exit};
-#[cfg(not(target_os="windows"))]
-use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
 use std::{env, thread};
@@ -25,6 +25,8 @@ use zeroize::Zeroize;
 use nix::libc;
 #[cfg(windows)]
 use std::ffi::OsString;
+#[cfg(unix)]
+use std::os::fd::AsRawFd;
 #[cfg(windows)]
 use std::os::windows::ffi::OsStrExt;
 #[cfg(windows)]
@@ -44,6 +46,42 @@ use winapi::um::winbase::CREATE_NEW_PROCESS_GROUP;
 #[cfg(windows)]
 use winapi::um::winbase::DETACHED_PROCESS;
 
+/// Open database alongside others if -D given and daemon running, otherwise (re)launch daemon
+pub fn open(dbfile: &str, n_password: [u8; 32]) -> Result<(), Error> {
+    if CONFIG.db_alias.is_some() && ping() {
+        attach(dbfile, n_password)
+    } else {
+        launch(dbfile, n_password)
+    }
+}
+
+/// Unlock additional database within the running daemon, under alias given by -D
+pub fn attach(dbfile: &str, n_password: [u8; 32]) -> Result<(), Error> {
+    let alias = CONFIG.db_alias.as_deref().unwrap_or(DEFAULT_ALIAS);
+    let timeout = match CONFIG.timeout {
+        Some(DatabaseTimeout::Never) => "n".to_string(),
+        Some(DatabaseTimeout::Duration(duration)) => format!("{}s", duration.as_secs()),
+        None => String::new(),
+    };
+
+    let mut hashed_password = general_purpose::STANDARD.encode(n_password);
+    let res = super::send::<String, bool>(
+        "db.open",
+        &vec![
+            alias.to_string(),
+            dbfile.to_string(),
+            hashed_password.to_string(),
+            timeout,
+            CONFIG.lock_on.to_string(),
+            NyxDb::take_failed_unlocks().to_string(),
+        ],
+    );
+    hashed_password.zeroize();
+    res?;
+
+    Ok(())
+}
+
 /// Launch the RPC daemon
 /// Run start command, launch RPC daemon
 pub fn launch(dbfile: &str, n_password: [u8; 32]) -> Result<(), Error> {
@@ -53,10 +91,7 @@ pub fn launch(dbfile: &str, n_password: [u8; 32]) -> Result<(), Error> {
         thread::sleep(Duration::from_millis(300));
     }
 
-    // Base64
-    let hashed_password = general_purpose::STANDARD.encode(n_password);
-
-    #[cfg(any(target_os="linux", feature = "fuse"))]
+    #[cfg(any(target_os = "linux", feature = "fuse"))]
     // Check for unmount
     {
         if super::fs_launcher::is_mount_point(&CONFIG.fuse_mount_dir) {
@@ -78,21 +113,37 @@ pub fn launch(dbfile: &str, n_password: [u8; 32]) -> Result<(), Error> {
         } else if [
             "-f",
             "--dbfile",
+            "-D",
+            "--db",
             "-h",
             "--host",
             "-p",
             "--port",
             "-t",
             "--timeout",
+            "-L",
+            "--lock-on",
             "-c",
             "--cb-timeout",
             "-m",
             "--mount-dir",
+            "-a",
+            "--agent-sock",
+            "-k",
+            "--token-file",
+            "-s",
+            "--rpc-sock",
+            "-r",
+            "--rpc-transport",
+            "--tls-cert",
+            "--tls-key",
         ]
         .contains(&value.as_str())
         {
             cmd_args.push(value.to_string());
             include_next = true;
+        } else if ["-S", "--tls"].contains(&value.as_str()) {
+            cmd_args.push(value.to_string());
         }
     }
     cmd_args.push("-d".to_string());
@@ -102,14 +153,23 @@ pub fn launch(dbfile: &str, n_password: [u8; 32]) -> Result<(), Error> {
     let mut cmd = Command::new(&run_cmd);
     cmd.args(cmd_args);
 
-    // Set environment vars for child command only
-    cmd.env("NYX_LAUNCH_HASH", &hashed_password);
-    cmd.env("NYX_LAUNCH_DBFILE", dbfile);
-
     // Set up the command to detach
     #[cfg(unix)]
     {
-        let mut child = cmd.stdin(Stdio::null()).stdout(log_file).stderr(err_file).spawn()?;
+        // Hand key and database file over a pipe on stdin, instead of the environment
+        let (reader, mut writer) = io::pipe()?;
+        let mut child = cmd.stdin(reader).stdout(log_file).stderr(err_file).spawn()?;
+        let failed_attempts = NyxDb::take_failed_unlocks().to_le_bytes();
+        let mut payload = [
+            n_password.as_slice(),
+            failed_attempts.as_slice(),
+            dbfile.as_bytes(),
+        ]
+        .concat();
+        let res = writer.write_all(&payload);
+        payload.zeroize();
+        drop(writer);
+        res?;
 
         match child.try_wait() {
             Ok(None) => unsafe {
@@ -190,14 +250,8 @@ pub fn launch(dbfile: &str, n_password: [u8; 32]) -> Result<(), Error> {
         exit(1);
     }
 
-    // Remove env vars
-    unsafe {
-        env::remove_var("NYX_LAUNCH_HASH");
-        env::remove_var("NYX_LAUNCH_DBFILE");
-    }
-
     // Checj fuse point
-    #[cfg(any(target_os="linux", feature = "fuse"))]
+    #[cfg(any(target_os = "linux", feature = "fuse"))]
     {
         super::fs_launcher::check_mount_successful();
     }
@@ -207,6 +261,14 @@ pub fn launch(dbfile: &str, n_password: [u8; 32]) -> Result<(), Error> {
 
 /// Ping, see if RPC daemon is online
 pub fn ping() -> bool {
+    #[cfg(unix)]
+    if super::transport::connect_unix().is_some() {
+        return true;
+    }
+    if !CONFIG.rpc_transport.has_tcp() {
+        return false;
+    }
+
     let addr = format!("{}:{}", CONFIG.host, CONFIG.port);
     let socket_addr: SocketAddr = match addr.parse() {
         Ok(addr) => addr,
@@ -218,30 +280,63 @@ pub fn ping() -> bool {
 
 /// Start daemon
 pub fn start_daemon() -> Result<(), Error> {
-    // Get environment variables
-    let mut hashed_password = env::var("NYX_LAUNCH_HASH")
-        .map_err(|e| Error::Generic(format!("Environment variable error: {}", e)))?;
-    let dbfile = env::var("NYX_LAUNCH_DBFILE")
-        .map_err(|e| Error::Generic(format!("Environment variable error: {}", e)))?;
-
-    // Decode base64
-    let tmp_password = general_purpose::STANDARD
-        .decode(&hashed_password)
-        .map_err(|e| Error::Generic(format!("Base64 decode error: {}", e)))?;
-    let n_password: [u8; 32] = tmp_password.try_into().unwrap();
+    // Keep secrets out of core dumps
+    if let Err(e) = memory::disable_core_dumps() {
+        cli_warn!("Unable to disable core dumps, continuing.  Error: {}", e);
+    }
+
+    // Read key, wrong passwords entered and database file from launcher
+    let mut payload = read_launch_pipe()?;
+    if payload.len() <= 36 {
+        payload.zeroize();
+        return Err(Error::Generic(
+            "Invalid launch parameters received.".to_string(),
+        ));
+    }
+    let mut n_password = [0u8; 32];
+    n_password.copy_from_slice(&payload[..32]);
+    let failed_attempts = u32::from_le_bytes([payload[32], payload[33], payload[34], payload[35]]);
+    let dbfile = String::from_utf8_lossy(&payload[36..]).to_string();
+    payload.zeroize();
+
+    // Launching process, which unlocked the database
+    #[cfg(unix)]
+    let client = format!(
+        "nyx/{} via {}",
+        env!("CARGO_PKG_VERSION"),
+        super::daemon::unix_peer(Some(std::os::unix::process::parent_id() as i32))
+    );
+    #[cfg(not(unix))]
+    let client = format!("nyx/{}", env!("CARGO_PKG_VERSION"));
+
+    // Keep unlocked database out of swap
+    if let Err(e) = memory::lock() {
+        cli_warn!("Unable to lock memory, continuing.  Error: {}", e);
+    }
 
     // Load database
     let db = NyxDb::load(&dbfile, n_password)?;
+    let lock_failures = memory::take_lock_failures();
+    if lock_failures > 0 {
+        cli_warn!(
+            "Memory lock limit reached, {} allocations were not locked and parts of the database may be swapped to disk.",
+            lock_failures
+        );
+    }
 
-    // Zero out variables
-    hashed_password.zeroize();
+    // Issue session token
+    let token = super::token::create()?;
 
     // Start runtime
     let rt = Runtime::new()?;
 
     // Start daemon
     rt.block_on(async {
-        let daemon = Arc::new(RpcDaemon::new(db, &dbfile, n_password));
+        let alias = CONFIG.db_alias.as_deref().unwrap_or(DEFAULT_ALIAS);
+        let daemon = Arc::new(RpcDaemon::new(alias, db, &dbfile, n_password, token));
+        for open_db in daemon.databases() {
+            open_db.log_unlock(&client, failed_attempts);
+        }
         if let Err(e) = daemon.start().await {
             cli_error!("Unable to start RPC daemon: {}", e);
         }
@@ -249,3 +344,21 @@ pub fn start_daemon() -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Read launch parameters once from pipe on stdin, then close it
+fn read_launch_pipe() -> Result<Vec<u8>, Error> {
+    // Allocate up front, so no partial copies are left behind by reallocation
+    let mut payload = Vec::with_capacity(4096);
+    io::stdin().read_to_end(&mut payload)?;
+
+    // Replace pipe with /dev/null
+    #[cfg(unix)]
+    {
+        let devnull = File::open("/dev/null")?;
+        unsafe {
+            libc::dup2(devnull.as_raw_fd(), libc::STDIN_FILENO);
+        }
+    }
+
+    Ok(payload)
+}
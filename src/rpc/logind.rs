@@ -0,0 +1,245 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(target_os = "linux")]
+use super::RpcDaemon;
+#[cfg(target_os = "linux")]
+use falcon_cli::*;
+#[cfg(target_os = "linux")]
+use futures_util::StreamExt;
+#[cfg(target_os = "linux")]
+use std::sync::Arc;
+#[cfg(target_os = "linux")]
+use zbus::zvariant::OwnedObjectPath;
+#[cfg(target_os = "linux")]
+use zbus::{Connection, MatchRule, MessageStream, message::Type};
+
+#[cfg(target_os = "linux")]
+const LOGIN1_NAME: &str = "org.freedesktop.login1";
+#[cfg(target_os = "linux")]
+const LOGIN1_PATH: &str = "/org/freedesktop/login1";
+#[cfg(target_os = "linux")]
+const LOGIN1_MANAGER: &str = "org.freedesktop.login1.Manager";
+#[cfg(target_os = "linux")]
+const LOGIN1_SESSION: &str = "org.freedesktop.login1.Session";
+
+/// Desktop session events a database locks on
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct LockTriggers {
+    pub sleep: bool,
+    pub lock: bool,
+    pub logout: bool,
+}
+
+/// Single desktop session event
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LockTrigger {
+    Sleep,
+    Lock,
+    Logout,
+}
+
+impl LockTriggers {
+    /// Lock on no events
+    pub fn none() -> Self {
+        Self {
+            sleep: false,
+            lock: false,
+            logout: false,
+        }
+    }
+
+    /// Whether database locks on event
+    pub fn contains(&self, trigger: LockTrigger) -> bool {
+        match trigger {
+            LockTrigger::Sleep => self.sleep,
+            LockTrigger::Lock => self.lock,
+            LockTrigger::Logout => self.logout,
+        }
+    }
+}
+
+impl Default for LockTriggers {
+    fn default() -> Self {
+        Self {
+            sleep: true,
+            lock: true,
+            logout: true,
+        }
+    }
+}
+
+impl FromStr for LockTriggers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "all" => return Ok(Self::default()),
+            "none" => return Ok(Self::none()),
+            _ => {}
+        };
+
+        let mut triggers = Self::none();
+        for name in s.split(',') {
+            match name.trim().to_lowercase().as_str() {
+                "sleep" => triggers.sleep = true,
+                "lock" => triggers.lock = true,
+                "logout" => triggers.logout = true,
+                _ => {
+                    return Err(Error::Validate(format!(
+                        "Invalid lock event '{}', supported events are: sleep, lock, logout, all, none",
+                        name
+                    )));
+                }
+            };
+        }
+
+        Ok(triggers)
+    }
+}
+
+impl fmt::Display for LockTriggers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<&str> = [
+            (self.sleep, "sleep"),
+            (self.lock, "lock"),
+            (self.logout, "logout"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, name)| enabled.then_some(name))
+        .collect();
+
+        if names.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", names.join(","))
+        }
+    }
+}
+
+impl LockTrigger {
+    /// Reason given in lock events
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::Sleep => "sleep",
+            Self::Lock => "screen_lock",
+            Self::Logout => "logout",
+        }
+    }
+}
+
+/// Listen for logind sleep, lock and logout signals on the system bus
+#[cfg(target_os = "linux")]
+pub async fn listen(daemon: Arc<RpcDaemon>) -> Result<(), Error> {
+    let conn = Connection::system().await.map_err(dbus_error)?;
+
+    // Session of this process, otherwise lock signals of any session are followed
+    let session = own_session(&conn).await;
+
+    // Only signals sent by logind itself, not by other clients on the bus
+    let sleep = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender(LOGIN1_NAME)
+        .and_then(|rule| rule.interface(LOGIN1_MANAGER))
+        .and_then(|rule| rule.member("PrepareForSleep"))
+        .map_err(dbus_error)?
+        .build();
+
+    let lock = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender(LOGIN1_NAME)
+        .and_then(|rule| rule.interface(LOGIN1_SESSION));
+    let lock = match &session {
+        Some(path) => lock.and_then(|rule| rule.path(path.as_str())),
+        None => lock.and_then(|rule| rule.path_namespace(format!("{}/session", LOGIN1_PATH))),
+    }
+    .and_then(|rule| rule.member("Lock"))
+    .map_err(dbus_error)?
+    .build();
+
+    let logout = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender(LOGIN1_NAME)
+        .and_then(|rule| rule.interface(LOGIN1_MANAGER))
+        .and_then(|rule| rule.member("SessionRemoved"))
+        .map_err(dbus_error)?
+        .build();
+
+    let mut sleep = MessageStream::for_match_rule(sleep, &conn, None).await.map_err(dbus_error)?;
+    let mut lock = MessageStream::for_match_rule(lock, &conn, None).await.map_err(dbus_error)?;
+    let mut logout =
+        MessageStream::for_match_rule(logout, &conn, None).await.map_err(dbus_error)?;
+
+    loop {
+        let trigger = tokio::select! {
+            Some(Ok(msg)) = sleep.next() => {
+                // Sent with true before suspending, and false on resume
+                match msg.body().deserialize::<bool>() {
+                    Ok(true) => LockTrigger::Sleep,
+                    _ => continue,
+                }
+            },
+            Some(Ok(_)) = lock.next() => LockTrigger::Lock,
+            Some(Ok(msg)) = logout.next() => {
+                match (msg.body().deserialize::<(String, OwnedObjectPath)>(), &session) {
+                    (Ok((_, path)), Some(session)) if path == *session => LockTrigger::Logout,
+                    _ => continue,
+                }
+            },
+            else => return Ok(()),
+        };
+
+        daemon.lock_on(trigger);
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl RpcDaemon {
+    /// Lock all databases configured to lock on event
+    pub(super) fn lock_on(&self, trigger: LockTrigger) {
+        for open_db in self.databases() {
+            let enabled = open_db
+                .session
+                .lock()
+                .map(|session| session.lock_on.contains(trigger))
+                .unwrap_or(true);
+
+            if enabled {
+                cli_info!(
+                    "Database '{}' locked due to {}.",
+                    open_db.alias,
+                    trigger.reason()
+                );
+                let _ = self.lock_db(&open_db.alias, trigger.reason());
+            }
+        }
+    }
+}
+
+/// Get logind session of this process
+#[cfg(target_os = "linux")]
+async fn own_session(conn: &Connection) -> Option<OwnedObjectPath> {
+    let reply = conn
+        .call_method(
+            Some(LOGIN1_NAME),
+            LOGIN1_PATH,
+            Some(LOGIN1_MANAGER),
+            "GetSessionByPID",
+            &(std::process::id()),
+        )
+        .await
+        .ok()?;
+    reply.body().deserialize::<OwnedObjectPath>().ok()
+}
+
+#[cfg(target_os = "linux")]
+fn dbus_error(e: zbus::Error) -> Error {
+    Error::Generic(format!("D-Bus error: {}", e))
+}
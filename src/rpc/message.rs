@@ -4,84 +4,211 @@
 // Apache License text: https://www.apache.org/licenses/LICENSE-2.0
 // MIT License text: https://opensource.org/licenses/MIT
 
+use crate::Error;
 use atlas_http::HttpResponse;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value, json};
+
+/// Protocol version of all requests and responses
+pub const JSONRPC_VERSION: &str = "2.0";
+
+// Standard JSON-RPC 2.0 error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+// Server defined error codes
+pub const SERVER_ERROR: i64 = -32000;
+pub const UNAUTHORIZED: i64 = -32001;
+pub const DB_NOT_OPEN: i64 = -32002;
+
+/// Request id, either a string or number, or null
+pub type RpcId = Value;
 
 #[derive(Serialize, Deserialize)]
 pub struct RpcRequest {
-    pub id: usize,
+    pub jsonrpc: String,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub id: Option<RpcId>,
     pub method: String,
-    pub params: Vec<String>,
+    #[serde(default)]
+    pub params: RpcParams,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db: Option<String>,
+    #[serde(skip)]
+    pub client: String,
+    #[serde(skip)]
+    pub is_secure: bool,
+}
+
+/// Parameters given by position or by name
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RpcParams {
+    Positional(Vec<Value>),
+    Named(Map<String, Value>),
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct RpcResponse<T> {
-    pub id: usize,
-    pub status: String,
+    pub jsonrpc: String,
+    pub id: RpcId,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<RpcError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<T>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct RpcError {
-    pub code: usize,
+    pub code: i64,
     pub message: String,
 }
 
-// Generate ok response
-pub fn ok<T: Serialize>(id: usize, result: T) -> HttpResponse {
-    // set response
-    let response = RpcResponse {
-        id,
-        status: "ok".to_string(),
-        error: None,
-        result: Some(result),
-    };
-    let json: String = serde_json::to_string_pretty(&response).unwrap();
+impl RpcRequest {
+    /// Check whether request is a notification, which receives no response
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
 
-    HttpResponse::new(
-        &200,
-        &vec!["Content-type: application/json".to_string()],
-        &json,
-    )
+    /// Get id to respond with
+    pub fn id(&self) -> RpcId {
+        self.id.clone().unwrap_or(Value::Null)
+    }
 }
+
+impl RpcParams {
+    /// Flatten into positional strings in order of given names, with structured values as JSON
+    pub fn to_strings(&self, names: &[&str]) -> Result<Vec<String>, Error> {
+        let values: Vec<&Value> = match self {
+            Self::Positional(values) => {
+                if values.len() > names.len() {
+                    return Err(Error::Validate(format!(
+                        "Expected at most {} parameters, received {}",
+                        names.len(),
+                        values.len()
+                    )));
+                }
+                values.iter().collect()
+            }
+            Self::Named(map) => {
+                if let Some(key) = map.keys().find(|key| !names.contains(&key.as_str())) {
+                    return Err(Error::Validate(format!("Unknown parameter, {}", key)));
+                }
+
+                // Optional parameters may only be left off the end
+                let values: Vec<&Value> = names.iter().map_while(|name| map.get(*name)).collect();
+                if values.len() < map.len() {
+                    return Err(Error::Validate(format!(
+                        "Missing parameter, {}",
+                        names[values.len()]
+                    )));
+                }
+                values
+            }
+        };
+
+        Ok(values
+            .into_iter()
+            .map(|value| match value {
+                Value::String(s) => s.to_string(),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            })
+            .collect())
+    }
+}
+
+impl Default for RpcParams {
+    fn default() -> Self {
+        Self::Positional(vec![])
+    }
+}
+
+/// Keep explicit null id apart from a missing one
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<RpcId>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+// Generate ok response
+pub fn ok<T: Serialize>(id: &RpcId, result: T) -> Value {
+    json!({
+        "jsonrpc": JSONRPC_VERSION,
+        "id": id,
+        "result": result,
+    })
+}
+
 /// Give error response
-pub fn err(id: usize, code: usize, message: &str) -> HttpResponse {
-    // set response
-    let response: RpcResponse<()> = RpcResponse {
-        id,
-        status: "error".to_string(),
-        error: Some(RpcError {
-            code,
-            message: message.to_string(),
-        }),
-        result: None,
+pub fn err(id: &RpcId, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": JSONRPC_VERSION,
+        "id": id,
+        "error": {
+            "code": code,
+            "message": message,
+        },
+    })
+}
+
+/// Give error response for error returned by a method
+pub fn from_error(id: &RpcId, error: &Error) -> Value {
+    let code = match error {
+        Error::Json(_) => INVALID_PARAMS,
+        Error::Validate(_) => SERVER_ERROR,
+        _ => INTERNAL_ERROR,
     };
-    let json: String = serde_json::to_string(&response).unwrap();
+    err(id, code, &error.to_string())
+}
+
+/// Give HTTP response of JSON-RPC response, or no content for notifications
+pub fn http(response: Option<Value>) -> HttpResponse {
+    match response {
+        Some(response) => HttpResponse::new(
+            &200,
+            &vec!["Content-type: application/json".to_string()],
+            &response.to_string(),
+        ),
+        None => HttpResponse::new(&204, &vec![], &String::new()),
+    }
+}
 
+/// Give unauthorized response
+pub fn unauthorized() -> HttpResponse {
+    let response = err(
+        &Value::Null,
+        UNAUTHORIZED,
+        "Unauthorized, missing or invalid RPC token",
+    );
     HttpResponse::new(
-        &500,
+        &401,
         &vec!["Content-type: application/json".to_string()],
-        &json,
+        &response.to_string(),
     )
 }
 
 pub struct CmdResponse {
-    pub http_res: HttpResponse,
+    pub response: Value,
     pub is_modified: bool,
     pub is_copy: bool,
 }
 
 impl CmdResponse {
-    pub fn new(is_modified: bool, is_copy: bool, http_res: HttpResponse) -> Self {
+    pub fn new(is_modified: bool, is_copy: bool, response: Value) -> Self {
         Self {
-            http_res,
+            response,
             is_modified,
             is_copy,
         }
     }
 
-    pub fn none(http_res: HttpResponse) -> Self {
-        Self::new(false, false, http_res)
+    pub fn none(response: Value) -> Self {
+        Self::new(false, false, response)
     }
 }
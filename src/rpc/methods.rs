@@ -0,0 +1,548 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::databases::OpenDb;
+use super::{CmdResponse, OpenDbInfo, RpcDaemon, RpcId, RpcRequest, message};
+use crate::Error;
+use crate::database::{
+    BaseDbFunctions, DbStats, EntryTags, ExportDump, HistoryAction, HistoryDataType, HistoryHead,
+    HistoryItem, HistoryRetention, HistoryVerify, ImportBundle, ImportSummary, Note, NyxDb, Oauth,
+    OtpCode, SshKey, StrItem, User, VersionInfo,
+};
+use lazy_static::lazy_static;
+use schemars::generate::SchemaSettings;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+/// Version of the OpenRPC specification the discovery document follows
+const OPENRPC_VERSION: &str = "1.3.2";
+
+lazy_static! {
+    /// All methods served by the daemon, by name
+    static ref METHODS: BTreeMap<&'static str, RpcMethod> =
+        registry().into_iter().map(|method| (method.name, method)).collect();
+}
+
+/// Generate JSON schema of a parameter or result
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// Method run against the daemon itself, before any database is selected
+type DaemonFn = fn(&RpcDaemon, &RpcId, &RpcRequest, &[String]) -> Result<CmdResponse, Error>;
+
+/// Method run against the selected database, while locked
+type DbFn = fn(&RpcDaemon, &RpcId, &OpenDb, &mut NyxDb, &Vec<String>) -> Result<CmdResponse, Error>;
+
+/// Method handler
+pub enum Handler {
+    Daemon(DaemonFn),
+    Db(DbFn),
+}
+
+pub struct RpcMethod {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub params: Vec<RpcParam>,
+    pub result: SchemaFn,
+    pub handler: Handler,
+}
+
+pub struct RpcParam {
+    pub name: &'static str,
+    pub required: bool,
+    pub schema: SchemaFn,
+}
+
+/// Get method by name
+pub fn get(name: &str) -> Option<&'static RpcMethod> {
+    METHODS.get(name)
+}
+
+/// Generate OpenRPC document describing every method
+pub fn discover() -> Value {
+    let mut generator = SchemaSettings::draft07()
+        .with(|s| {
+            s.definitions_path = "/components/schemas".into();
+            s.meta_schema = None;
+        })
+        .into_generator();
+
+    let methods: Vec<Value> = METHODS
+        .values()
+        .map(|method| {
+            let params: Vec<Value> = method
+                .params
+                .iter()
+                .map(|param| {
+                    json!({
+                        "name": param.name,
+                        "required": param.required,
+                        "schema": (param.schema)(&mut generator),
+                    })
+                })
+                .collect();
+
+            json!({
+                "name": method.name,
+                "summary": method.summary,
+                "paramStructure": "either",
+                "params": params,
+                "result": {
+                    "name": "result",
+                    "schema": (method.result)(&mut generator),
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "Nyx RPC",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "methods": methods,
+        "components": {
+            "schemas": generator.take_definitions(true),
+        },
+    })
+}
+
+/// Register methods shared by all data types
+macro_rules! entry_methods {
+    ($methods:ident, $prefix:literal, $label:literal, $store:ident, $item:ty, $kind:ident, $copy:ident, $delete:ident, $rename:ident) => {
+        $methods.extend([
+            RpcMethod {
+                name: concat!($prefix, ".copy"),
+                summary: concat!("Copy ", $label, " to new name"),
+                params: vec![
+                    required("name", schema::<String>),
+                    required("dest", schema::<String>),
+                ],
+                result: schema::<bool>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    let res = db.$store.$copy(id, params)?;
+                    db.tags.copy(HistoryDataType::$kind, &params[0], &params[1]);
+                    Ok(res)
+                }),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".delete"),
+                summary: concat!("Delete ", $label),
+                params: vec![required("name", schema::<String>)],
+                result: schema::<bool>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    let res = db.$store.$delete(id, params)?;
+                    db.versions.remove(HistoryDataType::$kind, &params[0]);
+                    db.tags.remove(HistoryDataType::$kind, &params[0]);
+                    Ok(res)
+                }),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".exists"),
+                summary: concat!("Check whether ", $label, " exists"),
+                params: vec![required("name", schema::<String>)],
+                result: schema::<bool>,
+                handler: Handler::Db(|_, id, _, db, params| db.$store.exists(id, params)),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".find"),
+                summary: concat!(
+                    "Find names of each ",
+                    $label,
+                    " matching search term and optional tag, favorites first"
+                ),
+                params: vec![
+                    required("search", schema::<String>),
+                    optional("tag", schema::<String>),
+                ],
+                result: schema::<Vec<String>>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    db.tags.find_items(HistoryDataType::$kind, &db.$store, id, params)
+                }),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".get"),
+                summary: concat!("Get ", $label, ", starting clipboard timer if copy is true"),
+                params: vec![
+                    required("name", schema::<String>),
+                    optional("copy", schema::<bool>),
+                ],
+                result: schema::<$item>,
+                handler: Handler::Db(|_, id, _, db, params| db.$store.get_item(id, params)),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".list"),
+                summary: concat!(
+                    "List 25 names of each ",
+                    $label,
+                    " within directory, favorites first, or full names of those with tag"
+                ),
+                params: vec![
+                    required("dir", schema::<String>),
+                    optional("start", schema::<usize>),
+                    optional("tag", schema::<String>),
+                ],
+                result: schema::<Vec<String>>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    db.tags.list_items(HistoryDataType::$kind, &db.$store, id, params)
+                }),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".rename"),
+                summary: concat!("Rename ", $label),
+                params: vec![
+                    required("name", schema::<String>),
+                    required("dest", schema::<String>),
+                ],
+                result: schema::<bool>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    let res = db.$store.$rename(id, params)?;
+                    db.versions.rename(HistoryDataType::$kind, &params[0], &params[1]);
+                    db.tags.rename(HistoryDataType::$kind, &params[0], &params[1]);
+                    Ok(res)
+                }),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".history"),
+                summary: concat!("List prior versions of ", $label, ", newest first"),
+                params: vec![required("name", schema::<String>)],
+                result: schema::<Vec<VersionInfo>>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    db.versions.list_items(HistoryDataType::$kind, &db.$store, id, params)
+                }),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".revert"),
+                summary: concat!(
+                    "Restore prior version of ",
+                    $label,
+                    ", numbered as in history"
+                ),
+                params: vec![
+                    required("name", schema::<String>),
+                    required("version", schema::<usize>),
+                ],
+                result: schema::<bool>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    db.versions.revert_item(HistoryDataType::$kind, &mut db.$store, id, params)
+                }),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".tags"),
+                summary: concat!("Get tags of ", $label, " and whether it is a favorite"),
+                params: vec![required("name", schema::<String>)],
+                result: schema::<EntryTags>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    db.tags.get_item(HistoryDataType::$kind, &db.$store, id, params)
+                }),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".tag"),
+                summary: concat!("Add and remove comma separated tags of ", $label),
+                params: vec![
+                    required("name", schema::<String>),
+                    required("add", schema::<String>),
+                    optional("remove", schema::<String>),
+                ],
+                result: schema::<EntryTags>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    db.tags.tag_item(HistoryDataType::$kind, &db.$store, id, params)
+                }),
+            },
+            RpcMethod {
+                name: concat!($prefix, ".favorite"),
+                summary: concat!("Mark ", $label, " as favorite, or unmark it if false"),
+                params: vec![
+                    required("name", schema::<String>),
+                    optional("favorite", schema::<bool>),
+                ],
+                result: schema::<EntryTags>,
+                handler: Handler::Db(|_, id, _, db, params| {
+                    db.tags.favorite_item(HistoryDataType::$kind, &db.$store, id, params)
+                }),
+            },
+        ]);
+    };
+}
+
+/// Register method to add new item
+macro_rules! new_method {
+    ($methods:ident, $prefix:literal, $label:literal, $store:ident, $item:ty) => {
+        $methods.push(RpcMethod {
+            name: concat!($prefix, ".new"),
+            summary: concat!("Add ", $label),
+            params: vec![
+                required("name", schema::<String>),
+                required("item", schema::<$item>),
+            ],
+            result: schema::<bool>,
+            handler: Handler::Db(|_, id, _, db, params| db.$store.add_item(id, params)),
+        });
+    };
+}
+
+/// Register method to replace existing item, keeping its previous version
+macro_rules! edit_method {
+    ($methods:ident, $prefix:literal, $label:literal, $store:ident, $item:ty, $kind:ident) => {
+        $methods.push(RpcMethod {
+            name: concat!($prefix, ".edit"),
+            summary: concat!("Replace ", $label),
+            params: vec![
+                required("name", schema::<String>),
+                required("item", schema::<$item>),
+            ],
+            result: schema::<bool>,
+            handler: Handler::Db(|_, id, _, db, params| {
+                db.versions.edit_item(HistoryDataType::$kind, &mut db.$store, id, params)
+            }),
+        });
+    };
+}
+
+/// Build list of all methods
+fn registry() -> Vec<RpcMethod> {
+    let mut methods = vec![
+        // Daemon
+        RpcMethod {
+            name: "rpc.discover",
+            summary: "Get OpenRPC document describing all methods",
+            params: vec![],
+            result: schema::<Value>,
+            handler: Handler::Daemon(|_, id, _, _| {
+                Ok(CmdResponse::none(message::ok(id, discover())))
+            }),
+        },
+        RpcMethod {
+            name: "db.open",
+            summary: "Unlock database under alias, alongside those already open",
+            params: vec![
+                required("alias", schema::<String>),
+                required("dbfile", schema::<String>),
+                required("key", schema::<String>),
+                optional("timeout", schema::<String>),
+                optional("lock_on", schema::<String>),
+                optional("failed_attempts", schema::<u32>),
+            ],
+            result: schema::<bool>,
+            handler: Handler::Daemon(|daemon, id, req, params| daemon.open_db(id, req, params)),
+        },
+        RpcMethod {
+            name: "db.close",
+            summary: "Close database selected by db member, or all databases and the daemon",
+            params: vec![],
+            result: schema::<bool>,
+            handler: Handler::Daemon(|daemon, id, req, _| daemon.close_db(id, &req.db)),
+        },
+        RpcMethod {
+            name: "db.list",
+            summary: "List open databases",
+            params: vec![],
+            result: schema::<Vec<OpenDbInfo>>,
+            handler: Handler::Daemon(|daemon, id, _, _| daemon.list_dbs(id)),
+        },
+        // Database
+        RpcMethod {
+            name: "db.export",
+            summary: "Get plaintext dump of the entire database",
+            params: vec![],
+            result: schema::<ExportDump>,
+            handler: Handler::Db(|_, id, _, db, params| db.export(id, params)),
+        },
+        RpcMethod {
+            name: "db.history",
+            summary: "List 25 history entries matching optional filters, newest first",
+            params: vec![
+                required("start", schema::<usize>),
+                optional("data_type", schema::<HistoryDataType>),
+                optional("action", schema::<HistoryAction>),
+                optional("prefix", schema::<String>),
+                optional("since", schema::<u64>),
+                optional("until", schema::<u64>),
+            ],
+            result: schema::<Vec<HistoryItem>>,
+            handler: Handler::Db(|_, id, _, db, params| db.history.list_items(id, params)),
+        },
+        RpcMethod {
+            name: "db.history_head",
+            summary: "Get sequence number and hash of the newest history entry, for third parties to attest to",
+            params: vec![],
+            result: schema::<HistoryHead>,
+            handler: Handler::Db(|_, id, _, db, _| {
+                Ok(CmdResponse::none(message::ok(id, db.history.head())))
+            }),
+        },
+        RpcMethod {
+            name: "db.history_verify",
+            summary: "Verify history hash chain for missing or altered entries, and optionally that an attested head is still part of it",
+            params: vec![
+                optional("hash", schema::<String>),
+                optional("seq", schema::<u64>),
+            ],
+            result: schema::<HistoryVerify>,
+            handler: Handler::Db(|_, id, _, db, params| db.history.verify(id, params)),
+        },
+        RpcMethod {
+            name: "db.history_retention",
+            summary: "Set maximum number and age in days of history entries, or get them if none given",
+            params: vec![
+                optional("max_items", schema::<u32>),
+                optional("max_days", schema::<u32>),
+            ],
+            result: schema::<HistoryRetention>,
+            handler: Handler::Db(|_, id, _, db, params| db.history.retention(id, params)),
+        },
+        RpcMethod {
+            name: "db.retention",
+            summary: "Set number of prior versions kept of each entry, or get it if none given",
+            params: vec![optional("count", schema::<u32>)],
+            result: schema::<u32>,
+            handler: Handler::Db(|_, id, _, db, params| db.versions.retention(id, params)),
+        },
+        RpcMethod {
+            name: "db.import",
+            summary: "Import bundle of entries, skipping any that already exist",
+            params: vec![
+                required("bundle", schema::<ImportBundle>),
+                optional("mode", dry_run),
+            ],
+            result: schema::<ImportSummary>,
+            handler: Handler::Db(|_, id, _, db, params| db.import(id, params)),
+        },
+        RpcMethod {
+            name: "db.stats",
+            summary: "Get number of entries within each data store, and of entries with each tag",
+            params: vec![],
+            result: schema::<DbStats>,
+            handler: Handler::Db(|daemon, id, open_db, db, _| daemon.dbstats(id, open_db, db)),
+        },
+        // Type specific
+        RpcMethod {
+            name: "otp.generate",
+            summary: "Generate current OTP code",
+            params: vec![required("name", schema::<String>)],
+            result: schema::<OtpCode>,
+            handler: Handler::Db(|_, id, _, db, params| db.oauth.generate(id, params)),
+        },
+        RpcMethod {
+            name: "ssh.generate",
+            summary: "Reserved, SSH keys are generated by the client and added with ssh.import",
+            params: vec![],
+            result: schema::<bool>,
+            handler: Handler::Db(|_, id, _, db, params| db.ssh_keys.generate(id, params)),
+        },
+        RpcMethod {
+            name: "ssh.import",
+            summary: "Add SSH key",
+            params: vec![
+                required("name", schema::<String>),
+                required("item", schema::<SshKey>),
+            ],
+            result: schema::<bool>,
+            handler: Handler::Db(|_, id, _, db, params| db.ssh_keys.import(id, params)),
+        },
+        RpcMethod {
+            name: "str.set",
+            summary: "Add string",
+            params: vec![
+                required("name", schema::<String>),
+                required("item", schema::<StrItem>),
+            ],
+            result: schema::<bool>,
+            handler: Handler::Db(|_, id, _, db, params| db.strings.add_item(id, params)),
+        },
+    ];
+
+    entry_methods!(
+        methods,
+        "user",
+        "user",
+        users,
+        User,
+        User,
+        copy_item,
+        delete_item,
+        rename_item
+    );
+    entry_methods!(
+        methods,
+        "otp",
+        "OTP",
+        oauth,
+        Oauth,
+        Otp,
+        copy_item,
+        delete_item,
+        rename_item
+    );
+    entry_methods!(
+        methods, "ssh", "SSH key", ssh_keys, SshKey, SshKey, copy_key, delete_key, rename_key
+    );
+    entry_methods!(
+        methods,
+        "str",
+        "string",
+        strings,
+        StrItem,
+        StrItem,
+        copy_item,
+        delete_item,
+        rename_item
+    );
+    entry_methods!(
+        methods,
+        "note",
+        "note",
+        notes,
+        Note,
+        Note,
+        copy_item,
+        delete_item,
+        rename_item
+    );
+
+    new_method!(methods, "user", "user", users, User);
+    new_method!(methods, "otp", "OTP", oauth, Oauth);
+    new_method!(methods, "note", "note", notes, Note);
+    edit_method!(methods, "user", "user", users, User, User);
+    edit_method!(methods, "otp", "OTP", oauth, Oauth, Otp);
+    edit_method!(methods, "ssh", "SSH key", ssh_keys, SshKey, SshKey);
+    edit_method!(methods, "str", "string", strings, StrItem, StrItem);
+    edit_method!(methods, "note", "note", notes, Note, Note);
+
+    methods
+}
+
+/// Required parameter
+fn required(name: &'static str, schema: SchemaFn) -> RpcParam {
+    RpcParam {
+        name,
+        required: true,
+        schema,
+    }
+}
+
+/// Optional parameter
+fn optional(name: &'static str, schema: SchemaFn) -> RpcParam {
+    RpcParam {
+        name,
+        required: false,
+        schema,
+    }
+}
+
+/// Schema of type
+fn schema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    generator.subschema_for::<T>()
+}
+
+/// Schema of import mode
+fn dry_run(_generator: &mut SchemaGenerator) -> Schema {
+    schemars::json_schema!({
+        "type": "string",
+        "enum": ["", "dry-run"],
+    })
+}
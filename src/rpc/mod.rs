@@ -5,7 +5,9 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 pub use self::daemon::RpcDaemon;
-pub use self::message::{CmdResponse, RpcRequest, RpcResponse};
+pub use self::databases::{DEFAULT_ALIAS, OpenDbInfo};
+pub use self::events::RpcEvent;
+pub use self::message::{CmdResponse, RpcId, RpcParams, RpcRequest, RpcResponse};
 use crate::database::loader;
 use crate::{CONFIG, Error};
 use atlas_http::{HttpBody, HttpClient, HttpRequest};
@@ -13,63 +15,72 @@ use falcon_cli::*;
 use rand::Rng;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use std::fmt::Display;
+use serde_json::Value;
 use std::process::exit;
 
 mod daemon;
+mod databases;
+pub mod events;
 pub mod launcher;
+pub mod logind;
 pub mod message;
+mod methods;
+pub mod tls;
+pub mod token;
+pub mod transport;
 
-#[cfg(any(target_os="linux", feature = "fuse"))]
+#[cfg(any(target_os = "linux", feature = "fuse"))]
 pub mod fs_launcher;
+#[cfg(unix)]
+pub mod ssh_agent;
 
 /// Send request
 pub fn send<T, R>(method: &str, params: &Vec<T>) -> Result<R, Error>
 where
-    T: Serialize + Display + Send + Sync,
+    T: Serialize + Send + Sync,
     R: DeserializeOwned + 'static,
 {
     let mut rng = rand::thread_rng();
 
-    // Ping, and check if RPC server online
+    // Connect to Unix socket, or launch daemon if not online over any transport
+    #[cfg(unix)]
+    let mut stream = transport::connect_unix();
+    #[cfg(unix)]
+    if stream.is_none() && !launcher::ping() {
+        launch_daemon();
+        stream = transport::connect_unix();
+    }
+    #[cfg(not(unix))]
     if !launcher::ping() {
-        let (dbfile, n_password) = match loader::load() {
-            Ok(r) => r,
-            Err(e) => {
-                cli_error!("Unable to load Nyx database: {}", e);
-                exit(1);
-            }
-        };
-
-        if let Err(e) = launcher::launch(&dbfile, n_password) {
-            cli_error!("Unable to launch RPC daemon: {}", e);
-            exit(1);
-        }
+        launch_daemon();
     }
 
     // Create json request
     let req = RpcRequest {
-        id: rng.gen_range(100000..1000000),
+        jsonrpc: message::JSONRPC_VERSION.to_string(),
+        id: Some(Value::from(rng.gen_range(100000..1000000))),
         method: method.to_string(),
-        params: params.iter().map(|p| p.to_string()).collect(),
+        params: RpcParams::Positional(
+            params.iter().map(serde_json::to_value).collect::<Result<Vec<Value>, _>>()?,
+        ),
+        db: CONFIG.db_alias.clone(),
+        client: String::new(),
+        is_secure: false,
     };
     let json_str = serde_json::to_string(&req).unwrap();
 
-    // Create http request
-    let url = format!("http://{}:{}/", CONFIG.host, CONFIG.port);
-    let req = HttpRequest::new(
-        "POST",
-        &url,
-        &vec!["Content-type: application/json"],
-        &HttpBody::from_raw(json_str.as_bytes()),
-    );
-
-    // Send http request
-    let mut http = HttpClient::builder().build_sync();
-    let http_res = http.send(&req)?;
+    // Send over Unix socket if it accepts the connection, otherwise TCP
+    let token = token::read()?;
+    #[cfg(unix)]
+    let body = match stream {
+        Some(stream) => transport::send_unix(stream, &json_str, &token)?,
+        None => send_tcp(&json_str, &token)?,
+    };
+    #[cfg(not(unix))]
+    let body = send_tcp(&json_str, &token)?;
 
     // Decode json
-    let json_res: RpcResponse<R> = serde_json::from_str(&http_res.body())?;
+    let json_res: RpcResponse<R> = serde_json::from_str(&body)?;
 
     // Check status
     if let Some(error) = json_res.error {
@@ -83,3 +94,59 @@ where
         Err(Error::Rpc("Received an empty response.".to_string()))
     }
 }
+
+/// Load database and launch RPC daemon, exiting on failure
+fn launch_daemon() {
+    let (dbfile, n_password) = match loader::load() {
+        Ok(r) => r,
+        Err(e) => {
+            cli_error!("Unable to load Nyx database: {}", e);
+            exit(1);
+        }
+    };
+
+    if let Err(e) = launcher::launch(&dbfile, n_password) {
+        cli_error!("Unable to launch RPC daemon: {}", e);
+        exit(1);
+    }
+}
+
+/// Subscribe to events of the daemon, passing each to handler until it returns false
+pub fn subscribe<F: FnMut(RpcEvent) -> bool>(handler: F) -> Result<(), Error> {
+    let token = token::read()?;
+
+    #[cfg(unix)]
+    if let Some(stream) = transport::connect_unix() {
+        return transport::subscribe(stream, &token, handler);
+    }
+
+    if CONFIG.tls {
+        return transport::subscribe(tls::connect()?, &token, handler);
+    }
+    let stream = std::net::TcpStream::connect((CONFIG.host.as_str(), CONFIG.port))?;
+    transport::subscribe(stream, &token, handler)
+}
+
+/// Send JSON request over TCP, and return body of response
+fn send_tcp(json_str: &str, token: &str) -> Result<String, Error> {
+    if CONFIG.tls {
+        return tls::send(json_str, token);
+    }
+
+    let auth_header = format!("Authorization: Bearer {}", token);
+    let url = format!("http://{}:{}/", CONFIG.host, CONFIG.port);
+    let req = HttpRequest::new(
+        "POST",
+        &url,
+        &vec![
+            "Content-type: application/json",
+            &auth_header,
+            &transport::user_agent(),
+        ],
+        &HttpBody::from_raw(json_str.as_bytes()),
+    );
+
+    let mut http = HttpClient::builder().build_sync();
+    let http_res = http.send(&req)?;
+    Ok(http_res.body())
+}
@@ -0,0 +1,256 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::RpcDaemon;
+use crate::database::SshKey;
+use crate::{CONFIG, Error};
+use falcon_cli::*;
+use rsa::pkcs1v15::SigningKey;
+use rsa::signature::Signer;
+use rsa::{BigUint, RsaPrivateKey};
+use sha2::{Sha256, Sha512};
+use ssh_key::private::KeypairData;
+use ssh_key::{Algorithm, HashAlg, Mpint, PrivateKey, Signature};
+use std::fs;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::task;
+
+// Message numbers, see draft-miller-ssh-agent section 6.1
+const SSH_AGENT_FAILURE: u8 = 5;
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+
+// Signature flags
+const SSH_AGENT_RSA_SHA2_256: u32 = 0x02;
+const SSH_AGENT_RSA_SHA2_512: u32 = 0x04;
+
+// Largest message we are willing to read from a client
+const MAX_MESSAGE_LEN: usize = 256 * 1024;
+
+/// Listen for SSH agent connections
+pub async fn listen(daemon: Arc<RpcDaemon>) -> Result<(), Error> {
+    let sock_path = Path::new(&CONFIG.ssh_agent_sock);
+
+    // Ensure parent directory exists, and is private
+    if let Some(parent) = sock_path.parent()
+        && !parent.exists()
+    {
+        fs::DirBuilder::new().recursive(true).mode(0o700).create(parent)?;
+    }
+
+    // Remove stale socket from previous session
+    if sock_path.exists() {
+        fs::remove_file(sock_path)?;
+    }
+
+    // Bind
+    let listener = UnixListener::bind(sock_path)?;
+    fs::set_permissions(sock_path, fs::Permissions::from_mode(0o600))?;
+    cli_info!("SSH agent listening on {}...", CONFIG.ssh_agent_sock);
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+
+        let handler_daemon = Arc::clone(&daemon);
+        task::spawn(async move {
+            let _ = handle_connection(handler_daemon, stream).await;
+        });
+    }
+}
+
+/// Remove the agent socket
+pub fn cleanup() {
+    let _ = fs::remove_file(&CONFIG.ssh_agent_sock);
+}
+
+/// Handle a single client connection, which may send multiple requests
+async fn handle_connection(daemon: Arc<RpcDaemon>, mut stream: UnixStream) -> Result<(), Error> {
+    loop {
+        // Get message length
+        let mut len_bytes = [0u8; 4];
+        if stream.read_exact(&mut len_bytes).await.is_err() {
+            return Ok(());
+        }
+        let len = u32::from_be_bytes(len_bytes) as usize;
+        if len == 0 || len > MAX_MESSAGE_LEN {
+            return Err(Error::Rpc(format!(
+                "Invalid SSH agent message length, {}",
+                len
+            )));
+        }
+
+        // Read message
+        let mut message = vec![0u8; len];
+        stream.read_exact(&mut message).await?;
+
+        // Process, and send response
+        let res = daemon.handle_agent_message(&message);
+        let mut output = (res.len() as u32).to_be_bytes().to_vec();
+        output.extend(res);
+        stream.write_all(&output).await?;
+    }
+}
+
+impl RpcDaemon {
+    /// Process a single SSH agent message, returns the response body
+    fn handle_agent_message(&self, message: &[u8]) -> Vec<u8> {
+        let mut reader = AgentReader(&message[1..]);
+
+        let res = match message[0] {
+            SSH_AGENTC_REQUEST_IDENTITIES => self.agent_identities(),
+            SSH_AGENTC_SIGN_REQUEST => self.agent_sign(&mut reader),
+            _ => None,
+        };
+
+        res.unwrap_or_else(|| vec![SSH_AGENT_FAILURE])
+    }
+
+    /// List all keys within the open databases
+    fn agent_identities(&self) -> Option<Vec<u8>> {
+        let mut keys = vec![];
+        for open_db in self.databases().iter().filter(|open_db| !open_db.is_expired()) {
+            let db = open_db.nyxdb.lock().ok()?;
+            for ssh_key in db.ssh_keys.values() {
+                if let Some(privkey) = parse_private_key(ssh_key) {
+                    keys.push((
+                        privkey.public_key().to_bytes().ok()?,
+                        ssh_key.display_name.clone(),
+                    ));
+                }
+            }
+        }
+
+        // Build response
+        let mut res = vec![SSH_AGENT_IDENTITIES_ANSWER];
+        res.extend((keys.len() as u32).to_be_bytes());
+        for (blob, comment) in keys {
+            put_string(&mut res, &blob);
+            put_string(&mut res, comment.as_bytes());
+        }
+
+        Some(res)
+    }
+
+    /// Sign data with one of the keys within the database
+    fn agent_sign(&self, reader: &mut AgentReader) -> Option<Vec<u8>> {
+        let key_blob = reader.read_string()?;
+        let data = reader.read_string()?;
+        let flags = reader.read_u32().unwrap_or(0);
+
+        // Find key, skipping databases that have been locked
+        let mut privkey = None;
+        let mut is_expired = false;
+        for open_db in self.databases() {
+            let db = open_db.nyxdb.lock().ok()?;
+            let found = db.ssh_keys.values().filter_map(parse_private_key).find(|privkey| {
+                privkey.public_key().to_bytes().map(|b| b == key_blob).unwrap_or(false)
+            });
+            if found.is_some() && open_db.is_expired() {
+                is_expired = true;
+            } else if found.is_some() {
+                privkey = found;
+                break;
+            }
+        }
+
+        if privkey.is_none() && is_expired {
+            cli_warn!("Refusing SSH agent sign request, database session has expired.");
+        }
+        let privkey = privkey?;
+
+        // Sign
+        let signature = sign(&privkey, data, flags)?;
+        let mut sig_blob = vec![];
+        put_string(&mut sig_blob, signature.algorithm().as_str().as_bytes());
+        put_string(&mut sig_blob, signature.as_bytes());
+
+        let mut res = vec![SSH_AGENT_SIGN_RESPONSE];
+        put_string(&mut res, &sig_blob);
+        Some(res)
+    }
+}
+
+/// Parse the private key of an entry
+fn parse_private_key(ssh_key: &SshKey) -> Option<PrivateKey> {
+    let privkey = PrivateKey::from_openssh(&ssh_key.private_key).ok()?;
+    if privkey.is_encrypted() {
+        return None;
+    }
+    Some(privkey)
+}
+
+/// Sign data, honoring the RSA hash algorithm requested by the client
+fn sign(privkey: &PrivateKey, data: &[u8], flags: u32) -> Option<Signature> {
+    let keypair = match privkey.key_data() {
+        KeypairData::Rsa(keypair) => keypair,
+        _ => return privkey.try_sign(data).ok(),
+    };
+
+    // Legacy SHA-1 based ssh-rsa signatures are not supported
+    let hash = if flags & SSH_AGENT_RSA_SHA2_512 != 0 {
+        HashAlg::Sha512
+    } else if flags & SSH_AGENT_RSA_SHA2_256 != 0 {
+        HashAlg::Sha256
+    } else {
+        return None;
+    };
+
+    // Build RSA key from its components
+    let to_uint = |mpint: &Mpint| mpint.as_positive_bytes().map(BigUint::from_bytes_be);
+    let rsa_key = RsaPrivateKey::from_components(
+        to_uint(&keypair.public.n)?,
+        to_uint(&keypair.public.e)?,
+        to_uint(&keypair.private.d)?,
+        vec![to_uint(&keypair.private.p)?, to_uint(&keypair.private.q)?],
+    )
+    .ok()?;
+
+    let sig: Box<[u8]> = match hash {
+        HashAlg::Sha256 => SigningKey::<Sha256>::new(rsa_key).try_sign(data).ok()?.into(),
+        _ => SigningKey::<Sha512>::new(rsa_key).try_sign(data).ok()?.into(),
+    };
+
+    Signature::new(Algorithm::Rsa { hash: Some(hash) }, sig.to_vec()).ok()
+}
+
+/// Append SSH wire format string
+fn put_string(buf: &mut Vec<u8>, value: &[u8]) {
+    buf.extend((value.len() as u32).to_be_bytes());
+    buf.extend_from_slice(value);
+}
+
+/// Minimal reader for SSH wire format
+struct AgentReader<'a>(&'a [u8]);
+
+impl<'a> AgentReader<'a> {
+    fn read_u32(&mut self) -> Option<u32> {
+        if self.0.len() < 4 {
+            return None;
+        }
+        let (num, rest) = self.0.split_at(4);
+        self.0 = rest;
+        Some(u32::from_be_bytes(num.try_into().ok()?))
+    }
+
+    fn read_string(&mut self) -> Option<&'a [u8]> {
+        let len = self.read_u32()? as usize;
+        if self.0.len() < len {
+            return None;
+        }
+        let (value, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(value)
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::{CONFIG, Error};
+use falcon_cli::*;
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{self, CryptoProvider, aws_lc_rs};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    self, ClientConfig, ClientConnection, DigitallySignedStruct, ServerConfig, SignatureScheme,
+    StreamOwned,
+};
+
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+
+/// Server certificate accepted only if its SHA-256 fingerprint matches the pinned one
+#[derive(Debug)]
+struct PinnedCertVerifier {
+    fingerprint: Vec<u8>,
+    provider: Arc<CryptoProvider>,
+}
+
+/// Build TLS acceptor for the daemon
+pub fn acceptor() -> Result<TlsAcceptor, Error> {
+    let (certs, key) = load_or_generate()?;
+    cli_info!(
+        "TLS certificate fingerprint (SHA-256): {}",
+        fingerprint(&certs[0])
+    );
+
+    let config = ServerConfig::builder_with_provider(Arc::new(aws_lc_rs::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| Error::Crypto(format!("Unable to configure TLS: {}", e)))?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| Error::Crypto(format!("Invalid TLS certificate or key: {}", e)))?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Send JSON request over TLS, and return body of response
+pub fn send(json_str: &str, token: &str) -> Result<String, Error> {
+    let mut stream = connect()?;
+    stream.sock.set_read_timeout(Some(Duration::from_secs(60)))?;
+    super::transport::exchange(&mut stream, json_str, token)
+}
+
+/// Connect to daemon over TLS, verifying its pinned certificate
+pub fn connect() -> Result<StreamOwned<ClientConnection, TcpStream>, Error> {
+    let provider = Arc::new(aws_lc_rs::default_provider());
+    let verifier = PinnedCertVerifier {
+        fingerprint: pinned_fingerprint()?,
+        provider: Arc::clone(&provider),
+    };
+
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| Error::Crypto(format!("Unable to configure TLS: {}", e)))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth();
+
+    // Connect
+    let server_name = ServerName::try_from(CONFIG.host.to_string())
+        .map_err(|e| Error::Rpc(format!("Invalid RPC host {}, {}", CONFIG.host, e)))?;
+    let conn = ClientConnection::new(Arc::new(config), server_name)
+        .map_err(|e| Error::Rpc(format!("Unable to start TLS session: {}", e)))?;
+    let sock = TcpStream::connect((CONFIG.host.as_str(), CONFIG.port))?;
+
+    Ok(StreamOwned::new(conn, sock))
+}
+
+/// Get SHA-256 fingerprint of the daemon certificate, generating it if needed
+pub fn local_fingerprint() -> Result<String, Error> {
+    let (certs, _) = load_or_generate()?;
+    Ok(fingerprint(&certs[0]))
+}
+
+/// Format SHA-256 fingerprint of certificate, as shown by openssl
+pub fn fingerprint(cert: &CertificateDer) -> String {
+    Sha256::digest(cert.as_ref())
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+/// Load certificate and key from config, or the self-signed pair generated on first use
+fn load_or_generate() -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), Error> {
+    let (cert_file, key_file) = (&CONFIG.tls_cert, &CONFIG.tls_key);
+    if !Path::new(cert_file).exists() && !Path::new(key_file).exists() {
+        generate(cert_file, key_file)?;
+    }
+
+    let certs = CertificateDer::pem_file_iter(cert_file)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| {
+            Error::Crypto(format!(
+                "Unable to read TLS certificate {}: {}",
+                cert_file, e
+            ))
+        })?;
+    if certs.is_empty() {
+        return Err(Error::Crypto(format!(
+            "No certificate found within {}",
+            cert_file
+        )));
+    }
+
+    let key = PrivateKeyDer::from_pem_file(key_file)
+        .map_err(|e| Error::Crypto(format!("Unable to read TLS key {}: {}", key_file, e)))?;
+
+    Ok((certs, key))
+}
+
+/// Generate self-signed certificate and key
+fn generate(cert_file: &str, key_file: &str) -> Result<(), Error> {
+    let mut names = vec!["localhost".to_string()];
+    if CONFIG.host != "localhost" {
+        names.push(CONFIG.host.to_string());
+    }
+    let certified = rcgen::generate_simple_self_signed(names)
+        .map_err(|e| Error::Crypto(format!("Unable to generate TLS certificate: {}", e)))?;
+
+    write_private(key_file, certified.key_pair.serialize_pem().as_bytes())?;
+    write_private(cert_file, certified.cert.pem().as_bytes())?;
+    Ok(())
+}
+
+/// Write file readable by owner only, creating private parent directory
+fn write_private(filename: &str, contents: &[u8]) -> Result<(), Error> {
+    if let Some(parent) = Path::new(filename).parent()
+        && !parent.as_os_str().is_empty()
+        && !parent.exists()
+    {
+        let mut builder = fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        builder.mode(0o700);
+        builder.create(parent)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options.open(filename)?.write_all(contents)?;
+    Ok(())
+}
+
+/// Get fingerprint to pin, from flag or the local certificate
+fn pinned_fingerprint() -> Result<Vec<u8>, Error> {
+    let fingerprint = match &CONFIG.tls_fingerprint {
+        Some(fingerprint) => fingerprint.to_string(),
+        None if Path::new(&CONFIG.tls_cert).exists() => local_fingerprint()?,
+        None => {
+            return Err(Error::Rpc(format!(
+                "Unable to verify TLS certificate of {}, specify its fingerprint with --tls-fingerprint.  It is shown by running 'nyx db fingerprint' on the server.",
+                CONFIG.host
+            )));
+        }
+    };
+
+    let hex: String = fingerprint.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+    if hex.len() != 64 {
+        return Err(Error::Validate(format!(
+            "Invalid SHA-256 fingerprint, {}",
+            fingerprint
+        )));
+    }
+    (0..64)
+        .step_by(2)
+        .map(|x| u8::from_str_radix(&hex[x..x + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| Error::Validate(format!("Invalid SHA-256 fingerprint, {}", fingerprint)))
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if Sha256::digest(end_entity.as_ref()).as_slice() == self.fingerprint.as_slice() {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "Server certificate does not match pinned fingerprint".to_string(),
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider.signature_verification_algorithms.supported_schemes()
+    }
+}
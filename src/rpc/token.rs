@@ -0,0 +1,84 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::{CONFIG, Error};
+use rand::RngCore;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use zeroize::Zeroize;
+
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+
+/// Generate random session token, and save to runtime file readable by owner only
+pub fn create() -> Result<String, Error> {
+    let token_path = Path::new(&CONFIG.rpc_token_file);
+
+    // Ensure parent directory exists, and is private
+    if let Some(parent) = token_path.parent()
+        && !parent.as_os_str().is_empty()
+        && !parent.exists()
+    {
+        let mut builder = fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        builder.mode(0o700);
+        builder.create(parent)?;
+    }
+
+    // Remove stale token from previous session
+    if token_path.exists() {
+        fs::remove_file(token_path)?;
+    }
+
+    // Generate token
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    bytes.zeroize();
+
+    // Save file
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options.open(token_path)?.write_all(token.as_bytes())?;
+
+    Ok(token)
+}
+
+/// Read session token of running daemon
+pub fn read() -> Result<String, Error> {
+    let token = fs::read_to_string(&CONFIG.rpc_token_file).map_err(|e| {
+        Error::Rpc(format!(
+            "Unable to read RPC token at {}, error: {}",
+            CONFIG.rpc_token_file, e
+        ))
+    })?;
+    Ok(token.trim().to_string())
+}
+
+/// Check bearer token from Authorization header, in constant time
+pub fn verify(token: &str, header: Option<String>) -> bool {
+    let Some(header) = header else {
+        return false;
+    };
+    let Some(given) = header.trim().strip_prefix("Bearer ") else {
+        return false;
+    };
+
+    let (given, token) = (given.trim().as_bytes(), token.as_bytes());
+    if given.len() != token.len() {
+        return false;
+    }
+    given.iter().zip(token.iter()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Remove the token file
+pub fn cleanup() {
+    let _ = fs::remove_file(&CONFIG.rpc_token_file);
+}
@@ -0,0 +1,237 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use super::events::{EVENTS_PATH, RpcEvent};
+use crate::Error;
+use atlas_http::{HttpBody, HttpHeaders, HttpRequest};
+use std::str::FromStr;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+
+use std::io::{BufRead, ErrorKind, Read, Write};
+
+#[cfg(unix)]
+use crate::CONFIG;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::time::Duration;
+
+// Largest request body accepted by the daemon, enough for a user with attachments at their limit
+const MAX_BODY_LEN: usize = 16 * 1024 * 1024;
+
+// Longest request line or header, and most headers accepted by the daemon
+const MAX_LINE_LEN: usize = 8 * 1024;
+const MAX_HEADERS: usize = 64;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RpcTransport {
+    Unix,
+    Tcp,
+    Both,
+}
+
+impl RpcTransport {
+    /// Whether daemon listens on Unix socket
+    pub fn has_unix(&self) -> bool {
+        cfg!(unix) && *self != Self::Tcp
+    }
+
+    /// Whether daemon listens on TCP port
+    pub fn has_tcp(&self) -> bool {
+        !cfg!(unix) || *self != Self::Unix
+    }
+}
+
+impl Default for RpcTransport {
+    fn default() -> Self {
+        if cfg!(unix) { Self::Unix } else { Self::Tcp }
+    }
+}
+
+impl FromStr for RpcTransport {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "unix" => Ok(Self::Unix),
+            "tcp" => Ok(Self::Tcp),
+            "both" => Ok(Self::Both),
+            _ => Err(Error::Validate(format!(
+                "Invalid RPC transport '{}', supported transports are: unix, tcp, both",
+                s
+            ))),
+        }
+    }
+}
+
+/// Read HTTP request from any incoming stream, only reading the body if it carries the session token
+pub async fn read_request<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    token: &str,
+) -> Result<HttpRequest, Error> {
+    let mut reader = BufReader::new(stream);
+
+    // Request line
+    let mut first_line = String::new();
+    read_line(&mut reader, &mut first_line).await?;
+    let (method, path) = HttpRequest::parse_first_line(first_line.trim())?;
+
+    // Headers, until empty line
+    let mut header_lines = vec![];
+    loop {
+        let mut line = String::new();
+        if read_line(&mut reader, &mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        } else if header_lines.len() == MAX_HEADERS {
+            return Err(Error::Rpc("Too many request headers.".to_string()));
+        }
+        header_lines.push(line.trim().to_string());
+    }
+    let headers = HttpHeaders::from_vec(&header_lines);
+
+    // Body, left unread for requests without the session token, which are refused
+    let length =
+        headers.get_lower("content-length").and_then(|len| len.parse::<usize>().ok()).unwrap_or(0);
+    let mut body = vec![];
+    if super::token::verify(token, headers.get_lower("authorization")) {
+        if length > MAX_BODY_LEN {
+            return Err(Error::Rpc("Request body too large.".to_string()));
+        }
+        body = vec![0u8; length];
+        reader.read_exact(&mut body).await?;
+    }
+
+    Ok(HttpRequest {
+        method,
+        url: format!("http://localhost{}", path),
+        headers,
+        body: HttpBody::from_raw(&body),
+    })
+}
+
+/// Read single line, refusing any longer than the limit
+async fn read_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    line: &mut String,
+) -> Result<usize, Error> {
+    let len = reader.take(MAX_LINE_LEN as u64).read_line(line).await?;
+    if len == MAX_LINE_LEN && !line.ends_with('\n') {
+        return Err(Error::Rpc("Request line too long.".to_string()));
+    }
+    Ok(len)
+}
+
+/// Connect to Unix socket of daemon, if enabled and accepting connections
+#[cfg(unix)]
+pub fn connect_unix() -> Option<UnixStream> {
+    if !CONFIG.rpc_transport.has_unix() {
+        return None;
+    }
+    UnixStream::connect(&CONFIG.rpc_sock).ok()
+}
+
+/// Send JSON request over connected Unix socket, and return body of response
+#[cfg(unix)]
+pub fn send_unix(mut stream: UnixStream, json_str: &str, token: &str) -> Result<String, Error> {
+    stream.set_read_timeout(Some(Duration::from_secs(60)))?;
+    exchange(&mut stream, json_str, token)
+}
+
+/// User-Agent header identifying the CLI to the daemon
+pub fn user_agent() -> String {
+    format!("User-Agent: nyx/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Write JSON request to connected stream, and return body of response
+pub fn exchange<S: Read + Write>(
+    stream: &mut S,
+    json_str: &str,
+    token: &str,
+) -> Result<String, Error> {
+    // Send request
+    let req = format!(
+        "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n{}\r\nAuthorization: Bearer {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        user_agent(),
+        token,
+        json_str.len(),
+        json_str
+    );
+    stream.write_all(req.as_bytes())?;
+    stream.flush()?;
+
+    // Read response, daemon closes connection once written
+    let mut buf = vec![];
+    match stream.read_to_end(&mut buf) {
+        Ok(_) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof && !buf.is_empty() => {}
+        Err(e) => return Err(e.into()),
+    };
+    let res = String::from_utf8_lossy(&buf);
+
+    let body = res
+        .split_once("\r\n\r\n")
+        .or_else(|| res.split_once("\n\n"))
+        .map(|(_, body)| body.trim().to_string())
+        .ok_or(Error::Rpc("Received an invalid response.".to_string()))?;
+    Ok(body)
+}
+
+/// Request event stream over connected stream, and pass each event to handler until it returns false
+pub fn subscribe<S, F>(mut stream: S, token: &str, mut handler: F) -> Result<(), Error>
+where
+    S: Read + Write,
+    F: FnMut(RpcEvent) -> bool,
+{
+    let req = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nAccept: text/event-stream\r\nAuthorization: Bearer {}\r\n\r\n",
+        EVENTS_PATH, token
+    );
+    stream.write_all(req.as_bytes())?;
+    stream.flush()?;
+    let mut reader = std::io::BufReader::new(stream);
+
+    // Status line
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if line.split_whitespace().nth(1) != Some("200") {
+        return Err(Error::Rpc(format!(
+            "Unable to subscribe to events, {}",
+            line.trim()
+        )));
+    }
+
+    // Headers, until empty line
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+    }
+
+    // Events, each ended by an empty line
+    let mut data = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        let text = line.trim_end();
+        if let Some(value) = text.strip_prefix("data:") {
+            data.push_str(value.trim_start());
+        } else if text.is_empty() && !data.is_empty() {
+            if let Ok(event) = serde_json::from_str::<RpcEvent>(&data)
+                && !handler(event)
+            {
+                return Ok(());
+            }
+            data.clear();
+        }
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright 2025 Aquila Labs of Alberta, Canada <matt@cicero.sh>
+// Licensed under either the Apache License, Version 2.0 OR the MIT License, at your option.
+// You may not use this file except in compliance with one of the Licenses.
+// Apache License text: https://www.apache.org/licenses/LICENSE-2.0
+// MIT License text: https://opensource.org/licenses/MIT
+
+use crate::Error;
+#[cfg(unix)]
+use nix::libc;
+use std::alloc::{GlobalAlloc, Layout, System};
+#[cfg(unix)]
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+// Whether new allocations are locked into RAM, and largest allocation the memlock limit allows
+static LOCK_ALLOCATIONS: AtomicBool = AtomicBool::new(false);
+#[cfg(unix)]
+static LOCK_MAX_SIZE: AtomicUsize = AtomicUsize::new(usize::MAX);
+#[cfg(unix)]
+static PAGE_SIZE: AtomicUsize = AtomicUsize::new(4096);
+
+// Allocations not locked since last reported, once the memlock limit was reached
+static LOCK_FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+/// Keep process memory out of core dumps, and away from debuggers of the same user
+pub fn disable_core_dumps() -> Result<(), Error> {
+    #[cfg(unix)]
+    {
+        let limit = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        if unsafe { libc::setrlimit(libc::RLIMIT_CORE, &limit) } != 0 {
+            return Err(last_error("Unable to set RLIMIT_CORE"));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    if unsafe { libc::prctl(libc::PR_SET_DUMPABLE, 0, 0, 0, 0) } != 0 {
+        return Err(last_error("Unable to set PR_SET_DUMPABLE"));
+    }
+
+    Ok(())
+}
+
+/// Lock allocations made from now on into RAM, so the unlocked vault is never written to swap
+///
+/// Called before the vault is loaded, so the decrypted buffer and every entry decoded from it
+/// are locked.  Allocations larger than the memlock limit, such as the Argon2 working memory,
+/// are left unlocked, as are any made once the limit is reached, which are counted and
+/// reported by take_lock_failures().  Pages are unlocked again as allocations are freed.
+pub fn lock() -> Result<(), Error> {
+    #[cfg(target_os = "linux")]
+    {
+        // Raise soft limit as far as allowed
+        let mut limit = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        if unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut limit) } != 0 {
+            return Err(last_error("Unable to get RLIMIT_MEMLOCK"));
+        }
+        limit.rlim_cur = limit.rlim_max;
+        unsafe {
+            libc::setrlimit(libc::RLIMIT_MEMLOCK, &limit);
+        }
+        if limit.rlim_max != libc::RLIM_INFINITY {
+            LOCK_MAX_SIZE.store(limit.rlim_max as usize, Ordering::Relaxed);
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if page_size > 0 {
+            PAGE_SIZE.store(page_size as usize, Ordering::Relaxed);
+        }
+        LOCK_ALLOCATIONS.store(true, Ordering::Relaxed);
+    }
+
+    Ok(())
+}
+
+/// Get number of allocations which could not be locked since last called
+pub fn take_lock_failures() -> usize {
+    LOCK_FAILURES.swap(0, Ordering::Relaxed)
+}
+
+/// System allocator, locking memory of each allocation once enabled by lock()
+pub struct LockingAllocator;
+
+unsafe impl GlobalAlloc for LockingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        lock_region(ptr, layout.size());
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        lock_region(ptr, layout.size());
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if !LOCK_ALLOCATIONS.load(Ordering::Relaxed) {
+            return unsafe { System.realloc(ptr, layout, new_size) };
+        }
+
+        // Move to new allocation, so old pages are unlocked before being freed
+        let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+        let new_ptr = unsafe { self.alloc(new_layout) };
+        if !new_ptr.is_null() {
+            unsafe {
+                std::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+                self.dealloc(ptr, layout);
+            }
+        }
+        new_ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unlock_region(ptr, layout.size());
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+/// Lock pages of allocated region, if locking is enabled and the region fits within the limit
+fn lock_region(ptr: *mut u8, size: usize) {
+    #[cfg(unix)]
+    if !ptr.is_null()
+        && size > 0
+        && LOCK_ALLOCATIONS.load(Ordering::Relaxed)
+        && size <= LOCK_MAX_SIZE.load(Ordering::Relaxed)
+        && unsafe { libc::mlock(ptr as *const libc::c_void, size) } != 0
+    {
+        LOCK_FAILURES.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Unlock pages wholly within region about to be freed, leaving those shared with neighbours
+fn unlock_region(ptr: *mut u8, size: usize) {
+    #[cfg(unix)]
+    if LOCK_ALLOCATIONS.load(Ordering::Relaxed) {
+        let page_size = PAGE_SIZE.load(Ordering::Relaxed);
+        let start = (ptr as usize).next_multiple_of(page_size);
+        let end = (ptr as usize + size) / page_size * page_size;
+        if end > start {
+            unsafe {
+                libc::munlock(start as *const libc::c_void, end - start);
+            }
+        }
+    }
+}
+
+/// Describe last OS error
+#[cfg(unix)]
+fn last_error(context: &str) -> Error {
+    Error::Generic(format!("{}, {}", context, io::Error::last_os_error()))
+}
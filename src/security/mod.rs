@@ -5,4 +5,5 @@
 // MIT License text: https://opensource.org/licenses/MIT
 
 pub mod crypto;
+pub mod memory;
 pub mod password;
@@ -1,4 +1,3 @@
-
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::thread;
@@ -8,6 +7,13 @@ pub struct TestContext {
     pub dbfile: String,
     pub port: u16,
     pub password: String,
+    pub agent_sock: String,
+    pub token_file: String,
+    pub rpc_sock: String,
+    pub rpc_transport: String,
+    pub tls: bool,
+    pub lock_on: Option<String>,
+    pub env: Vec<(String, String)>,
 }
 
 impl TestContext {
@@ -19,6 +25,13 @@ impl TestContext {
             dbfile: dbfile.to_string(),
             port: 7924,
             password: "password123".to_string(),
+            agent_sock: "/tmp/nyx_test_agent.sock".to_string(),
+            token_file: "/tmp/nyx_test_rpc.token".to_string(),
+            rpc_sock: "/tmp/nyx_test_rpc.sock".to_string(),
+            rpc_transport: "unix".to_string(),
+            tls: false,
+            lock_on: None,
+            env: vec![],
         }
     }
 
@@ -31,6 +44,19 @@ impl TestContext {
     pub fn cmd(&self) -> Command {
         let mut cmd = Command::new(Self::nyx_bin());
         cmd.arg("-f").arg(&self.dbfile);
+        cmd.arg("-a").arg(&self.agent_sock);
+        cmd.arg("-k").arg(&self.token_file);
+        cmd.arg("-s").arg(&self.rpc_sock);
+        cmd.arg("-r").arg(&self.rpc_transport);
+        if self.tls {
+            cmd.arg("--tls");
+            cmd.arg("--tls-cert").arg("/tmp/nyx_test_tls/cert.pem");
+            cmd.arg("--tls-key").arg("/tmp/nyx_test_tls/key.pem");
+        }
+        if let Some(lock_on) = &self.lock_on {
+            cmd.arg("-L").arg(lock_on);
+        }
+        cmd.envs(self.env.iter().map(|(key, value)| (key, value)));
         cmd.env("RUST_BACKTRACE", "1");
         cmd.stdin(Stdio::null());
         cmd.stdout(Stdio::piped());
@@ -48,7 +74,10 @@ impl TestContext {
     /// Create the test database and start daemon
     pub fn create_db(&self) {
         let mut cmd = self.cmd();
-        cmd.arg("test").arg("createdb").arg(&self.dbfile.to_string()).arg(self.password.to_string());
+        cmd.arg("test")
+            .arg("createdb")
+            .arg(&self.dbfile.to_string())
+            .arg(self.password.to_string());
         cmd.stdin(Stdio::piped());
 
         // Send command
@@ -56,12 +85,21 @@ impl TestContext {
         let output = child.wait_with_output().expect("Failed to wait for db create");
 
         if !output.status.success() {
-            panic!("Failed to create database:\nstdout: {}\nstderr: {}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+            panic!(
+                "Failed to create database:\nstdout: {}\nstderr: {}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
         }
 
         // Check if database was created
         if !Path::new(&self.dbfile).exists() {
-            panic!("Database file was not created at {}!\nstdout: {}\nstderr: {}", self.dbfile, String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+            panic!(
+                "Database file was not created at {}!\nstdout: {}\nstderr: {}",
+                self.dbfile,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
         }
 
         // Verify daemon is running
@@ -72,7 +110,10 @@ impl TestContext {
             let check_result = check_cmd.output();
 
             if let Ok(output) = &check_result {
-                check_for_password_error(output, &format!("daemon verification attempt {}", attempt + 1));
+                check_for_password_error(
+                    output,
+                    &format!("daemon verification attempt {}", attempt + 1),
+                );
                 if output.status.success() {
                     return; // Daemon is up!
                 }
@@ -82,18 +123,22 @@ impl TestContext {
             if attempt == 19 {
                 let last_check = check_cmd.output();
                 let (stdout, stderr) = if let Ok(out) = last_check {
-                    (String::from_utf8_lossy(&out.stdout).to_string(),
-                     String::from_utf8_lossy(&out.stderr).to_string())
+                    (
+                        String::from_utf8_lossy(&out.stdout).to_string(),
+                        String::from_utf8_lossy(&out.stderr).to_string(),
+                    )
                 } else {
                     ("N/A".to_string(), "N/A".to_string())
                 };
 
-                panic!("Daemon failed to start after database creation after {} attempts.\nDB exists: {}\nPort: {}\nLast stats check:\nstdout: {}\nstderr: {}",
+                panic!(
+                    "Daemon failed to start after database creation after {} attempts.\nDB exists: {}\nPort: {}\nLast stats check:\nstdout: {}\nstderr: {}",
                     attempt + 1,
-                    self.dbfile, 
+                    self.dbfile,
                     "",
                     stdout,
-                    stderr);
+                    stderr
+                );
             }
         }
     }
@@ -116,9 +161,12 @@ impl TestContext {
         let output = child.wait_with_output().expect("Failed to wait for nyx");
         check_for_password_error(&output, "db open");
 
-        assert!(output.status.success(), "Failed to open database:\nstdout: {}\nstderr: {}",
+        assert!(
+            output.status.success(),
+            "Failed to open database:\nstdout: {}\nstderr: {}",
             String::from_utf8_lossy(&output.stdout),
-            String::from_utf8_lossy(&output.stderr));
+            String::from_utf8_lossy(&output.stderr)
+        );
 
         // Give daemon time to start
         thread::sleep(Duration::from_millis(200));
@@ -171,7 +219,8 @@ pub fn check_for_password_error(output: &std::process::Output, context: &str) {
     let stderr = String::from_utf8_lossy(&output.stderr);
 
     if stdout.contains("Invalid password, please double check and try again.")
-        || stderr.contains("Invalid password, please double check and try again.") {
+        || stderr.contains("Invalid password, please double check and try again.")
+    {
         panic!(
             "Test failed: Invalid password prompt detected in {}\nstdout: {}\nstderr: {}",
             context, stdout, stderr
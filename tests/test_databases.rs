@@ -0,0 +1,293 @@
+// Integration tests for multiple databases open under one daemon
+mod common;
+
+use assert_cmd::assert::OutputAssertExt;
+use common::TestContext;
+use predicates::prelude::*;
+use std::process::Stdio;
+
+const TEAM_DBFILE: &str = "/tmp/nyx_test_team.db";
+
+/// Create the team database and open it alongside the default one
+fn create_team_db(ctx: &TestContext) {
+    let _ = std::fs::remove_file(TEAM_DBFILE);
+    let mut cmd = ctx.cmd();
+    cmd.arg("-D").arg("team").arg("test").arg("createdb").arg(TEAM_DBFILE).arg(&ctx.password);
+    cmd.stdin(Stdio::piped());
+    cmd.assert().success();
+}
+
+#[test]
+fn test_databases_alias() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+    create_team_db(&ctx);
+
+    // Set value in each database
+    let mut cmd = ctx.cmd();
+    cmd.arg("-D").arg("team").arg("set").arg("shared/key").arg("team-value");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("set").arg("personal/key").arg("default-value");
+    cmd.assert().success();
+
+    // Read back via -D and alias prefix
+    let mut cmd = ctx.cmd();
+    cmd.arg("-D").arg("team").arg("get").arg("shared/key");
+    cmd.assert().success().stdout(predicate::str::contains("team-value"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("get").arg("team:shared/key");
+    cmd.assert().success().stdout(predicate::str::contains("team-value"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("-D").arg("default").arg("get").arg("personal/key");
+    cmd.assert().success().stdout(predicate::str::contains("default-value"));
+
+    // Databases are isolated
+    let mut cmd = ctx.cmd();
+    cmd.arg("-D").arg("default").arg("get").arg("shared/key");
+    cmd.assert().stdout(predicate::str::contains("team-value").not());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("-D").arg("team").arg("get").arg("personal/key");
+    cmd.assert().stdout(predicate::str::contains("default-value").not());
+
+    // Both listed
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("ls");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("default"))
+        .stdout(predicate::str::contains("team"))
+        .stdout(predicate::str::contains(TEAM_DBFILE));
+
+    // Closing team database leaves default open
+    let mut cmd = ctx.cmd();
+    cmd.arg("-D").arg("team").arg("db").arg("close");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("ls");
+    cmd.assert().success().stdout(predicate::str::contains(TEAM_DBFILE).not());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("get").arg("personal/key");
+    cmd.assert().success().stdout(predicate::str::contains("default-value"));
+
+    ctx.close_db();
+    let _ = std::fs::remove_file(TEAM_DBFILE);
+}
+
+#[test]
+fn test_databases_open_refused_over_tcp() {
+    let mut ctx = TestContext::new();
+    ctx.rpc_transport = "tcp".to_string();
+    ctx.create_db();
+
+    // Key of second database is not sent over plain TCP
+    let _ = std::fs::remove_file(TEAM_DBFILE);
+    let mut cmd = ctx.cmd();
+    cmd.arg("-D").arg("team").arg("test").arg("createdb").arg(TEAM_DBFILE).arg(&ctx.password);
+    cmd.stdin(Stdio::piped());
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string()
+        + &String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stdout.contains("plain TCP"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("ls");
+    cmd.assert().success().stdout(predicate::str::contains(TEAM_DBFILE).not());
+
+    ctx.close_db();
+    let _ = std::fs::remove_file(TEAM_DBFILE);
+}
+
+/// Run command with lines written to stdin, returning combined output
+fn run_with_input(ctx: &TestContext, args: &[&str], lines: &[&str]) -> String {
+    let mut cmd = ctx.cmd();
+    cmd.args(args);
+    cmd.stdin(Stdio::piped());
+    cmd.stdout(Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn nyx");
+    use std::io::Write;
+    if let Some(mut stdin) = child.stdin.take() {
+        for line in lines {
+            writeln!(stdin, "{}", line).ok();
+        }
+    }
+
+    let output = child.wait_with_output().expect("Failed to wait for nyx");
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_databases_audit_log() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Create and read entry
+    let mut cmd = ctx.cmd();
+    cmd.arg("set").arg("audit/key").arg("audit-value");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("get").arg("audit/key");
+    cmd.assert().success().stdout(predicate::str::contains("audit-value"));
+
+    // Reopen after a wrong password
+    ctx.close_db();
+    run_with_input(&ctx, &["db", "open"], &["wrong-password", &ctx.password]);
+    assert!(common::wait_for_daemon(&ctx, 50), "Daemon did not start");
+
+    // Copy to clipboard logged with client, and kept across the reopen
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--type").arg("str").arg("--action").arg("clipboard");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("audit/key"))
+        .stdout(predicate::str::contains("nyx/"))
+        .stdout(predicate::str::contains("Create").not());
+
+    // Unlock attempts
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--action").arg("unlock-failed");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("UnlockFailed"))
+        .stdout(predicate::str::contains("attempt"))
+        .stdout(predicate::str::contains(&ctx.dbfile));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--type").arg("db").arg("--action").arg("unlock");
+    cmd.assert().success().stdout(predicate::str::contains("Unlock"));
+
+    // Name prefix and date range
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--name").arg("other/");
+    cmd.assert().success().stdout(predicate::str::contains("audit/key").not());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--since").arg("2000-01-01").arg("--until").arg("2000-12-31");
+    cmd.assert().success().stdout(predicate::str::contains("audit/key").not());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--since").arg("2000-01-01");
+    cmd.assert().success().stdout(predicate::str::contains("audit/key"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--since").arg("01/01/2000");
+    let output = cmd.output().unwrap();
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        combined.contains("Invalid date"),
+        "Date not validated:\n{}",
+        combined
+    );
+
+    // Retention limit prunes older entries
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--keep").arg("2");
+    cmd.assert().success().stdout(predicate::str::contains("up to 2 entries"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--type").arg("str");
+    cmd.assert().success().stdout(predicate::str::contains("audit/key").not());
+
+    ctx.close_db();
+}
+
+/// Run command, returning combined stdout and stderr
+fn output_of(cmd: &mut std::process::Command) -> String {
+    let output = cmd.output().unwrap();
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[test]
+fn test_databases_history_chain() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+    let head_file = "/tmp/nyx_test_head.json";
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("set").arg("chain/first").arg("one");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("verify");
+    cmd.assert().success().stdout(predicate::str::contains("History chain is intact"));
+
+    // Export head for a third party
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("head").arg("--file").arg(head_file);
+    cmd.assert().success().stdout(predicate::str::contains("Exported head of history chain"));
+    let head = std::fs::read_to_string(head_file).expect("Head not exported");
+    assert!(
+        head.contains("\"seq\"") && head.contains("\"hash\""),
+        "Invalid head:\n{}",
+        head
+    );
+
+    // Head still part of the chain after further entries
+    let mut cmd = ctx.cmd();
+    cmd.arg("set").arg("chain/second").arg("two");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("verify").arg("--head").arg(head_file);
+    cmd.assert().success().stdout(predicate::str::contains("History chain is intact"));
+
+    // Head not matching the chain is reported
+    let value: serde_json::Value = serde_json::from_str(&head).unwrap();
+    let altered = head.replace(value["hash"].as_str().unwrap(), &"f".repeat(64));
+    std::fs::write(head_file, &altered).unwrap();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("verify").arg("--head").arg(head_file);
+    let output = output_of(&mut cmd);
+    assert!(
+        output.contains("no longer matches"),
+        "Altered head not reported:\n{}",
+        output
+    );
+
+    // Chain anchored to the last entry removed by the history limits
+    std::fs::write(head_file, &head).unwrap();
+    let mut cmd = ctx.cmd();
+    cmd.arg("set").arg("chain/third").arg("three");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--keep").arg("1");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("verify");
+    cmd.assert().success().stdout(predicate::str::contains("History chain is intact"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("verify").arg("--head").arg(head_file);
+    let output = output_of(&mut cmd);
+    assert!(
+        output.contains("removed by the history limits"),
+        "Pruned head not reported:\n{}",
+        output
+    );
+
+    ctx.close_db();
+    let _ = std::fs::remove_file(head_file);
+}
@@ -0,0 +1,341 @@
+// Integration tests for exporting the whole database
+mod common;
+
+use assert_cmd::assert::OutputAssertExt;
+use common::TestContext;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::{Output, Stdio};
+
+/// Run nyx command, writing given lines to stdin
+fn run(ctx: &TestContext, args: &[&str], input: &[&str]) -> Output {
+    let mut cmd = ctx.cmd();
+    cmd.args(args);
+    cmd.stdin(Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn nyx");
+    use std::io::Write;
+    if let Some(mut stdin) = child.stdin.take() {
+        for line in input {
+            writeln!(stdin, "{}", line).ok();
+        }
+    }
+
+    child.wait_with_output().expect("Failed to wait for nyx")
+}
+
+/// Create database populated from the KeePass fixture
+fn populated_db(ctx: &TestContext) {
+    ctx.create_db();
+    let output = run(
+        ctx,
+        &[
+            "db",
+            "import",
+            "--format",
+            "kdbx",
+            "tests/fixtures/import.kdbx",
+        ],
+        &["keepass123", "y"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Imported 6 entries"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("set").arg("api/token").arg("tok-123");
+    cmd.assert().success();
+}
+
+/// Replace database with a new empty one
+fn recreate_db(ctx: &TestContext) {
+    ctx.close_db();
+    let _ = std::fs::remove_file(&ctx.dbfile);
+    ctx.create_db();
+}
+
+/// Check entries of the KeePass fixture exist
+fn assert_restored(ctx: &TestContext) {
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("web/github");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("octocat"))
+        .stdout(predicate::str::contains("gh-pass-1"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("web/github");
+    cmd.assert().success().stdout(predicate::str::is_match(r"\b\d{6}\b").unwrap());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("ssh").arg("show").arg("servers/build-box");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("build.example.com"))
+        .stdout(predicate::str::contains("CI runner"));
+}
+
+#[test]
+fn test_export_json() {
+    let ctx = TestContext::new();
+    populated_db(&ctx);
+    let file = "/tmp/nyx_test_export.json";
+    let _ = std::fs::remove_file(file);
+
+    // Tags and favorites
+    run(&ctx, &["tag", "web/github", "work"], &[]);
+    run(&ctx, &["fav", "web/github"], &[]);
+    run(&ctx, &["tag", "api/token", "prod", "--type", "str"], &[]);
+
+    // Declined plaintext confirmation
+    let output = run(
+        &ctx,
+        &["db", "export", "--format", "json", "--file", file],
+        &["n"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Export cancelled"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+    assert!(!Path::new(file).exists());
+
+    // Plaintext export
+    let output = run(
+        &ctx,
+        &["db", "export", "--format", "json", "--file", file],
+        &["y"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Exported 7 entries"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    let contents = std::fs::read_to_string(file).unwrap();
+    assert!(contents.contains("\"format\": \"nyx\""));
+    assert!(contents.contains("gh-pass-1"));
+    assert!(contents.contains("tok-123"));
+    assert!(contents.contains("OPENSSH PRIVATE KEY"));
+    assert!(contents.contains("\"str/api/token\""));
+    assert!(contents.contains("\"user/web/github\""));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // Restore into new database
+    recreate_db(&ctx);
+    let output = run(&ctx, &["db", "import", "--format", "json", file], &["y"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Imported 7 entries"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+    assert_restored(&ctx);
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("get").arg("api/token");
+    cmd.assert().success().stdout(predicate::str::contains("tok-123"));
+
+    // Tags and favorites restored
+    let mut cmd = ctx.cmd();
+    cmd.arg("tag").arg("web/github");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("#work"))
+        .stdout(predicate::str::contains("Yes"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("ls").arg("--tag").arg("prod");
+    cmd.assert().success().stdout(predicate::str::contains("api/token"));
+
+    let _ = std::fs::remove_file(file);
+}
+
+#[test]
+fn test_export_json_encrypted() {
+    let ctx = TestContext::new();
+    populated_db(&ctx);
+    let file = "/tmp/nyx_test_export.enc";
+    let _ = std::fs::remove_file(file);
+
+    let output = run(
+        &ctx,
+        &[
+            "db",
+            "export",
+            "--format",
+            "json",
+            "--file",
+            file,
+            "--encrypt",
+        ],
+        &["export-pass", "export-pass"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Exported 7 entries"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    let contents = std::fs::read(file).unwrap();
+    assert!(!String::from_utf8_lossy(&contents).contains("gh-pass-1"));
+
+    // Wrong passphrase, then restore
+    recreate_db(&ctx);
+    let output = run(
+        &ctx,
+        &["db", "import", "--format", "json", file],
+        &["wrong-pass"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("ERROR"), "Unexpected output:\n{}", stdout);
+
+    let output = run(
+        &ctx,
+        &["db", "import", "--format", "json", file],
+        &["export-pass", "y"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Imported 7 entries"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+    assert_restored(&ctx);
+
+    let _ = std::fs::remove_file(file);
+}
+
+#[test]
+fn test_export_json_invalid_import() {
+    let ctx = TestContext::new();
+    populated_db(&ctx);
+    let file = "/tmp/nyx_test_export_invalid.json";
+    let _ = std::fs::remove_file(file);
+
+    let output = run(
+        &ctx,
+        &["db", "export", "--format", "json", "--file", file],
+        &["y"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Exported 7 entries"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    // OTP entry with invalid digits fails the whole import
+    let contents = std::fs::read_to_string(file).unwrap();
+    assert!(contents.contains("\"digits\": 6"));
+    std::fs::write(file, contents.replace("\"digits\": 6", "\"digits\": 25")).unwrap();
+
+    recreate_db(&ctx);
+    let output = run(&ctx, &["db", "import", "--format", "json", file], &["y"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Number of digits"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    // Nothing imported, not even entries ahead of the invalid one
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("ls");
+    cmd.assert().stdout(predicate::str::contains("web/github").not());
+
+    let _ = std::fs::remove_file(file);
+}
+
+#[test]
+fn test_export_bitwarden() {
+    let ctx = TestContext::new();
+    populated_db(&ctx);
+    let file = "/tmp/nyx_test_export_bitwarden.json";
+    let _ = std::fs::remove_file(file);
+
+    let output = run(
+        &ctx,
+        &["db", "export", "--format", "bitwarden", "--file", file],
+        &["y"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Exported 7 entries"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    let contents = std::fs::read_to_string(file).unwrap();
+    assert!(contents.contains("\"encrypted\": false"));
+    assert!(contents.contains("otpauth://totp/"));
+
+    // Round trip through Bitwarden import
+    recreate_db(&ctx);
+    let output = run(
+        &ctx,
+        &["db", "import", "--format", "bitwarden", file],
+        &["y"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Imported"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+    assert_restored(&ctx);
+
+    let _ = std::fs::remove_file(file);
+}
+
+#[test]
+fn test_export_kdbx() {
+    let ctx = TestContext::new();
+    populated_db(&ctx);
+    let file = "/tmp/nyx_test_export.kdbx";
+    let _ = std::fs::remove_file(file);
+
+    let output = run(
+        &ctx,
+        &["db", "export", "--format", "kdbx", "--file", file],
+        &["kdbx-pass", "kdbx-pass"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Exported 7 entries"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    let contents = std::fs::read(file).unwrap();
+    assert_eq!(&contents[..4], &[0x03, 0xd9, 0xa2, 0x9a]);
+
+    // Round trip through KeePass import
+    recreate_db(&ctx);
+    let output = run(
+        &ctx,
+        &["db", "import", "--format", "kdbx", file],
+        &["kdbx-pass", "y"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Imported"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+    assert_restored(&ctx);
+
+    let _ = std::fs::remove_file(file);
+}
@@ -0,0 +1,233 @@
+// Integration tests for importing from other password managers
+mod common;
+
+use assert_cmd::assert::OutputAssertExt;
+use common::TestContext;
+use predicates::prelude::*;
+use std::process::{Output, Stdio};
+
+/// Run import command, writing given lines to stdin
+fn import(ctx: &TestContext, format: &str, fixture: &str, args: &[&str], input: &[&str]) -> Output {
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("import").arg("--format").arg(format);
+    cmd.arg(format!("tests/fixtures/{}", fixture));
+    cmd.args(args);
+    cmd.stdin(Stdio::piped());
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn nyx");
+    use std::io::Write;
+    if let Some(mut stdin) = child.stdin.take() {
+        for line in input {
+            writeln!(stdin, "{}", line).ok();
+        }
+    }
+
+    child.wait_with_output().expect("Failed to wait for nyx")
+}
+
+#[test]
+fn test_import_kdbx() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Wrong password
+    let output = import(&ctx, "kdbx", "import.kdbx", &[], &["wrong-password"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Invalid KeePass password"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    // Import
+    let output = import(&ctx, "kdbx", "import.kdbx", &[], &["keepass123", "y"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "KeePass import failed:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Imported 6 entries"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    // Login with folder, custom field and TOTP
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("web/github");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("octocat"))
+        .stdout(predicate::str::contains("gh-pass-1"))
+        .stdout(predicate::str::contains("Recovery Email:"))
+        .stdout(predicate::str::contains("octo@example.com"))
+        .stdout(predicate::str::contains("Recovery Email: octo@example.com").not());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("web/github");
+    cmd.assert().success().stdout(predicate::str::is_match(r"\b\d{6}\b").unwrap());
+
+    // Nested folder, KeePass TOTP settings
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("web/email/fastmail");
+    cmd.assert().success().stdout(predicate::str::contains("fm-pass & <x>"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("web/email/fastmail");
+    cmd.assert().success().stdout(predicate::str::contains("SHA256, 8 digits, 60 seconds"));
+
+    // SSH key attachment, decrypted with entry password
+    let mut cmd = ctx.cmd();
+    cmd.arg("ssh").arg("show").arg("servers/build-box");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("build.example.com"))
+        .stdout(predicate::str::contains("2222"))
+        .stdout(predicate::str::contains("CI runner"));
+
+    // Note, recycle bin skipped
+    let mut cmd = ctx.cmd();
+    cmd.arg("note").arg("show").arg("wifi");
+    cmd.assert().success().stdout(predicate::str::contains("key hunter2"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("find").arg("gone");
+    cmd.assert().stdout(predicate::str::contains("deleted").not());
+
+    // Importing again skips existing entries
+    let output = import(&ctx, "kdbx", "import.kdbx", &[], &["keepass123"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(
+        stdout.contains("nothing to import"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_import_kdbx_chacha_dry_run() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Argon2id and ChaCha20, dry run
+    let output = import(
+        &ctx,
+        "kdbx",
+        "import_chacha.kdbx",
+        &["--dry-run"],
+        &["keepass123"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "KeePass dry run failed:\n{}",
+        stdout
+    );
+    assert!(stdout.contains("Dry run"), "Unexpected output:\n{}", stdout);
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("ls");
+    cmd.assert().success().stdout(predicate::str::contains("github").not());
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_import_bitwarden() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    let output = import(&ctx, "bitwarden", "import_bitwarden.json", &[], &["y"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Bitwarden import failed:\n{}",
+        stdout
+    );
+
+    // Duplicate names receive a suffix
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("personal/example-site");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("bw-pass-1"))
+        .stdout(predicate::str::contains("PIN:"))
+        .stdout(predicate::str::contains("1234").not());
+
+    // Hidden field imported as concealed field
+    let out = format!("{}.pin", ctx.dbfile);
+    let mut cmd = ctx.cmd();
+    cmd.arg("x").arg("personal/example-site").arg("pin").arg("--file").arg(&out);
+    cmd.assert().success();
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "1234");
+    let _ = std::fs::remove_file(&out);
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("personal/example-site-2");
+    cmd.assert().success().stdout(predicate::str::contains("bw-pass-2"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("personal/example-site");
+    cmd.assert().success().stdout(predicate::str::contains("JBSWY3DPEHPK3PXP"));
+
+    // Secure note and card
+    let mut cmd = ctx.cmd();
+    cmd.arg("note").arg("show").arg("recovery-codes");
+    cmd.assert().success().stdout(predicate::str::contains("code-2"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("note").arg("show").arg("personal/visa");
+    cmd.assert().success().stdout(predicate::str::contains("4111111111111111"));
+
+    // Nested folder SSH key
+    let mut cmd = ctx.cmd();
+    cmd.arg("ssh").arg("show").arg("work/servers/web-server");
+    cmd.assert().success().stdout(predicate::str::contains("prod"));
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_import_csv() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // 1Password
+    let output = import(&ctx, "1password", "import_1password.csv", &[], &["y"]);
+    assert!(output.status.success(), "1Password import failed");
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("amazon");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("op-pass-1"))
+        .stdout(predicate::str::contains("renews in May"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("bank");
+    cmd.assert().success().stdout(predicate::str::contains("Bank"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("note").arg("show").arg("server-notes");
+    cmd.assert().success().stdout(predicate::str::contains("rack 4"));
+
+    // Generic CSV, leading Root group removed and title from URL
+    let output = import(&ctx, "csv", "import.csv", &[], &["y"]);
+    assert!(output.status.success(), "CSV import failed");
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("social/mastodon");
+    cmd.assert().success().stdout(predicate::str::contains("csv-pass-1"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("forum.example.net");
+    cmd.assert().success().stdout(predicate::str::contains("csv-pass-2"));
+
+    ctx.close_db();
+}
@@ -0,0 +1,201 @@
+// Integration tests for locking on logind signals, against a private D-Bus bus
+mod common;
+
+use common::TestContext;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use zbus::Connection;
+use zbus::zvariant::OwnedObjectPath;
+
+const MANAGER: &str = "org.freedesktop.login1.Manager";
+const SESSION: &str = "org.freedesktop.login1.Session";
+const LOGIN1_PATH: &str = "/org/freedesktop/login1";
+const SESSION_PATH: &str = "/org/freedesktop/login1/session/c1";
+
+/// Stand-in for the logind manager, placing every process in the same session
+struct FakeManager;
+
+#[zbus::interface(name = "org.freedesktop.login1.Manager")]
+impl FakeManager {
+    #[zbus(name = "GetSessionByPID")]
+    fn get_session_by_pid(&self, _pid: u32) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(SESSION_PATH).unwrap()
+    }
+}
+
+/// Private D-Bus bus standing in for the system bus, with a fake logind owning its name
+struct TestBus {
+    child: Child,
+    address: String,
+    rt: Runtime,
+    logind: Connection,
+}
+
+impl TestBus {
+    /// Start bus and fake logind, panics if dbus-daemon is not installed
+    fn start() -> Self {
+        let mut child = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("dbus-daemon is required to run the logind tests");
+
+        let mut address = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut address).unwrap();
+        let address = address.trim().to_string();
+
+        let rt = Runtime::new().unwrap();
+        let logind = rt
+            .block_on(async {
+                zbus::connection::Builder::address(address.as_str())?
+                    .name("org.freedesktop.login1")?
+                    .serve_at(LOGIN1_PATH, FakeManager)?
+                    .build()
+                    .await
+            })
+            .expect("Unable to start fake logind");
+
+        Self {
+            child,
+            address,
+            rt,
+            logind,
+        }
+    }
+
+    /// Emit signal from fake logind
+    fn signal<B>(&self, path: &str, interface: &str, member: &str, body: &B)
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        self.rt
+            .block_on(self.logind.emit_signal(None::<()>, path, interface, member, body))
+            .expect("Unable to emit signal");
+    }
+
+    /// Emit signal from another client of the bus, which is not logind
+    fn impostor_signal(&self, path: &str, member: &str, args: &[&str]) {
+        let status = Command::new("dbus-send")
+            .arg(format!("--bus={}", self.address))
+            .arg("--type=signal")
+            .arg(path)
+            .arg(member)
+            .args(args)
+            .status()
+            .expect("Unable to run dbus-send");
+        assert!(status.success());
+    }
+}
+
+impl Drop for TestBus {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Start daemon with system bus pointed at test bus
+fn start_daemon(bus: &TestBus, lock_on: Option<&str>) -> TestContext {
+    let mut ctx = TestContext::new();
+    ctx.lock_on = lock_on.map(|l| l.to_string());
+    ctx.env.push(("DBUS_SYSTEM_BUS_ADDRESS".to_string(), bus.address.clone()));
+    ctx.create_db();
+
+    // Give daemon time to subscribe
+    thread::sleep(Duration::from_millis(500));
+    ctx
+}
+
+/// Wait for daemon to exit, returning whether it did, allowing time to save pending history
+fn wait_for_exit(ctx: &TestContext) -> bool {
+    for _ in 0..100 {
+        if !Path::new(&ctx.rpc_sock).exists() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    false
+}
+
+#[test]
+fn test_logind_sleep() {
+    let bus = TestBus::start();
+    let ctx = start_daemon(&bus, None);
+
+    // Resuming does not lock
+    bus.signal(LOGIN1_PATH, MANAGER, "PrepareForSleep", &(false,));
+    assert!(!wait_for_exit(&ctx), "Daemon exited on resume");
+
+    // Suspending does
+    bus.signal(LOGIN1_PATH, MANAGER, "PrepareForSleep", &(true,));
+    assert!(
+        wait_for_exit(&ctx),
+        "Daemon still running after PrepareForSleep"
+    );
+}
+
+#[test]
+fn test_logind_screen_lock() {
+    let bus = TestBus::start();
+    let ctx = start_daemon(&bus, None);
+
+    // Signal from any other client is ignored
+    bus.impostor_signal(SESSION_PATH, "org.freedesktop.login1.Session.Lock", &[]);
+    assert!(
+        !wait_for_exit(&ctx),
+        "Daemon exited on Lock not sent by logind"
+    );
+
+    bus.signal(SESSION_PATH, SESSION, "Lock", &());
+    assert!(wait_for_exit(&ctx), "Daemon still running after Lock");
+}
+
+#[test]
+fn test_logind_logout() {
+    let bus = TestBus::start();
+    let ctx = start_daemon(&bus, None);
+
+    // Removal of another session is ignored
+    let other = OwnedObjectPath::try_from("/org/freedesktop/login1/session/c2").unwrap();
+    bus.signal(LOGIN1_PATH, MANAGER, "SessionRemoved", &("c2", other));
+    assert!(
+        !wait_for_exit(&ctx),
+        "Daemon exited on removal of another session"
+    );
+
+    // Removal of own session locks
+    let own = OwnedObjectPath::try_from(SESSION_PATH).unwrap();
+    bus.signal(LOGIN1_PATH, MANAGER, "SessionRemoved", &("c1", own));
+    assert!(
+        wait_for_exit(&ctx),
+        "Daemon still running after SessionRemoved"
+    );
+}
+
+#[test]
+fn test_logind_lock_on() {
+    let bus = TestBus::start();
+
+    // Only sleep enabled
+    let ctx = start_daemon(&bus, Some("sleep"));
+    bus.signal(SESSION_PATH, SESSION, "Lock", &());
+    assert!(
+        !wait_for_exit(&ctx),
+        "Daemon exited on Lock, with only sleep enabled"
+    );
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("set").arg("web/github").arg("gh-pass-1");
+    assert!(cmd.output().unwrap().status.success());
+
+    bus.signal(LOGIN1_PATH, MANAGER, "PrepareForSleep", &(true,));
+    assert!(
+        wait_for_exit(&ctx),
+        "Daemon still running after PrepareForSleep"
+    );
+}
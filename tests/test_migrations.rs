@@ -0,0 +1,297 @@
+// Integration tests for loading databases created by older versions of Nyx
+mod common;
+
+use assert_cmd::assert::OutputAssertExt;
+use common::TestContext;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Stdio;
+
+/// Copy fixture database into place, and open it
+fn open_fixture(ctx: &TestContext, fixture: &str) -> String {
+    for version in 1..=6 {
+        let _ = std::fs::remove_file(format!("{}.v{}.bak", ctx.dbfile, version));
+    }
+    std::fs::copy(format!("tests/fixtures/{}", fixture), &ctx.dbfile)
+        .expect("Failed to copy fixture");
+
+    open_in_place(ctx)
+}
+
+/// Open the database already in place, returning output of the open command
+fn open_in_place(ctx: &TestContext) -> String {
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("open");
+    cmd.stdin(Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn nyx");
+    use std::io::Write;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "{}", ctx.password).ok();
+    }
+
+    let output = child.wait_with_output().expect("Failed to wait for nyx");
+    assert!(output.status.success(), "Failed to open database");
+    assert!(common::wait_for_daemon(ctx, 50), "Daemon did not start");
+
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Verify entries shared by all fixtures
+fn assert_common_entries(ctx: &TestContext) {
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("mysite/alice");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("secret-pass-1"))
+        .stdout(predicate::str::contains("https://example.com"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("mysite/github");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("JBSWY3DPEHPK3PXP"))
+        .stdout(predicate::str::contains("TOTP"))
+        .stdout(predicate::str::contains("SHA1, 6 digits, 30 seconds"))
+        .stdout(predicate::str::contains("recovery-1 recovery-2"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("mysite/github");
+    cmd.assert().success().stdout(predicate::str::is_match(r"\b\d{6}\b").unwrap());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("ssh").arg("show").arg("mysite/server1");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("server1.example.com"))
+        .stdout(predicate::str::contains("2222"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("get").arg("mysite/api-key");
+    cmd.assert().success().stdout(predicate::str::contains("sk_live_123"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("note").arg("show").arg("mysite/readme");
+    cmd.assert().success().stdout(predicate::str::contains("second line"));
+}
+
+#[test]
+fn test_migrate_v1() {
+    let ctx = TestContext::new();
+
+    // Open, which upgrades the database
+    let stdout = open_fixture(&ctx, "db_v1.nyx");
+    assert!(
+        stdout.contains("upgraded from format version 1 to 7"),
+        "No upgrade notice:\n{}",
+        stdout
+    );
+    assert!(
+        Path::new(&format!("{}.v1.bak", ctx.dbfile)).exists(),
+        "Original database was not kept"
+    );
+    assert_common_entries(&ctx);
+    ctx.close_db();
+
+    // Reopen, database now saved in current version
+    let stdout = open_in_place(&ctx);
+    assert!(
+        !stdout.contains("upgraded from format version"),
+        "Database upgraded twice:\n{}",
+        stdout
+    );
+    assert_common_entries(&ctx);
+    ctx.close_db();
+
+    let _ = std::fs::remove_file(format!("{}.v1.bak", ctx.dbfile));
+}
+
+#[test]
+fn test_migrate_v2() {
+    let ctx = TestContext::new();
+
+    let stdout = open_fixture(&ctx, "db_v2.nyx");
+    assert!(
+        stdout.contains("upgraded from format version 2 to 7"),
+        "No upgrade notice:\n{}",
+        stdout
+    );
+    assert!(
+        Path::new(&format!("{}.v2.bak", ctx.dbfile)).exists(),
+        "Original database was not kept"
+    );
+    assert_common_entries(&ctx);
+
+    // Token parameters
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("mysite/vendor");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("SHA256, 8 digits, 60 seconds"))
+        .stdout(predicate::str::contains("carol"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("mysite/hardware");
+    cmd.assert().success().stdout(predicate::str::contains("287082"));
+
+    // Prior versions kept from now on, with default retention
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("retention");
+    cmd.assert().success().stdout(predicate::str::contains("Keeping 10 prior versions"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("history").arg("mysite/alice");
+    cmd.assert().success().stdout(predicate::str::contains("No prior versions"));
+
+    ctx.close_db();
+    let _ = std::fs::remove_file(format!("{}.v2.bak", ctx.dbfile));
+}
+
+#[test]
+fn test_migrate_v3() {
+    let ctx = TestContext::new();
+
+    let stdout = open_fixture(&ctx, "db_v3.nyx");
+    assert!(
+        stdout.contains("upgraded from format version 3 to 7"),
+        "No upgrade notice:\n{}",
+        stdout
+    );
+    assert!(
+        Path::new(&format!("{}.v3.bak", ctx.dbfile)).exists(),
+        "Original database was not kept"
+    );
+    assert_common_entries(&ctx);
+
+    // Prior versions and retention carried over
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("retention");
+    cmd.assert().success().stdout(predicate::str::contains("Keeping 5 prior versions"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("revert").arg("mysite/alice").arg("1");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("mysite/alice");
+    cmd.assert().success().stdout(predicate::str::contains("old-pass-0"));
+
+    // Earlier history kept without a client, reads now logged with one
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--type").arg("user").arg("--action").arg("read");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("mysite/alice"))
+        .stdout(predicate::str::contains("nyx/"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--action").arg("update");
+    cmd.assert().success().stdout(predicate::str::contains("mysite/alice"));
+
+    ctx.close_db();
+    let _ = std::fs::remove_file(format!("{}.v3.bak", ctx.dbfile));
+}
+
+#[test]
+fn test_migrate_v4() {
+    let ctx = TestContext::new();
+
+    let stdout = open_fixture(&ctx, "db_v4.nyx");
+    assert!(
+        stdout.contains("upgraded from format version 4 to 7"),
+        "No upgrade notice:\n{}",
+        stdout
+    );
+    assert!(
+        Path::new(&format!("{}.v4.bak", ctx.dbfile)).exists(),
+        "Original database was not kept"
+    );
+    assert_common_entries(&ctx);
+
+    // Existing history kept with its clients and limits, and chained on upgrade
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--action").arg("unlock-failed");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("UnlockFailed"))
+        .stdout(predicate::str::contains("nyx/"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("--keep-days").arg("3650");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "up to 500 entries, from the last 3650 days",
+    ));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("verify");
+    cmd.assert().success().stdout(predicate::str::contains("History chain is intact"));
+
+    ctx.close_db();
+    let _ = std::fs::remove_file(format!("{}.v4.bak", ctx.dbfile));
+}
+
+#[test]
+fn test_migrate_v5() {
+    let ctx = TestContext::new();
+
+    let stdout = open_fixture(&ctx, "db_v5.nyx");
+    assert!(
+        stdout.contains("upgraded from format version 5 to 7"),
+        "No upgrade notice:\n{}",
+        stdout
+    );
+    assert!(
+        Path::new(&format!("{}.v5.bak", ctx.dbfile)).exists(),
+        "Original database was not kept"
+    );
+    assert_common_entries(&ctx);
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("get").arg("mysite/v5-key");
+    cmd.assert().success().stdout(predicate::str::contains("v5-value"));
+
+    // Users upgraded without custom fields, and history chain kept
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("mysite/alice");
+    cmd.assert().success().stdout(predicate::str::contains("Attachments:").not());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("history").arg("verify");
+    cmd.assert().success().stdout(predicate::str::contains("History chain is intact"));
+
+    ctx.close_db();
+    let _ = std::fs::remove_file(format!("{}.v5.bak", ctx.dbfile));
+}
+
+#[test]
+fn test_migrate_v6() {
+    let ctx = TestContext::new();
+
+    let stdout = open_fixture(&ctx, "db_v6.nyx");
+    assert!(
+        stdout.contains("upgraded from format version 6 to 7"),
+        "No upgrade notice:\n{}",
+        stdout
+    );
+    assert!(
+        Path::new(&format!("{}.v6.bak", ctx.dbfile)).exists(),
+        "Original database was not kept"
+    );
+    assert_common_entries(&ctx);
+
+    // Custom fields and attachments kept, without any tags
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("mysite/alice");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("v6-account"))
+        .stdout(predicate::str::contains("v6.txt (11 bytes)"))
+        .stdout(predicate::str::contains("Tags:").not());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("stats");
+    cmd.assert().success().stdout(predicate::str::contains("Favorites:"));
+
+    ctx.close_db();
+    let _ = std::fs::remove_file(format!("{}.v6.bak", ctx.dbfile));
+}
@@ -2,8 +2,10 @@
 mod common;
 
 use assert_cmd::assert::OutputAssertExt;
-use predicates::prelude::*;
 use common::TestContext;
+use predicates::prelude::*;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[test]
 fn test_otp_new() {
@@ -89,9 +91,7 @@ fn test_otp_generate() {
     cmd.arg("otp").arg("testservice");
 
     // Should output a 6-digit code
-    cmd.assert()
-        .success()
-        .stdout(predicate::str::is_match(r"\d{6}").unwrap());
+    cmd.assert().success().stdout(predicate::str::is_match(r"\d{6}").unwrap());
 
     ctx.close_db();
 }
@@ -164,9 +164,7 @@ fn test_otp_edit() {
     let mut cmd = ctx.cmd();
     cmd.arg("otp").arg("show").arg("editotp");
 
-    cmd.assert()
-        .success()
-        .stdout(predicate::str::contains("updated.com"));
+    cmd.assert().success().stdout(predicate::str::contains("updated.com"));
 
     ctx.close_db();
 }
@@ -342,9 +340,226 @@ fn test_otp_categories() {
     let mut cmd = ctx.cmd();
     cmd.arg("otp").arg("ls").arg("work");
 
+    cmd.assert().success().stdout(predicate::str::contains("aws"));
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_otp_hotp_and_parameters() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Create HOTP entry using the RFC 4226 test secret
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("new").arg("hardware");
+    cmd.stdin(std::process::Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn");
+    use std::io::Write;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").ok();
+        writeln!(stdin, "").ok(); // url
+        writeln!(stdin, "").ok(); // recovery keys
+        writeln!(stdin, "hotp").ok(); // type
+        writeln!(stdin, "").ok(); // algorithm
+        writeln!(stdin, "").ok(); // digits
+        writeln!(stdin, "").ok(); // counter
+    }
+    child.wait().ok();
+
+    // Counter increments and persists between generations
+    for expected in ["755224", "287082", "359152"] {
+        let mut cmd = ctx.cmd();
+        cmd.arg("otp").arg("hardware");
+        cmd.assert().success().stdout(predicate::str::contains(expected));
+    }
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("hardware");
     cmd.assert()
         .success()
-        .stdout(predicate::str::contains("aws"));
+        .stdout(predicate::str::contains("HOTP"))
+        .stdout(predicate::str::contains("counter 3"));
+
+    // Watching is refused without advancing the counter
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("hardware").arg("--watch");
+    cmd.assert().stdout(predicate::str::contains("only supported for time based"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("hardware");
+    cmd.assert().success().stdout(predicate::str::contains("counter 3"));
+
+    // Create 8 digit SHA256 TOTP entry with 60 second period
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("new").arg("vendor");
+    cmd.stdin(std::process::Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn");
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "JBSWY3DPEHPK3PXP").ok();
+        writeln!(stdin, "").ok();
+        writeln!(stdin, "").ok();
+        writeln!(stdin, "totp").ok();
+        writeln!(stdin, "sha256").ok();
+        writeln!(stdin, "8").ok();
+        writeln!(stdin, "60").ok();
+    }
+    child.wait().ok();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("vendor");
+    cmd.assert().success().stdout(predicate::str::contains("SHA256, 8 digits, 60 seconds"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("vendor");
+    cmd.assert().success().stdout(predicate::str::is_match(r"\b\d{8}\b").unwrap());
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_otp_import_uri() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Import single otpauth URI
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("new").arg("github").arg(
+        "otpauth://totp/GitHub:alice%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=GitHub&algorithm=SHA256&digits=8&period=60",
+    );
+    cmd.assert().success().stdout(predicate::str::contains("Created new entry"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("github");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("JBSWY3DPEHPK3PXP"))
+        .stdout(predicate::str::contains("SHA256, 8 digits, 60 seconds"))
+        .stdout(predicate::str::contains("GitHub"))
+        .stdout(predicate::str::contains("alice@example.com"));
+
+    // Import Google Authenticator migration payload
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("new").arg("phone").arg(
+        "otpauth-migration://offline?data=Ci4KCkhlbGxvId6tvu8SEWFsaWNlQGV4YW1wbGUuY29tGgdFeGFtcGxlIAEoATACCjYKFDEyMzQ1Njc4OTAxMjM0NTY3ODkwEgxIYXJkd2FyZTpib2IaCEhhcmR3YXJlIAEoATABOAAQARgBIAA%3D",
+    );
+    cmd.assert().success().stdout(predicate::str::contains("Imported 2 entries"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("phone/example-alice@example.com");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("JBSWY3DPEHPK3PXP"))
+        .stdout(predicate::str::contains("TOTP"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("phone/hardware-bob");
+    cmd.assert().success().stdout(predicate::str::contains("755224"));
+
+    // Invalid URI is rejected
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("new").arg("broken").arg("otpauth://totp/Broken?issuer=Broken");
+    cmd.assert().stdout(predicate::str::contains("does not contain a secret"));
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_otp_import_qr() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Import QR code image
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("new").arg("gitlab").arg("--qr").arg("tests/fixtures/otp_qr.png");
+    cmd.assert().success().stdout(predicate::str::contains("Created new entry"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("show").arg("gitlab");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("JBSWY3DPEHPK3PXP"))
+        .stdout(predicate::str::contains("SHA1, 8 digits, 45 seconds"))
+        .stdout(predicate::str::contains("GitLab"))
+        .stdout(predicate::str::contains("bob@example.com"));
+
+    // Image without QR code is rejected
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("new").arg("blank").arg("--qr").arg("tests/fixtures/otp_blank.png");
+    cmd.assert().stdout(predicate::str::contains("No QR code found"));
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_otp_export() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    let uri = "otpauth://totp/GitHub:alice%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=GitHub&algorithm=SHA256&digits=8&period=60";
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("new").arg("github").arg(uri);
+    cmd.assert().success();
+
+    // Exported URI round trips
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("export").arg("github");
+    cmd.assert().success().stdout(predicate::str::contains(uri));
+
+    // QR code rendered with half blocks
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("export").arg("github").arg("--qr");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("▀").or(predicate::str::contains("▄")))
+        .stdout(predicate::str::contains(uri));
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_otp_generate_remaining() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("new").arg("github").arg("otpauth://totp/GitHub?secret=JBSWY3DPEHPK3PXP");
+    cmd.assert().success();
+
+    // Remaining seconds and next code
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("github").arg("--min-remaining").arg("0");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Valid for \d+ more seconds, next code \d{6}").unwrap());
+
+    // Waits for a fresh window when the code expires too soon, using a 5 second
+    // period and starting with 3 seconds or fewer remaining
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp")
+        .arg("new")
+        .arg("short")
+        .arg("otpauth://totp/Short?secret=JBSWY3DPEHPK3PXP&period=5");
+    cmd.assert().success();
+
+    while SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() % 5 != 2 {
+        thread::sleep(Duration::from_millis(100));
+    }
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("short").arg("--min-remaining").arg("4");
+    cmd.assert().success().stdout(predicate::str::contains("waiting for next code"));
+
+    // Value must be less than the period
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("short").arg("--min-remaining").arg("5");
+    cmd.assert().stdout(predicate::str::contains("must be less than"));
+
+    // Invalid value is rejected
+    let mut cmd = ctx.cmd();
+    cmd.arg("otp").arg("github").arg("--min-remaining").arg("abc");
+    cmd.assert().stdout(predicate::str::contains("Invalid --min-remaining value"));
 
     ctx.close_db();
 }
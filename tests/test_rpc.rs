@@ -0,0 +1,621 @@
+// Integration tests for the RPC daemon
+mod common;
+
+use base64::{Engine as _, engine::general_purpose};
+use common::TestContext;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+
+/// Build raw JSON-RPC request, with optional bearer token
+fn request(method: &str, token: Option<&str>) -> String {
+    let body = format!(
+        r#"{{"jsonrpc":"2.0","id":1,"method":"{}","params":["web/github"]}}"#,
+        method
+    );
+    http_request(&body, token)
+}
+
+/// Build raw HTTP request of given body, with optional bearer token
+fn http_request(body: &str, token: Option<&str>) -> String {
+    let mut req = format!(
+        "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n",
+        body.len()
+    );
+    if let Some(token) = token {
+        req.push_str(&format!("Authorization: Bearer {}\r\n", token));
+    }
+    req.push_str("\r\n");
+    req.push_str(body);
+    req
+}
+
+/// Send request over stream, and read response
+fn exchange<S: Read + Write>(mut stream: S, req: &str) -> String {
+    stream.write_all(req.as_bytes()).unwrap();
+    let mut res = String::new();
+    stream.read_to_string(&mut res).ok();
+    res
+}
+
+/// Send request to daemon over Unix socket
+fn post_unix(ctx: &TestContext, method: &str, token: Option<&str>) -> String {
+    let stream = UnixStream::connect(&ctx.rpc_sock).expect("Unable to connect to daemon");
+    exchange(stream, &request(method, token))
+}
+
+/// Send JSON body to daemon over Unix socket, and decode JSON response
+fn call_unix(ctx: &TestContext, body: &str, token: &str) -> (String, Value) {
+    let stream = UnixStream::connect(&ctx.rpc_sock).expect("Unable to connect to daemon");
+    let res = exchange(stream, &http_request(body, Some(token))).replace("\r\n", "\n");
+    let (head, body) = res.split_once("\n\n").expect("Invalid HTTP response");
+    let body = body.trim();
+    let json = if body.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(body).unwrap()
+    };
+    (head.to_string(), json)
+}
+
+/// Send request to daemon over TCP
+fn post_tcp(ctx: &TestContext, method: &str, token: Option<&str>) -> String {
+    let stream = TcpStream::connect(("127.0.0.1", ctx.port)).expect("Unable to connect to daemon");
+    exchange(stream, &request(method, token))
+}
+
+/// Check file is readable by owner only
+fn assert_private(path: &str) {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600, "{} is not private", path);
+}
+
+/// Find /proc directory of daemon, by its forwarded arguments
+fn daemon_proc(ctx: &TestContext) -> PathBuf {
+    std::fs::read_dir("/proc")
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            let cmdline = std::fs::read(path.join("cmdline")).unwrap_or_default();
+            let args: Vec<&[u8]> = cmdline.split(|b| *b == 0).collect();
+            args.contains(&b"-d".as_slice()) && args.contains(&ctx.rpc_sock.as_bytes())
+        })
+        .expect("Daemon process not found")
+}
+
+/// Get soft and hard values of resource limit from /proc limits file
+fn proc_limit(limits_file: &Path, name: &str) -> (String, String) {
+    let limits = std::fs::read_to_string(limits_file).unwrap();
+    let line = limits.lines().find(|line| line.starts_with(name)).expect("Limit not found");
+    let mut values = line[name.len()..].split_whitespace();
+    (
+        values.next().unwrap().to_string(),
+        values.next().unwrap().to_string(),
+    )
+}
+
+#[test]
+fn test_rpc_token() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("set").arg("web/github").arg("gh-pass-1");
+    assert!(cmd.output().unwrap().status.success());
+
+    // Token file, readable by owner only
+    let token = std::fs::read_to_string(&ctx.token_file).expect("Token file not created");
+    assert_eq!(token.trim().len(), 64);
+    assert_private(&ctx.token_file);
+
+    // Missing token
+    let res = post_unix(&ctx, "db.stats", None);
+    assert!(
+        res.starts_with("HTTP/1.1 401"),
+        "Unexpected response:\n{}",
+        res
+    );
+
+    // Invalid token
+    let res = post_unix(&ctx, "str.get", Some(&"0".repeat(64)));
+    assert!(
+        res.starts_with("HTTP/1.1 401"),
+        "Unexpected response:\n{}",
+        res
+    );
+    assert!(!res.contains("gh-pass-1"));
+
+    // Valid token
+    let res = post_unix(&ctx, "str.get", Some(token.trim()));
+    assert!(
+        res.starts_with("HTTP/1.1 200"),
+        "Unexpected response:\n{}",
+        res
+    );
+    assert!(res.contains("gh-pass-1"));
+
+    // Body of request without token is never read, and oversized requests refused
+    let send_raw = |req: &str| {
+        let stream = UnixStream::connect(&ctx.rpc_sock).expect("Unable to connect to daemon");
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+        exchange(stream, req)
+    };
+    let res = send_raw("POST / HTTP/1.1\r\nContent-Length: 1000000000\r\n\r\n");
+    assert!(
+        res.starts_with("HTTP/1.1 401"),
+        "Unexpected response:\n{}",
+        res
+    );
+
+    let huge = format!(
+        "POST / HTTP/1.1\r\nAuthorization: Bearer {}\r\nContent-Length: 1000000000\r\n\r\n",
+        token.trim()
+    );
+    assert_eq!(send_raw(&huge), "");
+    assert_eq!(
+        send_raw(&format!(
+            "POST / HTTP/1.1\r\nX-Long: {}\r\n\r\n",
+            "a".repeat(16384)
+        )),
+        ""
+    );
+    let many: String = (0..100).map(|x| format!("X-Header-{}: 1\r\n", x)).collect();
+    assert_eq!(send_raw(&format!("POST / HTTP/1.1\r\n{}\r\n", many)), "");
+
+    // Token removed on close
+    ctx.close_db();
+    assert!(!Path::new(&ctx.token_file).exists());
+}
+
+#[test]
+fn test_rpc_unix_socket() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Socket readable by owner only, and no TCP port
+    assert_private(&ctx.rpc_sock);
+    assert!(TcpStream::connect(("127.0.0.1", ctx.port)).is_err());
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("stats");
+    assert!(cmd.output().unwrap().status.success());
+
+    // Socket removed on close
+    ctx.close_db();
+    assert!(!Path::new(&ctx.rpc_sock).exists());
+}
+
+#[test]
+fn test_rpc_tcp_transport() {
+    let mut ctx = TestContext::new();
+    ctx.rpc_transport = "tcp".to_string();
+    ctx.create_db();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("set").arg("web/github").arg("gh-pass-1");
+    assert!(cmd.output().unwrap().status.success());
+    assert!(!Path::new(&ctx.rpc_sock).exists());
+
+    let token = std::fs::read_to_string(&ctx.token_file).expect("Token file not created");
+    let res = post_tcp(&ctx, "str.get", None);
+    assert!(
+        res.starts_with("HTTP/1.1 401"),
+        "Unexpected response:\n{}",
+        res
+    );
+
+    let res = post_tcp(&ctx, "str.get", Some(token.trim()));
+    assert!(
+        res.starts_with("HTTP/1.1 200"),
+        "Unexpected response:\n{}",
+        res
+    );
+    assert!(res.contains("gh-pass-1"));
+}
+
+#[test]
+fn test_rpc_tls() {
+    let _ = std::fs::remove_dir_all("/tmp/nyx_test_tls");
+    let mut ctx = TestContext::new();
+    ctx.rpc_transport = "tcp".to_string();
+    ctx.tls = true;
+    ctx.create_db();
+
+    // Self-signed pair generated, key readable by owner only
+    assert!(Path::new("/tmp/nyx_test_tls/cert.pem").exists());
+    assert_private("/tmp/nyx_test_tls/key.pem");
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("set").arg("web/github").arg("gh-pass-1");
+    assert!(cmd.output().unwrap().status.success());
+
+    // Plain HTTP is not answered
+    let token = std::fs::read_to_string(&ctx.token_file).expect("Token file not created");
+    let res = post_tcp(&ctx, "str.get", Some(token.trim()));
+    assert!(!res.contains("gh-pass-1"), "Unexpected response:\n{}", res);
+
+    // Fingerprint
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("fingerprint");
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fingerprint = stdout
+        .split_whitespace()
+        .find(|word| word.len() == 95 && word.split(':').count() == 32)
+        .expect("Fingerprint not shown")
+        .to_string();
+
+    // Pinned fingerprint
+    let mut cmd = ctx.cmd();
+    cmd.arg("--tls-fingerprint").arg(&fingerprint);
+    cmd.arg("str").arg("get").arg("web/github");
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("gh-pass-1"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    // Mismatched fingerprint
+    let mut cmd = ctx.cmd();
+    cmd.arg("--tls-fingerprint").arg(["00"; 32].join(":"));
+    cmd.arg("str").arg("get").arg("web/github");
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        !stdout.contains("gh-pass-1"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("fingerprint"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    ctx.close_db();
+    let _ = std::fs::remove_dir_all("/tmp/nyx_test_tls");
+}
+
+#[test]
+fn test_rpc_jsonrpc() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+    let token = std::fs::read_to_string(&ctx.token_file).expect("Token file not created");
+    let token = token.trim();
+
+    // Structured item, and named params with string id
+    let set = json!({"jsonrpc": "2.0", "id": 1, "method": "str.set",
+        "params": ["web/github", {"display_name": "web/github", "value": "gh-pass-1"}]});
+    let (_, res) = call_unix(&ctx, &set.to_string(), token);
+    assert_eq!(res, json!({"jsonrpc": "2.0", "id": 1, "result": true}));
+
+    let get = json!({"jsonrpc": "2.0", "id": "abc", "method": "str.get",
+        "params": {"name": "web/github"}});
+    let (_, res) = call_unix(&ctx, &get.to_string(), token);
+    assert_eq!(res["id"], "abc");
+    assert_eq!(res["result"]["value"], "gh-pass-1");
+    assert!(res.get("error").is_none());
+
+    // Standard error codes
+    let (_, res) = call_unix(&ctx, "{not json", token);
+    assert_eq!(res["error"]["code"], -32700);
+
+    let (_, res) = call_unix(
+        &ctx,
+        r#"{"id":1,"method":"str.get","params":["web/github"]}"#,
+        token,
+    );
+    assert_eq!(res["error"]["code"], -32600);
+
+    let (_, res) = call_unix(
+        &ctx,
+        r#"{"jsonrpc":"2.0","id":2,"method":"str.nope"}"#,
+        token,
+    );
+    assert_eq!(res["error"]["code"], -32601);
+    assert_eq!(res["id"], 2);
+
+    let bad = r#"{"jsonrpc":"2.0","id":3,"method":"str.get","params":{"title":"web/github"}}"#;
+    let (_, res) = call_unix(&ctx, bad, token);
+    assert_eq!(res["error"]["code"], -32602);
+
+    let missing = r#"{"jsonrpc":"2.0","id":4,"method":"str.get","params":["web/none"]}"#;
+    let (_, res) = call_unix(&ctx, missing, token);
+    assert_eq!(res["error"]["code"], -32000);
+
+    // Batch, with notification left out of responses
+    let batch = json!([
+        {"jsonrpc": "2.0", "id": 5, "method": "str.exists", "params": ["web/github"]},
+        {"jsonrpc": "2.0", "method": "str.set",
+            "params": ["web/gitlab", {"display_name": "web/gitlab", "value": "gl-pass-1"}]},
+        {"jsonrpc": "2.0", "id": 6, "method": "str.nope"},
+    ]);
+    let (_, res) = call_unix(&ctx, &batch.to_string(), token);
+    let responses = res.as_array().expect("Batch response is not an array");
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["result"], true);
+    assert_eq!(responses[1]["error"]["code"], -32601);
+
+    let (_, res) = call_unix(&ctx, "[]", token);
+    assert_eq!(res["error"]["code"], -32600);
+
+    // Notification only, no content
+    let notify = r#"{"jsonrpc":"2.0","method":"str.exists","params":["web/gitlab"]}"#;
+    let (head, res) = call_unix(&ctx, notify, token);
+    assert!(
+        head.starts_with("HTTP/1.1 204"),
+        "Unexpected response:\n{}",
+        head
+    );
+    assert_eq!(res, Value::Null);
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("get").arg("web/gitlab");
+    let output = cmd.output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("gl-pass-1"));
+
+    // Method without a name parameter, database remains usable afterwards
+    let generate = r#"{"jsonrpc":"2.0","id":7,"method":"ssh.generate"}"#;
+    let (_, res) = call_unix(&ctx, generate, token);
+    assert_eq!(res["result"], true);
+
+    let set = json!({"jsonrpc": "2.0", "id": 8, "method": "str.set",
+        "params": ["web/bitbucket", {"display_name": "web/bitbucket", "value": "bb-pass-1"}]});
+    let (_, res) = call_unix(&ctx, &set.to_string(), token);
+    assert_eq!(res, json!({"jsonrpc": "2.0", "id": 8, "result": true}));
+
+    // OTP parameters validated by daemon
+    let otp = |id: u32, digits: u32, period: u64| {
+        json!({"jsonrpc": "2.0", "id": id, "method": "otp.new", "params": ["totp/bank",
+            {"display_name": "totp/bank", "secret_code": "JBSWY3DPEHPK3PXP",
+                "digits": digits, "period": period}]})
+        .to_string()
+    };
+    let (_, res) = call_unix(&ctx, &otp(9, 25, 30), token);
+    assert_eq!(res["error"]["code"], -32000, "Unexpected response: {}", res);
+    let (_, res) = call_unix(&ctx, &otp(10, 6, 0), token);
+    assert_eq!(res["error"]["code"], -32000, "Unexpected response: {}", res);
+    let (_, res) = call_unix(&ctx, &otp(11, 8, 60), token);
+    assert_eq!(res["result"], true);
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_rpc_discover() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+    let token = std::fs::read_to_string(&ctx.token_file).expect("Token file not created");
+    let token = token.trim();
+
+    let discover = r#"{"jsonrpc":"2.0","id":1,"method":"rpc.discover"}"#;
+    let (_, res) = call_unix(&ctx, discover, token);
+    let doc = &res["result"];
+    assert!(doc["openrpc"].is_string());
+
+    // Every method listed, with params and result
+    let methods = doc["methods"].as_array().expect("No methods listed");
+    let names: Vec<&str> = methods.iter().filter_map(|m| m["name"].as_str()).collect();
+    let expected = [
+        "rpc.discover",
+        "db.stats",
+        "db.history",
+        "user.new",
+        "otp.generate",
+    ];
+    for name in expected {
+        assert!(names.contains(&name), "Method {} not listed", name);
+    }
+
+    let user_new = methods.iter().find(|m| m["name"] == "user.new").unwrap();
+    assert_eq!(user_new["params"][0]["name"], "name");
+    assert_eq!(user_new["params"][1]["name"], "item");
+    assert_eq!(user_new["params"][1]["required"], true);
+    assert_eq!(
+        user_new["params"][1]["schema"]["$ref"],
+        "#/components/schemas/User"
+    );
+
+    let history = methods.iter().find(|m| m["name"] == "db.history").unwrap();
+    let items = &history["result"]["schema"]["items"];
+    assert_eq!(items["$ref"], "#/components/schemas/HistoryItem");
+
+    // Result types described
+    let schemas = &doc["components"]["schemas"];
+    for name in [
+        "User",
+        "Oauth",
+        "SshKey",
+        "StrItem",
+        "Note",
+        "HistoryItem",
+        "DbStats",
+    ] {
+        assert!(schemas[name].is_object(), "Schema {} missing", name);
+    }
+    assert!(schemas["User"]["properties"]["password"].is_object());
+
+    // Required parameters checked
+    let (_, res) = call_unix(
+        &ctx,
+        r#"{"jsonrpc":"2.0","id":2,"method":"db.history"}"#,
+        token,
+    );
+    assert_eq!(res["error"]["code"], -32602);
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_rpc_events() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Subscribing requires token
+    let stream = UnixStream::connect(&ctx.rpc_sock).expect("Unable to connect to daemon");
+    let res = exchange(stream, "GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    assert!(
+        res.starts_with("HTTP/1.1 401"),
+        "Unexpected response:\n{}",
+        res
+    );
+
+    // Subscribe, until daemon exits
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("events").arg("--json").stdout(std::process::Stdio::piped());
+    let child = cmd.spawn().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("set").arg("web/github").arg("gh-pass-1");
+    assert!(cmd.output().unwrap().status.success());
+
+    let token = std::fs::read_to_string(&ctx.token_file).expect("Token file not created");
+    let body = r#"{"jsonrpc":"2.0","id":1,"method":"str.delete","params":["web/github"]}"#;
+    let (_, json) = call_unix(&ctx, body, token.trim());
+    assert_eq!(json["result"], true);
+
+    ctx.close_db();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let events: Vec<Value> =
+        stdout.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+
+    let names: Vec<&str> = events.iter().map(|e| e["event"].as_str().unwrap()).collect();
+    assert_eq!(
+        names,
+        vec!["create", "delete", "lock"],
+        "Unexpected events:\n{}",
+        stdout
+    );
+    assert_eq!(events[0]["db"], "default");
+    assert_eq!(events[0]["data_type"], "str");
+    assert_eq!(events[0]["name"], "web/github");
+    assert_eq!(events[2]["reason"], "closed");
+
+    // Never any secret values
+    assert!(!stdout.contains("gh-pass-1"));
+}
+
+#[test]
+fn test_rpc_launch_no_environ() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    let pid = daemon_proc(&ctx);
+
+    // Key and database file are not in the environment.  The daemon disables dumping, which
+    // leaves its environ file readable by root only, so the check needs to run as root.
+    if unsafe { libc::geteuid() } == 0 {
+        let key = general_purpose::STANDARD.encode(Sha256::digest(ctx.password.as_bytes()));
+        let environ = std::fs::read(pid.join("environ")).unwrap();
+        let environ = String::from_utf8_lossy(&environ);
+        assert!(
+            !environ.contains("NYX_LAUNCH"),
+            "Launch parameters found in environment"
+        );
+        assert!(!environ.contains(&key), "Database key found in environment");
+    } else {
+        eprintln!("Not running as root, skipping check of daemon environment");
+    }
+
+    // Pipe the key was read from is closed
+    let stdin = std::fs::read_link(pid.join("fd/0")).unwrap();
+    assert_eq!(stdin, Path::new("/dev/null"));
+
+    // Daemon unlocked database with the key
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("set").arg("web/github").arg("gh-pass-1");
+    assert!(cmd.output().unwrap().status.success());
+}
+
+#[test]
+fn test_rpc_daemon_hardened() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+    let pid = daemon_proc(&ctx);
+
+    // No core dumps
+    let (soft, hard) = proc_limit(&pid.join("limits"), "Max core file size");
+    assert_eq!((soft.as_str(), hard.as_str()), ("0", "0"));
+
+    // Memory of unlocked database locked, within the default memlock limit
+    let status = std::fs::read_to_string(pid.join("status")).unwrap();
+    let locked = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmLck:"))
+        .and_then(|kb| kb.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        .unwrap_or(0);
+    let (_, memlock) = proc_limit(&pid.join("limits"), "Max locked memory");
+    if memlock != "unlimited" {
+        assert!(
+            locked * 1024 <= memlock.parse::<u64>().unwrap(),
+            "Locked over limit:\n{}",
+            status
+        );
+    }
+    assert!(locked > 0, "Daemon memory not locked:\n{}", status);
+
+    // Still serving requests
+    let mut cmd = ctx.cmd();
+    cmd.arg("str").arg("set").arg("web/github").arg("gh-pass-1");
+    assert!(cmd.output().unwrap().status.success());
+}
+
+#[test]
+fn test_rpc_versions() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+    let token = std::fs::read_to_string(&ctx.token_file).expect("Token file not created");
+    let token = token.trim();
+
+    let call = |method: &str, params: Value| {
+        let body = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        call_unix(&ctx, &body.to_string(), token).1
+    };
+
+    // Strings keep prior values when edited
+    let item = |value: &str| json!({"display_name": "api/key", "value": value});
+    call("str.set", json!(["api/key", item("v1")]));
+    call("str.edit", json!(["api/key", item("v2")]));
+    call("str.edit", json!(["api/key", item("v3")]));
+
+    let res = call("str.history", json!({"name": "api/key"}));
+    let versions = res["result"].as_array().expect("No versions returned");
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["version"], 1);
+    assert_eq!(versions[0]["item"]["value"], "v2");
+    assert_eq!(versions[1]["item"]["value"], "v1");
+
+    let res = call("str.revert", json!({"name": "api/key", "version": 2}));
+    assert_eq!(res["result"], true);
+    let res = call("str.get", json!(["api/key"]));
+    assert_eq!(res["result"]["value"], "v1");
+    let res = call("str.history", json!(["api/key"]));
+    assert_eq!(res["result"][0]["item"]["value"], "v3");
+
+    // Deleted entries discard their versions
+    call("str.delete", json!(["api/key"]));
+    call("str.set", json!(["api/key", item("new")]));
+    let res = call("str.history", json!(["api/key"]));
+    assert_eq!(res["result"], json!([]));
+
+    // Notes, with entry required
+    let note = |contents: &str| json!({"display_name": "readme", "note": contents});
+    call("note.new", json!(["readme", note("first")]));
+    call("note.edit", json!(["readme", note("second")]));
+    let res = call("note.history", json!(["readme"]));
+    assert_eq!(res["result"][0]["item"]["note"], "first");
+
+    let res = call("note.history", json!(["missing"]));
+    assert_eq!(res["error"]["code"], -32000);
+
+    ctx.close_db();
+}
@@ -2,8 +2,8 @@
 mod common;
 
 use assert_cmd::assert::OutputAssertExt;
-use predicates::prelude::*;
 use common::TestContext;
+use predicates::prelude::*;
 use std::fs;
 
 #[test]
@@ -20,10 +20,10 @@ fn test_ssh_generate() {
     use std::io::Write;
     if let Some(mut stdin) = child.stdin.take() {
         writeln!(stdin, "server1.example.com").ok(); // host
-        writeln!(stdin, "22").ok();                   // port
-        writeln!(stdin, "ubuntu").ok();               // username
-        writeln!(stdin, "").ok();                     // password (optional)
-        writeln!(stdin, "Test SSH key").ok();         // notes
+        writeln!(stdin, "22").ok(); // port
+        writeln!(stdin, "ubuntu").ok(); // username
+        writeln!(stdin, "").ok(); // password (optional)
+        writeln!(stdin, "Test SSH key").ok(); // notes
     }
 
     let status = child.wait().expect("Failed to wait");
@@ -340,9 +340,7 @@ fn test_ssh_find() {
     let mut cmd = ctx.cmd();
     cmd.arg("ssh").arg("find").arg("aws");
 
-    cmd.assert()
-        .success()
-        .stdout(predicate::str::contains("aws_server"));
+    cmd.assert().success().stdout(predicate::str::contains("aws_server"));
 
     ctx.close_db();
 }
@@ -372,16 +370,222 @@ fn test_ssh_categories() {
     let mut cmd = ctx.cmd();
     cmd.arg("ssh").arg("ls").arg("production");
 
+    cmd.assert().success().stdout(predicate::str::contains("webserver"));
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_ssh_generate_key_types() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    for (name, key_type, prefix) in [
+        ("edkey", "ed25519", "ssh-ed25519"),
+        ("p256key", "ecdsa-p256", "ecdsa-sha2-nistp256"),
+        ("p384key", "ecdsa-p384", "ecdsa-sha2-nistp384"),
+        ("rsakey", "rsa", "ssh-rsa"),
+    ] {
+        let mut cmd = ctx.cmd();
+        cmd.arg("ssh").arg("gen").arg(name).arg("--type").arg(key_type);
+        if key_type == "rsa" {
+            cmd.arg("--bits").arg("2048");
+        }
+        cmd.arg("--comment").arg("deploy@example");
+        cmd.stdin(std::process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("Failed to spawn");
+        use std::io::Write;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "host.com").ok();
+            writeln!(stdin, "22").ok();
+            writeln!(stdin, "user").ok();
+            writeln!(stdin, "").ok();
+            writeln!(stdin, "").ok();
+        }
+        child.wait().ok();
+
+        // Public key has expected algorithm and comment
+        let mut cmd = ctx.cmd();
+        cmd.arg("ssh").arg("xb").arg(name);
+
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(prefix))
+            .stdout(predicate::str::contains("deploy@example"));
+    }
+
+    // Invalid type is rejected
+    let mut cmd = ctx.cmd();
+    cmd.arg("ssh").arg("gen").arg("badkey").arg("--type").arg("dsa");
+    cmd.assert().stdout(predicate::str::contains("Created new entry").not());
+
+    // Bits only accepted for RSA keys
+    let mut cmd = ctx.cmd();
+    cmd.arg("ssh").arg("gen").arg("badkey").arg("--type").arg("ed25519").arg("--bits").arg("4096");
     cmd.assert()
-        .success()
-        .stdout(predicate::str::contains("webserver"));
+        .stdout(predicate::str::contains("only applies to RSA keys"))
+        .stdout(predicate::str::contains("Created new entry").not());
 
     ctx.close_db();
 }
 
+#[test]
+fn test_ssh_import_export_encrypted() {
+    use ssh_key::{Algorithm, LineEnding, PrivateKey};
+
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Create passphrase protected key
+    let privkey = PrivateKey::random(&mut rand::rngs::OsRng, Algorithm::Ed25519).unwrap();
+    let encrypted = privkey.encrypt(&mut rand::rngs::OsRng, "import-pass").unwrap();
+    let import_file = "/tmp/nyx_test_import_key";
+    fs::write(
+        import_file,
+        encrypted.to_openssh(LineEnding::LF).unwrap().as_bytes(),
+    )
+    .unwrap();
+
+    // Import
+    let mut cmd = ctx.cmd();
+    cmd.arg("ssh").arg("import").arg("enckey").arg("--file").arg(import_file);
+    cmd.stdin(std::process::Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn");
+    use std::io::Write;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "import-pass").ok(); // key passphrase
+        writeln!(stdin, "host.com").ok();
+        writeln!(stdin, "22").ok();
+        writeln!(stdin, "user").ok();
+        writeln!(stdin, "").ok();
+        writeln!(stdin, "").ok();
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Created new entry"));
+
+    // Export with new passphrase
+    let export_file = "/tmp/nyx_test_export_key";
+    let _ = fs::remove_file(export_file);
+    let mut cmd = ctx.cmd();
+    cmd.arg("ssh").arg("export").arg("enckey").arg("--file").arg(export_file);
+    cmd.stdin(std::process::Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn");
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "export-pass").ok();
+        writeln!(stdin, "export-pass").ok();
+    }
+    child.wait().ok();
+
+    // Exported key is encrypted with the new passphrase
+    let exported = PrivateKey::from_openssh(fs::read_to_string(export_file).unwrap()).unwrap();
+    assert!(exported.is_encrypted());
+    assert!(exported.decrypt("import-pass").is_err());
+    let decrypted = exported.decrypt("export-pass").unwrap();
+    assert_eq!(
+        decrypted.public_key().key_data(),
+        privkey.public_key().key_data()
+    );
+
+    let _ = fs::remove_file(import_file);
+    let _ = fs::remove_file(export_file);
+    ctx.close_db();
+}
+
 // Note: FUSE filesystem tests (xb, xh, xp, xu, xv commands for copying keys)
 // are difficult to test in integration tests as they require:
 // 1. FUSE support on the system
 // 2. Proper permissions
 // 3. Mount point availability
 // These are better tested manually or in a specialized test environment
+
+/// Send a single message to the SSH agent, and return the response body
+fn agent_request(sock: &str, message: &[u8]) -> Vec<u8> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(sock).expect("Failed to connect to SSH agent");
+    let mut payload = (message.len() as u32).to_be_bytes().to_vec();
+    payload.extend_from_slice(message);
+    stream.write_all(&payload).unwrap();
+
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes).unwrap();
+    let mut res = vec![0u8; u32::from_be_bytes(len_bytes) as usize];
+    stream.read_exact(&mut res).unwrap();
+    res
+}
+
+/// Read SSH wire format string at offset, returns value and next offset
+fn agent_string(buf: &[u8], offset: usize) -> (Vec<u8>, usize) {
+    let len = u32::from_be_bytes(buf[offset..offset + 4].try_into().unwrap()) as usize;
+    (buf[offset + 4..offset + 4 + len].to_vec(), offset + 4 + len)
+}
+
+#[test]
+fn test_ssh_agent() {
+    use rsa::signature::Verifier;
+    use ssh_key::{PublicKey, Signature};
+
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Generate an SSH key
+    let mut cmd = ctx.cmd();
+    cmd.arg("ssh").arg("gen").arg("agentkey");
+    cmd.stdin(std::process::Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn");
+    use std::io::Write;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "agent.example.com").ok();
+        writeln!(stdin, "22").ok();
+        writeln!(stdin, "user").ok();
+        writeln!(stdin, "").ok();
+        writeln!(stdin, "").ok();
+    }
+    child.wait().ok();
+
+    // List identities
+    let res = agent_request(&ctx.agent_sock, &[11]);
+    assert_eq!(res[0], 12, "Expected identities answer");
+    assert_eq!(u32::from_be_bytes(res[1..5].try_into().unwrap()), 1);
+    let (key_blob, offset) = agent_string(&res, 5);
+    let (comment, _) = agent_string(&res, offset);
+    assert_eq!(comment, b"agentkey");
+
+    // Sign request
+    let data = b"nyx agent test data";
+    let mut message = vec![13];
+    message.extend((key_blob.len() as u32).to_be_bytes());
+    message.extend(&key_blob);
+    message.extend((data.len() as u32).to_be_bytes());
+    message.extend(data);
+    message.extend(4u32.to_be_bytes());
+
+    let res = agent_request(&ctx.agent_sock, &message);
+    assert_eq!(res[0], 14, "Expected sign response");
+
+    // Verify signature
+    let (sig_blob, _) = agent_string(&res, 1);
+    let (algorithm, offset) = agent_string(&sig_blob, 0);
+    let (sig_data, _) = agent_string(&sig_blob, offset);
+    let signature = Signature::new(
+        String::from_utf8(algorithm).unwrap().parse().unwrap(),
+        sig_data,
+    )
+    .unwrap();
+    let public_key = PublicKey::from_bytes(&key_blob).unwrap();
+    assert!(public_key.key_data().verify(data, &signature).is_ok());
+
+    // Unknown key fails
+    let res = agent_request(
+        &ctx.agent_sock,
+        &[13, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    );
+    assert_eq!(res, vec![5]);
+
+    ctx.close_db();
+}
@@ -2,8 +2,9 @@
 mod common;
 
 use assert_cmd::assert::OutputAssertExt;
-use predicates::prelude::*;
 use common::TestContext;
+use predicates::prelude::*;
+use std::os::unix::fs::PermissionsExt;
 
 #[test]
 fn test_user_new() {
@@ -136,9 +137,7 @@ fn test_user_edit() {
     let mut cmd = ctx.cmd();
     cmd.arg("show").arg("edituser");
 
-    cmd.assert()
-        .success()
-        .stdout(predicate::str::contains("updated_username"));
+    cmd.assert().success().stdout(predicate::str::contains("updated_username"));
 
     ctx.close_db();
 }
@@ -341,9 +340,478 @@ fn test_user_categories() {
     let mut cmd = ctx.cmd();
     cmd.arg("ls").arg("work");
 
+    cmd.assert().success().stdout(predicate::str::contains("gitlab"));
+
+    ctx.close_db();
+}
+
+/// Run command answering prompts with lines
+fn run_with_input(ctx: &TestContext, args: &[&str], lines: &[&str]) {
+    let mut cmd = ctx.cmd();
+    cmd.args(args);
+    cmd.stdin(std::process::Stdio::piped());
+
+    let mut child = cmd.spawn().expect("Failed to spawn");
+    use std::io::Write;
+    if let Some(mut stdin) = child.stdin.take() {
+        for line in lines {
+            writeln!(stdin, "{}", line).ok();
+        }
+    }
+    child.wait().ok();
+}
+
+#[test]
+fn test_user_history() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    // Create, then edit twice
+    run_with_input(&ctx, &["new", "histuser"], &["alice", "first_pass", "", ""]);
+    run_with_input(
+        &ctx,
+        &["edit", "histuser"],
+        &["alice", "second_pass", "", ""],
+    );
+    run_with_input(
+        &ctx,
+        &["edit", "histuser"],
+        &["alice2", "second_pass", "https://example.com", ""],
+    );
+
+    // Newest first, without passwords
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("history").arg("histuser");
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("username, url"),
+        "Missing changes:\n{}",
+        stdout
+    );
+    assert!(stdout.contains("password"), "Missing changes:\n{}", stdout);
+    assert!(
+        !stdout.contains("first_pass"),
+        "Password displayed:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.find("username, url").unwrap() < stdout.find("| password").unwrap(),
+        "Versions not newest first:\n{}",
+        stdout
+    );
+
+    // Revert to original
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("revert").arg("histuser").arg("2");
+    cmd.assert().success().stdout(predicate::str::contains("Reverted histuser to version 2"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("histuser");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("first_pass"))
+        .stdout(predicate::str::contains("alice"));
+
+    // Replaced details kept, and follow entry when renamed
+    let mut cmd = ctx.cmd();
+    cmd.arg("mv").arg("histuser").arg("renamed");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("revert").arg("renamed").arg("1");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("renamed");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("second_pass"))
+        .stdout(predicate::str::contains("alice2"));
+
+    // Out of range
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("revert").arg("renamed").arg("9");
+    let output = cmd.output().unwrap();
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        combined.contains("No version 9"),
+        "Unexpected output:\n{}",
+        combined
+    );
+
+    ctx.close_db();
+}
+
+#[test]
+fn test_user_history_retention() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("retention");
+    cmd.assert().success().stdout(predicate::str::contains("Keeping 10 prior versions"));
+
+    // Keep only the latest version
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("retention").arg("1");
+    cmd.assert().success();
+
+    run_with_input(&ctx, &["new", "keepuser"], &["bob", "pass_one", "", ""]);
+    run_with_input(&ctx, &["edit", "keepuser"], &["bob", "pass_two", "", ""]);
+    run_with_input(&ctx, &["edit", "keepuser"], &["bob", "pass_three", "", ""]);
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("revert").arg("keepuser").arg("2");
+    let output = cmd.output().unwrap();
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        combined.contains("it has 1 prior"),
+        "Unexpected output:\n{}",
+        combined
+    );
+
+    // Retention count persisted within database
+    ctx.close_db();
+    run_with_input(&ctx, &["db", "open"], &[&ctx.password]);
+    assert!(common::wait_for_daemon(&ctx, 50), "Daemon did not start");
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("db").arg("retention");
+    cmd.assert().success().stdout(predicate::str::contains("Keeping 1 prior versions"));
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("user").arg("revert").arg("keepuser").arg("1");
+    cmd.assert().success();
+
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("keepuser");
+    cmd.assert().success().stdout(predicate::str::contains("pass_two"));
+
+    ctx.close_db();
+}
+
+/// Combined stdout and stderr of command
+fn output_of(ctx: &TestContext, args: &[&str]) -> String {
+    let mut cmd = ctx.cmd();
+    cmd.args(args);
+    let output = cmd.output().unwrap();
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[test]
+fn test_user_custom_fields() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+    let dir = format!("{}.fields", ctx.dbfile);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    run_with_input(&ctx, &["new", "mybank"], &["alice", "bank_pass", "", ""]);
+    output_of(
+        &ctx,
+        &["edit", "mybank", "--field", "Account Number=12345678"],
+    );
+    output_of(&ctx, &["edit", "mybank", "--secret", "PIN=4321"]);
+
+    // Attach file
+    let codes = format!("{}/codes.txt", dir);
+    std::fs::write(&codes, b"code-1 code-2").unwrap();
+    let stdout = output_of(&ctx, &["edit", "mybank", "--attach", &codes]);
+    assert!(
+        stdout.contains("Updated user info for mybank"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    // Concealed fields masked
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("mybank");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Account Number:"))
+        .stdout(predicate::str::contains("12345678"))
+        .stdout(predicate::str::contains("PIN:"))
+        .stdout(predicate::str::contains("4321").not())
+        .stdout(predicate::str::contains("codes.txt (13 bytes)"));
+
+    // Only plain fields searchable
+    let stdout = output_of(&ctx, &["find", "12345678"]);
+    assert!(
+        stdout.contains("mybank"),
+        "Plain field not found:\n{}",
+        stdout
+    );
+    let stdout = output_of(&ctx, &["find", "4321"]);
+    assert!(
+        !stdout.contains("mybank"),
+        "Concealed field found:\n{}",
+        stdout
+    );
+
+    // Save fields and attachments to file
+    let out = format!("{}/out.txt", dir);
+    output_of(&ctx, &["x", "mybank", "pin", "--file", &out]);
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "4321");
+    let mode = std::fs::metadata(&out).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600, "Saved field is not private");
+    output_of(&ctx, &["x", "mybank", "Codes.txt", "--file", &out]);
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "code-1 code-2");
+    output_of(&ctx, &["x", "mybank", "username", "--file", &out]);
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "alice");
+
+    // Saving to file is logged as a read, not a copy to clipboard
+    let stdout = output_of(
+        &ctx,
+        &["db", "history", "--type", "user", "--action", "clipboard"],
+    );
+    assert!(
+        !stdout.contains("mybank"),
+        "Saving to file logged as copy:\n{}",
+        stdout
+    );
+
+    let stdout = output_of(&ctx, &["x", "mybank", "codes.txt"]);
+    assert!(
+        stdout.contains("save it with --file"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+    let stdout = output_of(&ctx, &["x", "mybank", "missing"]);
+    assert!(
+        stdout.contains("No field or attachment named missing"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    // Invalid names and sizes
+    let stdout = output_of(&ctx, &["edit", "mybank", "--field", "password=other"]);
+    assert!(
+        stdout.contains("already has it"),
+        "Builtin field accepted:\n{}",
+        stdout
+    );
+
+    let large = format!("{}/large.bin", dir);
+    std::fs::write(&large, vec![0u8; 1024 * 1024 + 1]).unwrap();
+    let stdout = output_of(&ctx, &["edit", "mybank", "--attach", &large]);
+    assert!(
+        stdout.contains("limit"),
+        "Large attachment accepted:\n{}",
+        stdout
+    );
+
+    // Remove
+    output_of(&ctx, &["edit", "mybank", "--remove", "PIN"]);
+    output_of(&ctx, &["edit", "mybank", "--remove", "codes.txt"]);
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("mybank");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Account Number:"))
+        .stdout(predicate::str::contains("PIN:").not())
+        .stdout(predicate::str::contains("Attachments:").not());
+
+    ctx.close_db();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_user_history_attachments() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+    let dir = format!("{}.attachments", ctx.dbfile);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Attachment which does not compress
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    let data: Vec<u8> = (0..1_000_000)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        })
+        .collect();
+    let large = format!("{}/large.bin", dir);
+    std::fs::write(&large, &data).unwrap();
+
+    run_with_input(&ctx, &["new", "mybank"], &["alice", "bank_pass", "", ""]);
+    output_of(&ctx, &["edit", "mybank", "--attach", &large]);
+    for x in 1..=5 {
+        output_of(
+            &ctx,
+            &["edit", "mybank", "--field", &format!("Edit {}={}", x, x)],
+        );
+    }
+
+    // Unchanged attachment is not copied into each version
+    let size = std::fs::metadata(&ctx.dbfile).unwrap().len();
+    assert!(size < 3 * 1024 * 1024, "Database grew to {} bytes", size);
+
+    // Versions from before attachment was removed still restore it
+    output_of(&ctx, &["edit", "mybank", "--remove", "large.bin"]);
+    output_of(&ctx, &["edit", "mybank", "--field", "Edit 6=6"]);
+    let stdout = output_of(&ctx, &["user", "revert", "mybank", "4"]);
+    assert!(
+        stdout.contains("Reverted mybank to version 4"),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    let out = format!("{}/out.bin", dir);
+    output_of(&ctx, &["x", "mybank", "large.bin", "--file", &out]);
+    assert!(
+        std::fs::read(&out).unwrap() == data,
+        "Restored attachment differs"
+    );
+
+    ctx.close_db();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_user_tags() {
+    let ctx = TestContext::new();
+    ctx.create_db();
+
+    for name in &["prod/web1", "prod/web2", "other"] {
+        run_with_input(&ctx, &["new", name], &["user", "pass", "", ""]);
+    }
+    let stdout = output_of(&ctx, &["tag", "prod/web1", "Prod", "web"]);
+    assert!(stdout.contains("#prod #web"), "Tags not added:\n{}", stdout);
+    output_of(&ctx, &["tag", "other", "prod"]);
+
+    // List and find by tag
+    let stdout = output_of(&ctx, &["ls", "--tag", "prod"]);
+    assert!(
+        stdout.contains("prod/web1"),
+        "Tagged entry not listed:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("other"),
+        "Tagged entry not listed:\n{}",
+        stdout
+    );
+    assert!(
+        !stdout.contains("prod/web2"),
+        "Untagged entry listed:\n{}",
+        stdout
+    );
+
+    let stdout = output_of(&ctx, &["find", "--tag", "web"]);
+    assert!(
+        stdout.contains("prod/web1"),
+        "Tagged entry not found:\n{}",
+        stdout
+    );
+    assert!(
+        !stdout.contains("other"),
+        "Untagged entry found:\n{}",
+        stdout
+    );
+
+    // Favorites listed first
+    output_of(&ctx, &["fav", "other"]);
+    let stdout = output_of(&ctx, &["ls"]);
+    assert!(
+        stdout.find("other").unwrap() < stdout.find("prod/").unwrap(),
+        "Favorite not listed first:\n{}",
+        stdout
+    );
+    let mut cmd = ctx.cmd();
+    cmd.arg("show").arg("other");
     cmd.assert()
         .success()
-        .stdout(predicate::str::contains("gitlab"));
+        .stdout(predicate::str::contains("#prod"))
+        .stdout(predicate::str::contains("Favorite:"));
+
+    // Kept when copied and renamed
+    output_of(&ctx, &["cp", "prod/web1", "prod/web3"]);
+    output_of(&ctx, &["mv", "other", "renamed"]);
+    let stdout = output_of(&ctx, &["tag", "renamed"]);
+    assert!(
+        stdout.contains("#prod"),
+        "Tags not kept on rename:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Yes"),
+        "Favorite not kept on rename:\n{}",
+        stdout
+    );
+
+    let stdout = output_of(&ctx, &["ls", "prod", "--tag", "web"]);
+    assert!(
+        stdout.contains("prod/web3"),
+        "Tags not kept on copy:\n{}",
+        stdout
+    );
+
+    // Other data types
+    output_of(&ctx, &["set", "api-key", "sk_123"]);
+    output_of(&ctx, &["tag", "api-key", "prod", "--type", "str"]);
+    let stdout = output_of(&ctx, &["str", "ls", "--tag", "prod"]);
+    assert!(
+        stdout.contains("api-key"),
+        "Tagged string not listed:\n{}",
+        stdout
+    );
+
+    // Counts in stats
+    let stdout = output_of(&ctx, &["stats"]);
+    assert!(
+        stdout.contains("#prod (4)"),
+        "Tag counts missing:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("#web (2)"),
+        "Tag counts missing:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.lines().any(|line| line.contains("Favorites:") && line.contains("1 entries")),
+        "Favorites count missing:\n{}",
+        stdout
+    );
+
+    // Remove, and deleted entries no longer counted
+    output_of(&ctx, &["tag", "rm", "prod/web3", "web"]);
+    output_of(&ctx, &["unfav", "renamed"]);
+    output_of(&ctx, &["rm", "prod/web1"]);
+    let stdout = output_of(&ctx, &["stats"]);
+    assert!(
+        stdout.contains("#prod (3)"),
+        "Tag counts not updated:\n{}",
+        stdout
+    );
+    assert!(
+        !stdout.contains("#web"),
+        "Removed tag still counted:\n{}",
+        stdout
+    );
+
+    let stdout = output_of(&ctx, &["tag", "renamed", "x", "--type", "bogus"]);
+    assert!(
+        stdout.contains("Invalid type"),
+        "Unexpected output:\n{}",
+        stdout
+    );
 
     ctx.close_db();
 }